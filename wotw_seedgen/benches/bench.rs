@@ -158,7 +158,7 @@ fn reach_checking(c: &mut Criterion) {
         b.iter(|| {
             let world_settings = WorldSettings::default();
             let mut world = World::new_spawn(&graph, &world_settings);
-            world.player.inventory = Pool::preset().inventory;
+            world.player.inventory = Pool::standard().inventory;
             world.player.inventory.grant(Item::SpiritLight(1), 10000);
             let spawn = world.graph.find_spawn("MarshSpawn.Main").unwrap();
             world
@@ -183,7 +183,12 @@ fn doors(c: &mut Criterion) {
 
             let mut rng: StdRng = Seeder::from(&"Test").make_rng();
 
-            let _ = generator::doors::generate_door_headers(&mut world, &mut rng);
+            let _ = generator::doors::generate_door_headers(
+                &graph,
+                &world_settings,
+                &mut world,
+                &mut rng,
+            );
         })
     });
 }
@@ -0,0 +1,282 @@
+//! Error types returned while generating a seed
+//!
+//! Every error can be serialized, which allows frontends to react to specific failures instead of only displaying a message
+
+use std::error::Error;
+use std::fmt::{self, Display};
+
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::languages::ParseErrorCollection;
+use crate::settings::ScoreRange;
+
+/// Any error that may occur during seed generation
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", content = "details", rename_all = "camelCase")]
+pub enum SeedgenError {
+    /// The provided settings are invalid
+    Settings(SettingsError),
+    /// A header could not be read, parsed or applied
+    Header(HeaderError),
+    /// The logic files could not be parsed
+    Logic(LogicError),
+    /// Door randomization failed
+    Doors(String),
+    /// The item pool could not be read or is invalid
+    ItemPool(String),
+    /// No valid placements could be found
+    Placement(PlacementError),
+    /// No generated seed had a score within the [`ScoreRange`]
+    Score(ScoreError),
+    /// Hints could not be generated
+    Hints(String),
+    /// A [`GenerationObserver`](crate::generator::GenerationObserver) cancelled the generation
+    Cancelled,
+}
+impl Display for SeedgenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SeedgenError::Settings(err) => write!(f, "{err}"),
+            SeedgenError::Header(err) => write!(f, "{err}"),
+            SeedgenError::Logic(err) => write!(f, "{err}"),
+            SeedgenError::Doors(err) => write!(f, "Failed to randomize doors: {err}"),
+            SeedgenError::ItemPool(err) => write!(f, "Failed to build the item pool: {err}"),
+            SeedgenError::Placement(err) => write!(f, "{err}"),
+            SeedgenError::Score(err) => write!(f, "{err}"),
+            SeedgenError::Hints(err) => write!(f, "Failed to generate hints: {err}"),
+            SeedgenError::Cancelled => write!(f, "Generation was cancelled"),
+        }
+    }
+}
+impl Error for SeedgenError {}
+impl SeedgenError {
+    /// Serialize into json format
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+}
+impl From<SettingsError> for SeedgenError {
+    fn from(err: SettingsError) -> SeedgenError {
+        SeedgenError::Settings(err)
+    }
+}
+impl From<HeaderError> for SeedgenError {
+    fn from(err: HeaderError) -> SeedgenError {
+        SeedgenError::Header(err)
+    }
+}
+impl From<LogicError> for SeedgenError {
+    fn from(err: LogicError) -> SeedgenError {
+        SeedgenError::Logic(err)
+    }
+}
+impl From<PlacementError> for SeedgenError {
+    fn from(err: PlacementError) -> SeedgenError {
+        SeedgenError::Placement(err)
+    }
+}
+impl From<ScoreError> for SeedgenError {
+    fn from(err: ScoreError) -> SeedgenError {
+        SeedgenError::Score(err)
+    }
+}
+
+/// The settings contradict themselves or reference things that don't exist
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", content = "details", rename_all = "camelCase")]
+pub enum SettingsError {
+    /// The difficulty doesn't allow any of the spawn locations
+    NoSpawnLocations,
+    /// The requested spawn doesn't exist in the logic
+    SpawnNotFound(String),
+    /// The requested spawn exists, but can't be spawned on
+    InvalidSpawn(String),
+    /// A name was used both for a file header and an inline header
+    AmbiguousHeaderName(String),
+    /// A header parameter was given multiple different values
+    ConflictingHeaderConfig {
+        header: String,
+        parameter: String,
+        first: String,
+        second: String,
+    },
+    /// A placement constraint can never be fulfilled
+    UnsatisfiablePlacementConstraint(String),
+    /// The plando placements can't be used
+    InvalidPlando(String),
+    /// The placement weights can't be used to choose items
+    InvalidPlacementWeights(String),
+    /// The shop pricing can't be used to choose prices
+    InvalidShopPricing(String),
+}
+impl Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsError::NoSpawnLocations => write!(f, "No valid spawn locations available"),
+            SettingsError::SpawnNotFound(spawn) => write!(f, "Spawn {spawn} not found"),
+            SettingsError::InvalidSpawn(spawn) => write!(f, "{spawn} is not a valid spawn"),
+            SettingsError::AmbiguousHeaderName(name) => write!(
+                f,
+                "Ambiguous name: {name} used both as a file header and an inline header"
+            ),
+            SettingsError::ConflictingHeaderConfig {
+                header,
+                parameter,
+                first,
+                second,
+            } => write!(f, "provided multiple values for configuration parameter {parameter} for header {header} ({first} and {second})"),
+            SettingsError::UnsatisfiablePlacementConstraint(constraint) => {
+                write!(f, "The placement constraint \"{constraint}\" can never be fulfilled")
+            }
+            SettingsError::InvalidPlando(message) => write!(f, "Invalid plando: {message}"),
+            SettingsError::InvalidPlacementWeights(message) => {
+                write!(f, "Invalid placement weights: {message}")
+            }
+            SettingsError::InvalidShopPricing(message) => {
+                write!(f, "Invalid shop pricing: {message}")
+            }
+        }
+    }
+}
+impl Error for SettingsError {}
+
+/// Failure while processing the headers of a world
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", content = "details", rename_all = "camelCase")]
+pub enum HeaderError {
+    /// The header could not be read from the file access
+    Read { header: String, message: String },
+    /// The header syntax is invalid
+    ///
+    /// Contains the individual [`ParseError`](crate::languages::ParseError)s, including their source spans
+    Parse {
+        header: String,
+        errors: ParseErrorCollection,
+    },
+    /// The header could not be built with the provided parameters
+    Build { header: String, message: String },
+    /// Two active headers exclude each other
+    Incompatible { header: String, other: String },
+    /// More than one header customized the same item
+    DuplicateCustomization { item: String },
+}
+impl Display for HeaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HeaderError::Read { header, message } => {
+                write!(f, "Failed to read header {header}: {message}")
+            }
+            HeaderError::Parse { header, errors } => {
+                write!(f, "Error in header {header}:\n{}", errors.verbose_display())
+            }
+            HeaderError::Build { header, message } => {
+                write!(f, "Error in header {header}: {message}")
+            }
+            HeaderError::Incompatible { header, other } => {
+                write!(f, "headers {other} and {header} are incompatible")
+            }
+            HeaderError::DuplicateCustomization { item } => {
+                write!(f, "multiple headers tried to customize the item {item}")
+            }
+        }
+    }
+}
+impl Error for HeaderError {}
+
+/// Failure while parsing or building the logic
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", content = "details", rename_all = "camelCase")]
+pub enum LogicError {
+    /// The areas file syntax is invalid
+    Areas(ParseErrorCollection),
+    /// The location data could not be parsed
+    Locations(String),
+    /// The state data could not be parsed
+    States(String),
+    /// The parsed files could not be combined into a logic graph
+    Build(String),
+}
+impl Display for LogicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogicError::Areas(errors) => write!(f, "{}", errors.verbose_display()),
+            LogicError::Locations(err) | LogicError::States(err) | LogicError::Build(err) => {
+                write!(f, "{err}")
+            }
+        }
+    }
+}
+impl Error for LogicError {}
+
+/// Placement failed on every attempt
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlacementError {
+    /// Report of each attempt, in order
+    pub attempts: Vec<PlacementAttempt>,
+}
+impl Display for PlacementError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "All {} attempts to generate a seed failed :(",
+            self.attempts.len()
+        )?;
+        for (index, attempt) in self.attempts.iter().enumerate() {
+            write!(f, "\nAttempt {}: {attempt}", index + 1)?;
+        }
+        Ok(())
+    }
+}
+impl Error for PlacementError {}
+
+/// A failed attempt to place items
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlacementAttempt {
+    /// Identifiers of the spawn chosen for each world
+    pub spawns: Vec<String>,
+    /// Why the attempt failed
+    pub reason: String,
+}
+impl Display for PlacementAttempt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Spawning on {} failed: {}",
+            self.spawns.join(", "),
+            self.reason
+        )
+    }
+}
+
+/// Every generated seed was rejected because its score was outside the [`ScoreRange`]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScoreError {
+    /// The required range
+    pub range: ScoreRange,
+    /// Total score of each world, for every rejected seed in order
+    pub scores: Vec<Vec<u32>>,
+}
+impl Display for ScoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "None of the {} generated seeds had a score within {}",
+            self.scores.len(),
+            self.range
+        )?;
+        for (index, scores) in self.scores.iter().enumerate() {
+            write!(
+                f,
+                "\nSeed {}: scored {}",
+                index + 1,
+                scores.iter().format(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+impl Error for ScoreError {}
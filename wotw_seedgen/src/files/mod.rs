@@ -10,6 +10,8 @@ pub trait FileAccess {
     fn read_world_preset(&self, identifier: &str) -> Result<String, String>;
     /// Read a [`Header`](crate::header::Header) with the given identifier, returning its contents
     fn read_header(&self, identifier: &str) -> Result<String, String>;
+    /// Read an [`ItemPool`](crate::settings::ItemPool) with the given identifier, returning its contents
+    fn read_item_pool(&self, identifier: &str) -> Result<String, String>;
 }
 
 /// A [`FileAccess`] implementation prohibiting access to any files
@@ -26,6 +28,9 @@ impl FileAccess for NoFileAccess {
     fn read_header(&self, _: &str) -> Result<String, String> {
         Err("no file access".into())
     }
+    fn read_item_pool(&self, _: &str) -> Result<String, String> {
+        Err("no file access".into())
+    }
 }
 /// Instance of [`NoFileAccess`]
 pub const NO_FILE_ACCESS: NoFileAccess = NoFileAccess;
@@ -44,6 +49,7 @@ mod fs_access {
     const UNIVERSE_PRESET_FOLDER: &str = "universe_presets";
     const WORLD_PRESET_FOLDER: &str = "world_presets";
     const HEADER_FOLDER: &str = "headers";
+    const ITEM_POOL_FOLDER: &str = "item_pools";
 
     /// A [`FileAccess`] implementation searching for identifiers in the local filesystem
     ///
@@ -53,6 +59,7 @@ mod fs_access {
     /// - "universe_presets" for [`UniversePreset`](crate::preset::UniversePreset)s
     /// - "world_presets" for [`WorldPreset`](crate::preset::WorldPreset)s
     /// - "headers" for [`Header`](crate::header::Header)s
+    /// - "item_pools" for [`ItemPool`](crate::settings::ItemPool)s
     ///
     /// If unable to perform the operation in the subfolder, it will be attempted in the current directory instead
     pub struct FileSystemAccess;
@@ -66,6 +73,9 @@ mod fs_access {
         fn read_header(&self, identifier: &str) -> Result<String, String> {
             read_file(identifier, "wotwrh", HEADER_FOLDER)
         }
+        fn read_item_pool(&self, identifier: &str) -> Result<String, String> {
+            read_file(identifier, "json", ITEM_POOL_FOLDER)
+        }
     }
     impl FileSystemAccess {
         pub fn write_universe_preset(
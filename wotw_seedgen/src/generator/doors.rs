@@ -1,31 +1,50 @@
 use std::collections::HashMap;
-use arrayvec::ArrayVec;
-use crate::item::{UberStateItem, UberStateValue};
+use crate::item::{Item, UberStateItem, UberStateValue};
 use crate::uber_state::{UberIdentifier, UberStateTrigger, UberType};
+use crate::util::constants::DEFAULT_SPAWN;
 use crate::World;
 use indexmap::{IndexSet};
 use itertools::Itertools;
 use rand::prelude::IteratorRandom;
 use rand::prelude::StdRng;
 use rand::seq::SliceRandom;
-use rustc_hash::{FxHashMap};
-use crate::settings::WorldSettings;
+use rustc_hash::{FxHashMap, FxHashSet};
+use crate::settings::{Spawn, WorldSettings};
 use crate::world::Graph;
+use crate::world::graph::Node;
+
+use super::SpoilerDoor;
 
 pub type DoorId = u8;
-type DoorGroups = [ArrayVec<DoorId, 15>; 16];
 
-struct DoorRandomizerConfig {
+/// Door information from the logic file that the door randomizer operates on
+#[derive(Debug, Default, Clone)]
+pub struct DoorData {
+    /// Doors which can reach each other without going through another door, ordered by their lowest door id
+    pub groups: Vec<Vec<DoorId>>,
+    /// Doors that a door may never be connected to
+    pub forbidden_targets: FxHashMap<DoorId, Vec<DoorId>>,
+    /// Identifier of the anchor each door is located at
+    pub anchors: FxHashMap<DoorId, String>,
+}
+
+/// Maximum amount of doors in a loop of connections, 2 means every door leads back to where it came from
+const MAX_LOOP_SIZE: u8 = 2;
+/// How many door layouts may be generated before giving up on keeping every location reachable
+const DOOR_LAYOUT_ATTEMPTS: u16 = 100;
+
+struct DoorRandomizerConfig<'a> {
     max_loop_size: u8,
-    door_groups: DoorGroups,
+    door_groups: &'a [Vec<DoorId>],
+    forbidden_targets: &'a FxHashMap<DoorId, Vec<DoorId>>,
     group_index_by_door_id: FxHashMap<DoorId, usize>,
 }
 
-impl DoorRandomizerConfig {
-    pub fn new(max_loop_size: u8, door_groups: DoorGroups) -> Self {
+impl<'a> DoorRandomizerConfig<'a> {
+    pub fn new(max_loop_size: u8, door_data: &'a DoorData) -> Self {
         let mut group_index_by_door_id: FxHashMap<DoorId, usize> = FxHashMap::default();
 
-        for (group_index, door_ids) in door_groups.iter().enumerate() {
+        for (group_index, door_ids) in door_data.groups.iter().enumerate() {
             for door_id in door_ids {
                 group_index_by_door_id.insert(*door_id, group_index.to_owned());
             }
@@ -33,7 +52,8 @@ impl DoorRandomizerConfig {
 
         let config = Self {
             max_loop_size,
-            door_groups,
+            door_groups: &door_data.groups,
+            forbidden_targets: &door_data.forbidden_targets,
             group_index_by_door_id,
         };
 
@@ -54,7 +74,7 @@ struct DoorRandomizerState {
 }
 
 fn generate_door_connections(config: &DoorRandomizerConfig, rng: &mut StdRng) -> Result<DoorRandomizerState, String> {
-    let initial_door = *config.door_groups.iter().flatten().choose(rng).unwrap();
+    let initial_door = *config.door_groups.iter().flatten().choose(rng).ok_or_else(|| "The logic contains no doors".to_string())?;
     let initial_door_group = config.group_index_by_door_id[&initial_door];
 
     let initial_state = DoorRandomizerState {
@@ -115,16 +135,10 @@ fn generate_door_connections_recursively(state: &DoorRandomizerState, config: &D
     #[cfg(feature = "log")]
     log::trace!("{log_indent}Possible doors: {}", possible_target_doors.iter().map(|d| d.to_string()).join(", "));
 
-    // TODO: Remove these special cases when there is a mechanism for
-    //       defining required pickups.
-    // Prevent second floor to connecting to third floor in wellspring
-    if door_id == 21 {  // 21 == Wellspring third floor entrance
-        possible_target_doors.shift_remove(&20);  // 20 == Wellspring second floor exit
-    }
-
-    // Prevent the Moki Father hut connecting to the Teddy hut
-    if door_id == 9 {  // 9 == Moki Father hut entrance
-        possible_target_doors.shift_remove(&26);  // 26 == Teddy hut exit
+    if let Some(forbidden_targets) = config.forbidden_targets.get(&door_id) {
+        for forbidden_target in forbidden_targets {
+            possible_target_doors.shift_remove(forbidden_target);
+        }
     }
 
     if possible_target_doors.is_empty() {
@@ -206,44 +220,96 @@ fn generate_door_connections_recursively(state: &DoorRandomizerState, config: &D
     Err("Found no possible solution".to_string())
 }
 
-pub fn generate_door_headers(graph: &Graph, world_settings: &WorldSettings, world: &mut World, rng: &mut StdRng) -> Result<String, String> {
+/// Returns the indices of every node reachable from the default spawn with the entire item pool, using the given door connections
+fn reachable_with_doors(graph: &Graph, world: &World, spawn: &Node, connections: &HashMap<DoorId, DoorId>) -> FxHashSet<usize> {
+    let mut player = world.player.clone();
+    player.inventory.merge(world.pool.inventory.clone());
+    player.inventory.grant(Item::SpiritLight(1), world.pool.spirit_light);
+
+    let mut states = world.uber_states().clone();
+    for (door_id, target_door_id) in connections {
+        states.insert(UberIdentifier::new(27, (*door_id).into()), *target_door_id as f32);
+        states.insert(UberIdentifier::new(28, (*door_id).into()), 1.);
+    }
+
+    let (reached, _progressions) = graph.reached_and_progressions(&player, spawn, &states, &world.sets);
+
+    #[cfg(feature = "log")]
+    log::trace!("{} unmet progressions remain with this door layout", _progressions.len());
+
+    reached.into_iter().map(Node::index).collect()
+}
+
+/// Returns the spawns the world might use, to check reachability from
+///
+/// For fully random spawns only the default spawn is checked, since checking every anchor would take too long
+fn possible_spawns<'a>(graph: &'a Graph, world_settings: &WorldSettings) -> Result<Vec<&'a Node>, String> {
+    let spawns = match &world_settings.spawn {
+        // An invalid spawn will be reported during placement
+        Spawn::Set(spawn) => vec![graph.find_spawn(spawn).or_else(|_| graph.find_spawn(DEFAULT_SPAWN))?],
+        Spawn::Random => {
+            let spawns = world_settings.difficulty.spawn_locations();
+            graph.nodes.iter().filter(|&node| spawns.contains(&node.identifier())).collect()
+        }
+        Spawn::FullyRandom => vec![graph.find_spawn(DEFAULT_SPAWN)?],
+    };
+    Ok(spawns)
+}
+
+/// Generates door connections until every location that would be reachable with the vanilla connections remains reachable from every possible spawn
+///
+/// Connectivity between the door groups alone doesn't guarantee this since entering a door may have logical requirements
+fn generate_reachable_door_connections(graph: &Graph, world_settings: &WorldSettings, world: &World, config: &DoorRandomizerConfig, rng: &mut StdRng) -> Result<HashMap<DoorId, DoorId>, String> {
+    let spawns = possible_spawns(graph, world_settings)?;
+    let vanilla_reachable = spawns
+        .iter()
+        .map(|spawn| reachable_with_doors(graph, world, spawn, &graph.default_door_connections))
+        .collect_vec();
+
+    for _ in 0..DOOR_LAYOUT_ATTEMPTS {
+        let connections = generate_door_connections(config, rng)?.connections;
+
+        let unreachable = spawns.iter().zip(&vanilla_reachable).find_map(|(spawn, vanilla_reachable)| {
+            let reachable = reachable_with_doors(graph, world, spawn, &connections);
+            let unreachable = vanilla_reachable.difference(&reachable).count();
+            (unreachable > 0).then(|| (spawn.identifier(), unreachable))
+        });
+
+        match unreachable {
+            None => return Ok(connections),
+            Some((_spawn, _unreachable)) => {
+                #[cfg(feature = "log")]
+                log::trace!("Door layout leaves {_unreachable} locations unreachable from {_spawn}, trying another one");
+            }
+        }
+    }
+
+    Err(format!("Failed to find a door layout that keeps every location reachable after {DOOR_LAYOUT_ATTEMPTS} attempts"))
+}
+
+/// Connects the doors of the world and returns the header lines to set up the connections in the seed
+///
+/// If doors are randomized, the connections for the spoiler are returned as well
+pub fn generate_door_headers(graph: &Graph, world_settings: &WorldSettings, world: &mut World, rng: &mut StdRng) -> Result<(String, Vec<SpoilerDoor>), String> {
     let mut header_lines: Vec<String> = vec![];
-    const LOOP_SIZE: u8 = 2;
 
+    let randomized_connections;
     let connections = if world_settings.randomize_doors {
         #[cfg(feature = "log")]
         log::trace!("Randomizing door connections");
 
-        let door_groups: DoorGroups = [
-            ArrayVec::from([1, 3, 5, 7, 9, 11, 13, 15, 17, 19, 21, 23, 25, 27, 29]),
-            ArrayVec::from_iter([2]),
-            ArrayVec::from_iter([4]),
-            ArrayVec::from_iter([6]),
-            ArrayVec::from_iter([8]),
-            ArrayVec::from_iter([10]),
-            ArrayVec::from_iter([12]),
-            ArrayVec::from_iter([14]),
-            ArrayVec::from_iter([16, 18]),
-            ArrayVec::from_iter([20]),
-            ArrayVec::from_iter([22]),
-            ArrayVec::from_iter([24]),
-            ArrayVec::from_iter([26]),
-            ArrayVec::from_iter([28]),
-            ArrayVec::from_iter([30, 31]),
-            ArrayVec::from_iter([32]),
-        ];
-
         header_lines.push("3|0|8|7|200|bool|true".to_string());
 
-        let config = DoorRandomizerConfig::new(LOOP_SIZE, door_groups);
-        &generate_door_connections(&config, rng)?.connections
+        let config = DoorRandomizerConfig::new(MAX_LOOP_SIZE, &graph.door_data);
+        randomized_connections = generate_reachable_door_connections(graph, world_settings, world, &config, rng)?;
+        &randomized_connections
     } else {
         #[cfg(feature = "log")]
         log::trace!("Using default door connections");
         &graph.default_door_connections
     };
 
-    for (door_id, target_door_id) in connections {
+    for (door_id, target_door_id) in connections.iter().sorted_unstable() {
         #[cfg(feature = "log")]
         log::trace!("Connected door {} → {}", door_id, target_door_id);
 
@@ -274,7 +340,7 @@ pub fn generate_door_headers(graph: &Graph, world_settings: &WorldSettings, worl
 
             // If the target door is connecting back to this door, mark
             // the target door as visited too once we went through this door
-            if LOOP_SIZE == 2 && connections[target_door_id] == *door_id {
+            if connections[target_door_id] == *door_id {
                 header_lines.push(format!("28|{}|8|28|{}|bool|true", door_id, target_door_id));
             }
         }
@@ -283,5 +349,95 @@ pub fn generate_door_headers(graph: &Graph, world_settings: &WorldSettings, worl
     #[cfg(feature = "log")]
     log::trace!("Doors generated");
 
-    Ok(header_lines.join("\n"))
+    let spoiler_doors = if world_settings.randomize_doors {
+        connections
+            .iter()
+            .sorted_unstable()
+            .map(|(door_id, target_door_id)| SpoilerDoor {
+                door_id: *door_id,
+                anchor: graph.door_data.anchors[door_id].clone(),
+                target_door_id: *target_door_id,
+                target_anchor: graph.door_data.anchors[target_door_id].clone(),
+            })
+            .collect()
+    } else {
+        vec![]
+    };
+
+    Ok((header_lines.join("\n"), spoiler_doors))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::SeedableRng;
+
+    use crate::{files::FILE_SYSTEM_ACCESS, generate_seed, settings::UniverseSettings, tests::test_graph, world::Pool};
+
+    #[test]
+    fn door_spoiler() {
+        let mut universe_settings = UniverseSettings {
+            seed: "door_spoiler".to_string(),
+            ..UniverseSettings::default()
+        };
+        universe_settings.world_settings[0].randomize_doors = true;
+        let graph = test_graph(&universe_settings);
+
+        let seed = generate_seed(&graph, &FILE_SYSTEM_ACCESS, &universe_settings).unwrap();
+        let doors = &seed.spoiler.doors[0];
+
+        // Every door is listed once, in order
+        let door_ids = doors.iter().map(|door| door.door_id).collect_vec();
+        assert_eq!(door_ids, graph.default_door_connections.keys().copied().sorted().collect_vec());
+
+        let spoiler = seed.spoiler.to_string();
+        assert!(spoiler.contains("Doors:"));
+        for door in doors {
+            assert_eq!(door.anchor, graph.door_data.anchors[&door.door_id]);
+            assert_eq!(door.target_anchor, graph.door_data.anchors[&door.target_door_id]);
+            let forbidden = graph.door_data.forbidden_targets.get(&door.door_id);
+            assert!(!forbidden.is_some_and(|forbidden| forbidden.contains(&door.target_door_id)));
+
+            // Doors lead back to where they came from
+            let target = &doors[door_ids.binary_search(&door.target_door_id).unwrap()];
+            assert_eq!(target.target_door_id, door.door_id);
+
+            let line = format!("-> {}", door.target_anchor);
+            assert!(spoiler.lines().any(|spoiler_line| spoiler_line.trim_start().starts_with(&door.anchor) && spoiler_line.ends_with(&line)));
+        }
+        assert!(doors.iter().any(|door| graph.default_door_connections[&door.door_id] != door.target_door_id));
+    }
+
+    #[test]
+    fn vanilla_doors() {
+        let universe_settings = UniverseSettings {
+            seed: "vanilla_doors".to_string(),
+            ..UniverseSettings::default()
+        };
+        let graph = test_graph(&universe_settings);
+
+        let seed = generate_seed(&graph, &FILE_SYSTEM_ACCESS, &universe_settings).unwrap();
+        assert!(seed.spoiler.doors.is_empty());
+        assert!(!seed.spoiler.to_string().contains("Doors:"));
+    }
+
+    #[test]
+    fn reachable_door_layout() {
+        let universe_settings = UniverseSettings::default();
+        let world_settings = &universe_settings.world_settings[0];
+        let graph = test_graph(&universe_settings);
+        let mut world = World::new_spawn(&graph, world_settings);
+        world.pool = Pool::standard();
+        let config = DoorRandomizerConfig::new(MAX_LOOP_SIZE, &graph.door_data);
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let connections = generate_reachable_door_connections(&graph, world_settings, &world, &config, &mut rng).unwrap();
+
+        let spawn = graph.find_spawn(DEFAULT_SPAWN).unwrap();
+        let vanilla = reachable_with_doors(&graph, &world, spawn, &graph.default_door_connections);
+        let reachable = reachable_with_doors(&graph, &world, spawn, &connections);
+        assert!(vanilla.is_subset(&reachable));
+        assert_ne!(connections, graph.default_door_connections);
+    }
 }
@@ -0,0 +1,333 @@
+use itertools::Itertools;
+use rand::{seq::SliceRandom, Rng};
+use rustc_hash::FxHashSet;
+
+use crate::{
+    item::{Item, Message},
+    log,
+    settings::HintSettings,
+    uber_state::UberStateTrigger,
+    util::Zone,
+};
+
+use super::{Placement, Seed, SeedSpoiler, SpoilerPlacement};
+
+/// Places hint messages on the hint slots declared by headers
+///
+/// The hints are based on the [`SeedSpoiler`] and the items required to beat the seed, as determined by the [`Playthrough`](super::Playthrough)
+pub(super) fn generate_hints(
+    seed: &mut Seed,
+    hint_slots: &[Vec<UberStateTrigger>],
+    rng: &mut impl Rng,
+) -> Result<(), String> {
+    if hint_slots.iter().all(Vec::is_empty) {
+        return Ok(());
+    }
+
+    let playthrough = seed.playthrough()?;
+    let required = playthrough
+        .spheres
+        .iter()
+        .flat_map(|sphere| &sphere.placements)
+        .collect::<Vec<_>>();
+
+    for (world_index, slots) in hint_slots.iter().enumerate() {
+        if slots.is_empty() {
+            continue;
+        }
+
+        let settings = &seed.worlds[world_index].world_settings.hints;
+        let mut hints = world_hints(world_index, settings, &seed.spoiler, &required, rng);
+        hints.shuffle(rng);
+
+        log::trace!(
+            "Placing {} hints in world {world_index}",
+            hints.len().min(slots.len())
+        );
+
+        let placements = slots.iter().zip(hints).map(|(slot, hint)| Placement {
+            node: None,
+            trigger: slot.clone(),
+            item: Item::Message(Message::new(hint)),
+            hide_message: false,
+        });
+        seed.worlds[world_index].placements.extend(placements);
+    }
+
+    Ok(())
+}
+
+/// A [`Zone`] in a specific world
+type WorldZone = (usize, Zone);
+
+fn hint_zone(placement: &SpoilerPlacement) -> Option<WorldZone> {
+    placement
+        .location
+        .zone
+        .filter(|zone| !matches!(zone, Zone::Spawn | Zone::Void))
+        .map(|zone| (placement.origin_world_index, zone))
+}
+
+fn zone_name(world_index: usize, (origin_world_index, zone): WorldZone) -> String {
+    if origin_world_index == world_index {
+        zone.to_string()
+    } else {
+        format!("$[15|5|{origin_world_index}]'s {zone}")
+    }
+}
+
+/// Generates the hint texts for one world
+fn world_hints(
+    world_index: usize,
+    settings: &HintSettings,
+    spoiler: &SeedSpoiler,
+    required: &[&SpoilerPlacement],
+    rng: &mut impl Rng,
+) -> Vec<String> {
+    let own_required = required
+        .iter()
+        .filter(|placement| placement.target_world_index == world_index)
+        .copied()
+        .collect::<Vec<_>>();
+
+    let mut way_of_the_hero = own_required
+        .iter()
+        .filter_map(|placement| hint_zone(placement))
+        .sorted_unstable()
+        .dedup()
+        .collect::<Vec<_>>();
+    way_of_the_hero.shuffle(rng);
+    way_of_the_hero.truncate(settings.way_of_the_hero);
+
+    // A zone is only barren if it holds nothing that any world requires
+    let required_zones = required
+        .iter()
+        .filter_map(|placement| hint_zone(placement))
+        .collect::<FxHashSet<_>>();
+    let mut barren = spoiler
+        .groups
+        .iter()
+        .flat_map(|group| &group.placements)
+        .filter(|placement| placement.origin_world_index == world_index)
+        .filter_map(hint_zone)
+        .filter(|zone| !required_zones.contains(zone))
+        .sorted_unstable()
+        .dedup()
+        .collect::<Vec<_>>();
+    barren.shuffle(rng);
+    barren.truncate(settings.barren);
+
+    // Prefer items the way of the hero hints don't already point towards
+    let mut items = own_required
+        .into_iter()
+        .filter_map(|placement| hint_zone(placement).map(|zone| (placement, zone)))
+        .collect::<Vec<_>>();
+    items.shuffle(rng);
+    items.sort_by_key(|(_, zone)| way_of_the_hero.contains(zone));
+    let items = items
+        .into_iter()
+        .unique_by(|(placement, _)| &placement.item_name)
+        .take(settings.item_in_zone);
+
+    let way_of_the_hero = way_of_the_hero
+        .iter()
+        .map(|&zone| format!("{} is on the Way of the Hero", zone_name(world_index, zone)));
+    let barren = barren
+        .iter()
+        .map(|&zone| format!("{} is barren", zone_name(world_index, zone)));
+    let items = items.map(|(placement, zone)| {
+        format!(
+            "{} is in {}",
+            placement.item_name,
+            zone_name(world_index, zone)
+        )
+    });
+
+    way_of_the_hero.chain(barren).chain(items).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::{
+        files::FILE_SYSTEM_ACCESS,
+        generate_seed,
+        generator::{NodeSummary, SpoilerGroup},
+        item::Skill,
+        settings::UniverseSettings,
+        tests::test_graph,
+        Inventory,
+    };
+
+    fn placement(
+        origin_world_index: usize,
+        target_world_index: usize,
+        zone: Zone,
+        item: Item,
+    ) -> SpoilerPlacement {
+        SpoilerPlacement {
+            origin_world_index,
+            target_world_index,
+            shared: false,
+            location: NodeSummary {
+                identifier: format!("{zone}.Location"),
+                position: None,
+                zone: Some(zone),
+            },
+            item_name: item.to_string(),
+            item,
+        }
+    }
+    fn spoiler(placements: Vec<SpoilerPlacement>) -> SeedSpoiler {
+        SeedSpoiler {
+            spawns: vec!["MarshSpawn.Main".to_string(); 2],
+            groups: vec![SpoilerGroup {
+                reachable: vec![],
+                forced_items: Inventory::default(),
+                placements,
+            }],
+            doors: vec![],
+            sharing: Default::default(),
+        }
+    }
+    fn hints(
+        settings: HintSettings,
+        spoiler: &SeedSpoiler,
+        required: &[&SpoilerPlacement],
+    ) -> Vec<String> {
+        let mut rng = StdRng::seed_from_u64(0);
+        world_hints(0, &settings, spoiler, required, &mut rng)
+            .into_iter()
+            .sorted()
+            .collect()
+    }
+
+    #[test]
+    fn hint_kinds() {
+        let launch = placement(0, 0, Zone::Marsh, Item::Skill(Skill::Launch));
+        let bash = placement(0, 0, Zone::Hollow, Item::Skill(Skill::Bash));
+        let spoiler = spoiler(vec![
+            launch.clone(),
+            bash.clone(),
+            placement(0, 0, Zone::Glades, Item::SpiritLight(100)),
+            placement(0, 0, Zone::Wellspring, Item::SpiritLight(50)),
+            placement(0, 0, Zone::Spawn, Item::SpiritLight(50)),
+        ]);
+        let required = [&launch, &bash];
+
+        let all = HintSettings {
+            way_of_the_hero: 5,
+            barren: 5,
+            item_in_zone: 5,
+        };
+        assert_eq!(
+            hints(all, &spoiler, &required),
+            [
+                "Bash is in Hollow",
+                "Glades is barren",
+                "Hollow is on the Way of the Hero",
+                "Launch is in Marsh",
+                "Marsh is on the Way of the Hero",
+                "Wellspring is barren",
+            ]
+        );
+
+        let none = HintSettings {
+            way_of_the_hero: 0,
+            barren: 0,
+            item_in_zone: 0,
+        };
+        assert!(hints(none, &spoiler, &required).is_empty());
+
+        // The item hint points to the zone the way of the hero hint doesn't mention
+        let one_each = HintSettings {
+            way_of_the_hero: 1,
+            barren: 1,
+            item_in_zone: 1,
+        };
+        let hints = hints(one_each, &spoiler, &required);
+        assert_eq!(hints.len(), 3);
+        let hero_zone = hints
+            .iter()
+            .find_map(|hint| hint.strip_suffix(" is on the Way of the Hero"))
+            .unwrap();
+        let item_zone = hints
+            .iter()
+            .find_map(|hint| hint.split_once(" is in ").map(|(_, zone)| zone))
+            .unwrap();
+        assert_ne!(hero_zone, item_zone);
+    }
+
+    #[test]
+    fn multiworld_hints() {
+        // World 1 requires an item from world 0's Glades, and world 0 requires an item from world 1's Marsh
+        let own = placement(1, 0, Zone::Marsh, Item::Skill(Skill::Launch));
+        let other = placement(0, 1, Zone::Glades, Item::Skill(Skill::Bash));
+        let spoiler = spoiler(vec![
+            own.clone(),
+            other.clone(),
+            placement(0, 0, Zone::Hollow, Item::SpiritLight(100)),
+        ]);
+        let settings = HintSettings {
+            way_of_the_hero: 5,
+            barren: 5,
+            item_in_zone: 5,
+        };
+
+        assert_eq!(
+            hints(settings, &spoiler, &[&own, &other]),
+            [
+                "$[15|5|1]'s Marsh is on the Way of the Hero",
+                "Hollow is barren",
+                "Launch is in $[15|5|1]'s Marsh",
+            ]
+        );
+    }
+
+    #[test]
+    fn hint_slots() {
+        let mut universe_settings = UniverseSettings {
+            seed: "hints".to_string(),
+            ..UniverseSettings::default()
+        };
+        universe_settings.world_settings[0]
+            .headers
+            .insert("spoiler_hints".to_string());
+        universe_settings.world_settings[0].hints = HintSettings {
+            way_of_the_hero: 2,
+            barren: 1,
+            item_in_zone: 1,
+        };
+        let graph = test_graph(&universe_settings);
+
+        let seed = generate_seed(&graph, &FILE_SYSTEM_ACCESS, &universe_settings).unwrap();
+        let hints = seed.worlds[0]
+            .placements
+            .iter()
+            .filter(|placement| placement.node.is_none())
+            .filter_map(|placement| match &placement.item {
+                Item::Message(message) if message.message.contains(" is ") => {
+                    Some((placement.trigger.code().to_string(), &message.message))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        // The header declares 11 slots, the hints fill the first ones
+        let slots = ["48248|18767", "48248|3638", "15|19396", "48248|1590"];
+        assert_eq!(
+            hints
+                .iter()
+                .map(|(slot, _)| slot.as_str())
+                .collect::<Vec<_>>(),
+            slots
+        );
+        let count = |kind: &str| hints.iter().filter(|(_, hint)| hint.contains(kind)).count();
+        assert_eq!(count(" is on the Way of the Hero"), 2);
+        assert_eq!(count(" is barren"), 1);
+        assert_eq!(count(" is in "), 1);
+    }
+}
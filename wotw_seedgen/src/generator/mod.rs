@@ -1,18 +1,30 @@
+mod hints;
+mod observer;
+mod options;
 mod placement;
+mod playthrough;
+mod plando;
+mod score;
 mod seed;
 mod spoiler;
 pub mod doors;
 
+pub use observer::{GenerationEvent, GenerationObserver, ObserverAction};
+pub use options::GeneratorOptions;
 pub use placement::*;
+pub use playthrough::*;
+pub use score::*;
 pub use seed::*;
 pub use spoiler::*;
 
 use std::{cmp::Ordering, fmt::Write};
 
+use itertools::Itertools;
 use rand::{prelude::StdRng, Rng};
 use rand_seeder::Seeder;
 use rustc_hash::{FxHashMap, FxHashSet};
 
+use crate::error::{HeaderError, ScoreError, SeedgenError, SettingsError};
 use crate::files::FileAccess;
 use crate::header::{self, Header, HeaderBuild};
 use crate::item::{Item, Message, UberStateOperator};
@@ -21,58 +33,149 @@ use crate::settings::{Goal, HeaderConfig, InlineHeader, UniverseSettings};
 use crate::uber_state::UberStateTrigger;
 use crate::world::{Graph, Pool, World};
 
+use hints::generate_hints;
 use placement::generate_placements;
+use plando::preplace_plando;
 use crate::generator::doors::generate_door_headers;
 
+/// Generate a seed using the default [`GeneratorOptions`]
 pub fn generate_seed<'graph, 'settings>(
     graph: &'graph Graph,
     file_access: &impl FileAccess,
     settings: &'settings UniverseSettings,
-) -> Result<Seed<'graph, 'settings>, String> {
+) -> Result<Seed<'graph, 'settings>, SeedgenError> {
+    generate_seed_with_options(graph, file_access, settings, &GeneratorOptions::default())
+}
+
+/// Generate a seed, using the [`GeneratorOptions`] to control the generation process
+pub fn generate_seed_with_options<'graph, 'settings>(
+    graph: &'graph Graph,
+    file_access: &impl FileAccess,
+    settings: &'settings UniverseSettings,
+    options: &GeneratorOptions,
+) -> Result<Seed<'graph, 'settings>, SeedgenError> {
+    generate_seed_with_observer(graph, file_access, settings, options, &())
+}
+
+/// Generate a seed, reporting progress to the [`GenerationObserver`] which may cancel the generation
+pub fn generate_seed_with_observer<'graph, 'settings>(
+    graph: &'graph Graph,
+    file_access: &impl FileAccess,
+    settings: &'settings UniverseSettings,
+    options: &GeneratorOptions,
+    observer: &dyn GenerationObserver,
+) -> Result<Seed<'graph, 'settings>, SeedgenError> {
     let mut rng: StdRng = Seeder::from(&settings.seed).make_rng();
     log::trace!("Seeded RNG with {}", settings.seed);
 
-    let (worlds, (flags, headers)): (Vec<_>, (Vec<_>, Vec<_>)) = settings
+    let (worlds, (flags, (headers, doors))): (Vec<_>, (Vec<_>, (Vec<_>, Vec<_>))) = settings
         .world_settings
         .iter()
         .map(|world_settings| {
             let mut world = World::new_spawn(graph, world_settings);
-            world.pool = Pool::preset();
+            world.pool = match &world_settings.item_pool {
+                None => Pool::standard(),
+                Some(item_pool) => Pool::from_item_pool(item_pool, file_access)
+                    .map_err(SeedgenError::ItemPool)?,
+            };
 
             let mut headers = vec![];
-            headers.push(generate_door_headers(graph, world_settings, &mut world, &mut rng)?);
+            let (door_headers, doors) =
+                generate_door_headers(graph, world_settings, &mut world, &mut rng)
+                    .map_err(SeedgenError::Doors)?;
+            headers.push(door_headers);
 
             let (goals, flags, file_headers) = parse_headers(&mut world, file_access, &mut rng)?;
             headers.push(file_headers);
 
+            preplace_plando(&mut world)?;
+
             world.goals = goals;
 
-            Ok((world, (flags, headers.join("\n"))))
+            Ok((world, (flags, (headers.join("\n"), doors))))
         })
-        .collect::<Result<Vec<_>, String>>()?
+        .collect::<Result<Vec<_>, SeedgenError>>()?
         .into_iter()
         .unzip();
 
-    let (mut worlds, spoiler) = generate_placements(graph, &worlds, &mut rng)?;
+    let (mut seed_worlds, mut spoiler) = generate_scored_placements(graph, &worlds, settings, options, observer, &mut rng)?;
+    if doors.iter().any(|doors| !doors.is_empty()) {
+        spoiler.doors = doors;
+    }
 
-    for ((world, flags), headers) in worlds.iter_mut().zip(flags).zip(headers) {
+    for ((world, mut flags), headers) in seed_worlds.iter_mut().zip(flags).zip(headers) {
+        if settings.world_count() > 1 && !settings.multiworld_sharing.is_default() {
+            flags.push(settings.multiworld_sharing.to_string());
+        }
         world.flags = flags;
         world.headers = headers;
     }
 
-    Ok(Seed {
-        worlds,
+    let mut seed = Seed {
+        worlds: seed_worlds,
         graph,
         settings,
         spoiler,
-    })
+    };
+
+    let hint_slots = worlds
+        .into_iter()
+        .map(|world| world.hint_slots)
+        .collect::<Vec<_>>();
+    generate_hints(&mut seed, &hint_slots, &mut rng).map_err(SeedgenError::Hints)?;
+
+    Ok(seed)
+}
+
+/// Generate placements until every world's [`SeedScore`] is within the [`UniverseSettings::score_range`]
+fn generate_scored_placements<'graph, 'settings>(
+    graph: &'graph Graph,
+    worlds: &[World<'graph, 'settings>],
+    settings: &UniverseSettings,
+    options: &GeneratorOptions,
+    observer: &dyn GenerationObserver,
+    rng: &mut StdRng,
+) -> Result<(Vec<SeedWorld<'graph, 'settings>>, SeedSpoiler), SeedgenError> {
+    let sharing = settings.multiworld_sharing;
+    let Some(range) = settings.score_range else {
+        return generate_placements(graph, worlds, sharing, options, observer, rng);
+    };
+
+    let retries = options.score_retries.get();
+    let mut scores = vec![];
+    for attempt in 0..retries {
+        if attempt > 0 {
+            let event = GenerationEvent::ScoreRejected { attempt, retries };
+            if observer.on_event(event) == ObserverAction::Cancel {
+                return Err(SeedgenError::Cancelled);
+            }
+        }
+
+        let (seed_worlds, spoiler) =
+            generate_placements(graph, worlds, sharing, options, observer, rng)?;
+        let world_scores = (0..worlds.len())
+            .map(|world_index| spoiler.score(world_index).total())
+            .collect::<Vec<_>>();
+        if world_scores.iter().all(|score| range.contains(*score)) {
+            return Ok((seed_worlds, spoiler));
+        }
+
+        log::warning!(
+            "Seed scored {}, which is outside {}\nRetrying...",
+            world_scores.iter().join(", "),
+            range
+        );
+        scores.push(world_scores);
+    }
+
+    Err(ScoreError { range, scores }.into())
 }
 
 fn parse_headers(
     world: &mut World,
     file_access: &impl FileAccess,
     rng: &mut impl Rng,
-) -> Result<(Vec<Goal>, Vec<String>, String), String> {
+) -> Result<(Vec<Goal>, Vec<String>, String), SeedgenError> {
     validate_header_names(
         &world.player.settings.headers,
         &world.player.settings.inline_headers,
@@ -84,8 +187,8 @@ fn parse_headers(
     let mut includes = FxHashSet::default();
     includes.extend(world.player.settings.headers.iter().cloned());
 
-    for header_name in &world.player.settings.headers {
-        let header = file_access.read_header(header_name)?;
+    for header_name in world.player.settings.headers.iter().sorted_unstable() {
+        let header = read_header(header_name, file_access)?;
         parse_header(
             header_name.clone(),
             header,
@@ -160,7 +263,7 @@ fn parse_headers(
                 world.preplace(preplacement.trigger, preplacement.item);
             }
 
-            for (item, amount) in header.item_pool_changes {
+            for (item, amount) in header.item_pool_changes.into_iter().sorted_unstable() {
                 #[allow(clippy::cast_sign_loss)]
                 match amount.cmp(&0) {
                     Ordering::Less => world.pool.remove(&item, (-amount) as u32),
@@ -172,13 +275,12 @@ fn parse_headers(
             for (item, details) in header.item_details {
                 let display = item.to_string();
                 if world.custom_items.insert(item, details).is_some() {
-                    return Err(format!(
-                        "multiple headers tried to customize the item {display}"
-                    ));
+                    return Err(HeaderError::DuplicateCustomization { item: display });
                 }
             }
 
             state_sets.append(&mut header.state_sets);
+            world.hint_slots.append(&mut header.hint_slots);
 
             Ok(header_name)
         })
@@ -186,9 +288,11 @@ fn parse_headers(
 
     for header_name in &header_names {
         if let Some(other) = excludes.get(header_name) {
-            return Err(format!(
-                "headers {other} and {header_name} are incompatible"
-            ));
+            return Err(HeaderError::Incompatible {
+                header: header_name.clone(),
+                other: other.clone(),
+            }
+            .into());
         }
     }
     for header_with_parameters in config_map.keys() {
@@ -205,7 +309,9 @@ fn parse_headers(
     goals.extend(world.player.settings.goals.iter().cloned());
 
     for flag in goals.iter().map(Goal::flag_name) {
-        flags.push(flag.to_string());
+        if !flags.iter().any(|existing| existing == flag) {
+            flags.push(flag.to_string());
+        }
     }
 
     let mut header_block = String::new();
@@ -214,6 +320,12 @@ fn parse_headers(
     if !state_sets.is_empty() {
         writeln!(header_block, "// Sets: {}", state_sets.join(", ")).unwrap();
     }
+    // Seed verification reads the conditions of custom goals from these lines
+    for goal in &goals {
+        if let Goal::Custom(goal) = goal {
+            writeln!(header_block, "// Goal: {goal}").unwrap();
+        }
+    }
 
     Ok((goals, flags, header_block))
 }
@@ -226,24 +338,25 @@ fn parse_header(
     config_map: &mut FxHashMap<String, FxHashMap<String, String>>,
     file_access: &impl FileAccess,
     rng: &mut impl Rng,
-) -> Result<(), String> {
+) -> Result<(), HeaderError> {
     log::trace!("Parsing header {header_name}");
 
     let header_config = config_map.remove(&header_name).unwrap_or_default();
 
     let header = Header::parse(header, rng)
-        .map_err(|err| {
-            format!(
-                "Error in header {}:\n{}",
-                header_name,
-                err.verbose_display()
-            )
+        .map_err(|errors| HeaderError::Parse {
+            header: header_name.clone(),
+            errors,
         })?
-        .build(header_config)?;
+        .build(header_config)
+        .map_err(|message| HeaderError::Build {
+            header: header_name.clone(),
+            message,
+        })?;
 
     for include in &header.includes {
         if includes.insert(include.clone()) {
-            let header = file_access.read_header(include)?;
+            let header = read_header(include, file_access)?;
             parse_header(
                 include.clone(),
                 header,
@@ -261,17 +374,24 @@ fn parse_header(
     Ok(())
 }
 
+fn read_header(header_name: &str, file_access: &impl FileAccess) -> Result<String, HeaderError> {
+    file_access
+        .read_header(header_name)
+        .map_err(|message| HeaderError::Read {
+            header: header_name.to_string(),
+            message,
+        })
+}
+
 /// verifies that inline headers don't claim names already in use
 fn validate_header_names(
     headers: &FxHashSet<String>,
     inline_headers: &[InlineHeader],
-) -> Result<(), String> {
+) -> Result<(), SettingsError> {
     for inline_header in inline_headers {
         if let Some(name) = &inline_header.name {
             if headers.contains(name) {
-                return Err(format!(
-                    "Ambiguous name: {name} used both as a file header and an inline header"
-                ));
+                return Err(SettingsError::AmbiguousHeaderName(name.clone()));
             }
         }
     }
@@ -281,7 +401,7 @@ fn validate_header_names(
 
 fn build_config_map(
     header_config: &[HeaderConfig],
-) -> Result<FxHashMap<String, FxHashMap<String, String>>, String> {
+) -> Result<FxHashMap<String, FxHashMap<String, String>>, SettingsError> {
     let mut config_map = FxHashMap::<String, FxHashMap<_, _>>::default();
 
     for config in header_config {
@@ -291,7 +411,12 @@ fn build_config_map(
             .insert(config.config_name.clone(), config.config_value.clone())
         {
             if prior != config.config_value {
-                return Err(format!("provided multiple values for configuration parameter {} for header {} ({} and {})", config.config_name, config.header_name, prior, config.config_value));
+                return Err(SettingsError::ConflictingHeaderConfig {
+                    header: config.header_name.clone(),
+                    parameter: config.config_name.clone(),
+                    first: prior,
+                    second: config.config_value.clone(),
+                });
             }
         }
     }
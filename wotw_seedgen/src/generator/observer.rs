@@ -0,0 +1,59 @@
+/// Progress reported to a [`GenerationObserver`] while generating a seed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GenerationEvent<'a> {
+    /// An attempt to place items failed and the next attempt is starting
+    ///
+    /// `attempt` starts counting at 0, so the first retry is attempt 1
+    RetryStarted { attempt: u16, retries: u16 },
+    /// A spawn location has been chosen for a world at the start of an attempt
+    SpawnChosen { world_index: usize, spawn: &'a str },
+    /// A step of item placement finished, `reached` out of `total` locations across all worlds are reachable now
+    PlacementStep { reached: usize, total: usize },
+    /// No new locations were reachable, so the generator forced progression items into a world
+    ForcedProgression { world_index: usize },
+    /// A seed was rejected because its score was outside the [`ScoreRange`](crate::settings::ScoreRange) and the next seed is being generated
+    ///
+    /// `attempt` starts counting at 0, so the first seed generated again is attempt 1
+    ScoreRejected { attempt: u16, retries: u16 },
+}
+
+/// How the generator should continue after reporting a [`GenerationEvent`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObserverAction {
+    Continue,
+    /// Abort generation with [`SeedgenError::Cancelled`](crate::SeedgenError::Cancelled)
+    Cancel,
+}
+
+/// Receives [`GenerationEvent`]s during generation and may cancel it
+///
+/// Attempts may run on multiple threads if [`GeneratorOptions::parallel_attempts`](super::GeneratorOptions::parallel_attempts) is set, so events of different attempts can arrive interleaved
+///
+/// # Examples
+///
+/// ```
+/// # use std::sync::atomic::{AtomicBool, Ordering};
+/// # use wotw_seedgen::generator::{GenerationEvent, GenerationObserver, ObserverAction};
+/// #
+/// struct Abort(AtomicBool);
+///
+/// impl GenerationObserver for Abort {
+///     fn on_event(&self, _event: GenerationEvent) -> ObserverAction {
+///         if self.0.load(Ordering::Relaxed) {
+///             ObserverAction::Cancel
+///         } else {
+///             ObserverAction::Continue
+///         }
+///     }
+/// }
+/// ```
+pub trait GenerationObserver: Sync {
+    fn on_event(&self, event: GenerationEvent) -> ObserverAction;
+}
+
+/// Ignores all events and never cancels
+impl GenerationObserver for () {
+    fn on_event(&self, _event: GenerationEvent) -> ObserverAction {
+        ObserverAction::Continue
+    }
+}
@@ -0,0 +1,41 @@
+use std::num::{NonZeroU16, NonZeroUsize};
+
+use crate::util::constants::{RETRIES, SCORE_RETRIES};
+
+/// Options that control how the generator behaves without influencing the resulting seed
+///
+/// # Examples
+///
+/// ```
+/// # use std::num::NonZeroU16;
+/// # use wotw_seedgen::generator::GeneratorOptions;
+/// #
+/// let mut options = GeneratorOptions::default();
+/// options.retries = NonZeroU16::new(50).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneratorOptions {
+    /// How many attempts to place items are allowed before giving up
+    ///
+    /// Every attempt uses the random state left behind by the previous one, so a seed generated with fewer retries stays the same with more retries
+    pub retries: NonZeroU16,
+    /// How many seeds may be generated before giving up on one within the [`ScoreRange`](crate::settings::ScoreRange)
+    ///
+    /// Each of these seeds may use up to `retries` attempts to place items
+    pub score_retries: NonZeroU16,
+    /// If set, run up to this many attempts to place items concurrently
+    ///
+    /// Every attempt then uses its own random state derived from the seed and the attempt's index, and the successful attempt with the lowest index is used.
+    /// This produces a different seed than serial generation, but the same seed for any amount of concurrent attempts
+    pub parallel_attempts: Option<NonZeroUsize>,
+}
+
+impl Default for GeneratorOptions {
+    fn default() -> GeneratorOptions {
+        GeneratorOptions {
+            retries: RETRIES,
+            score_retries: SCORE_RETRIES,
+            parallel_attempts: None,
+        }
+    }
+}
@@ -1,14 +1,25 @@
-use std::mem;
+use std::{
+    iter, mem,
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicU16, Ordering},
+        Mutex,
+    },
+    thread,
+};
 
+use itertools::Itertools;
 use rand::{
     distributions::{Bernoulli, Distribution, Uniform},
+    rngs::StdRng,
     seq::{IteratorRandom, SliceRandom},
     Rng,
 };
+use rand_seeder::Seeder;
+use rustc_hash::{FxHashMap, FxHashSet};
 
-#[cfg(feature = "log")]
-use crate::settings::Difficulty;
 use crate::{
+    error::{PlacementAttempt, PlacementError, SeedgenError, SettingsError},
     generator::spoiler::NodeSummary,
     header::CodeDisplay,
     inventory::Inventory,
@@ -16,13 +27,17 @@ use crate::{
         Command, Item, Message, Resource, ShopCommand, Teleporter, UberStateItem, UberStateValue,
     },
     log,
-    settings::{Goal, Spawn, WorldSettings},
+    settings::{
+        Difficulty, Goal, MultiworldSharing, PlacementConstraint, ShopPriceModel, Spawn,
+        SpiritLightMode, WorldSettings,
+    },
     util::{
         self,
         constants::{
             DEFAULT_SPAWN, KEYSTONE_DOORS, PLACEHOLDER_SLOTS, RANDOM_PROGRESSION, RELIC_ZONES,
-            RESERVE_SLOTS, RETRIES, SHOP_PRICES,
+            RESERVE_SLOTS, SHOP_PRICES,
         },
+        Zone,
     },
     world::{
         graph::{self, Graph, Node},
@@ -34,8 +49,13 @@ use crate::{
     util::constants::{PREFERRED_SPAWN_SLOTS, SPAWN_SLOTS},
 };
 
+use super::plando::plando_nodes;
 use super::seed::SeedWorld;
 use super::spoiler::{SeedSpoiler, SpoilerGroup, SpoilerPlacement};
+use super::{
+    observer::{GenerationEvent, GenerationObserver, ObserverAction},
+    options::GeneratorOptions,
+};
 
 #[derive(Debug, Clone)]
 /// One [`Item`] tied to an [`UberStateTrigger`]
@@ -75,6 +95,55 @@ struct WorldContext<'a, 'b> {
     random_spirit_light: Bernoulli,
     shop_slots: usize,
     world_tour: Option<usize>,
+    reached_before: FxHashMap<usize, usize>,
+    reached_count: usize,
+    /// The progress at which each zone was first reached
+    zone_progress: FxHashMap<Zone, f32>,
+    /// Shop locations that are reachable, but held back until the player has enough Spirit Light for the next shop tier
+    postponed_shops: Vec<&'a Node>,
+    opened_shops: usize,
+    /// Sum of all shop prices so far
+    shop_price_total: u32,
+    /// Sum of the shop prices of progression items so far
+    progression_shop_total: u32,
+}
+
+impl<'a> WorldContext<'a, '_> {
+    /// How far placement has progressed in this world, from 0 at the start to 1 once every location has been reached
+    fn progress(&self) -> f32 {
+        (self.reached_count as f32 / self.reachable_locations.len().max(1) as f32).min(1.)
+    }
+
+    /// Chooses the Spirit Light to fill a location that doesn't hold an item from the pool
+    fn filler_item(&mut self, node: Option<&Node>, rng: &mut impl Rng) -> Item {
+        let progress = node
+            .and_then(Node::zone)
+            .and_then(|zone| self.zone_progress.get(&zone).copied())
+            .unwrap_or_else(|| self.progress());
+        Item::SpiritLight(self.spirit_light_rng.sample(progress, rng))
+    }
+
+    /// How much Spirit Light the player owns
+    fn shop_currency(&self) -> u32 {
+        self.world.player.inventory.get(&Item::SpiritLight(1))
+    }
+
+    /// Moves postponed shop locations into `needs_placement` for as long as the player meets the next shop minimum
+    fn open_shops(&mut self, needs_placement: &mut Vec<&'a Node>) {
+        let shop_currency = self.shop_currency();
+        while !self.postponed_shops.is_empty()
+            && shop_currency
+                >= self
+                    .world
+                    .player
+                    .settings
+                    .spirit_light
+                    .shop_minimum(self.opened_shops)
+        {
+            needs_placement.push(self.postponed_shops.remove(0));
+            self.opened_shops += 1;
+        }
+    }
 }
 
 struct GeneratorContext<'a, R, I>
@@ -87,9 +156,9 @@ where
     multiworld_state_index: I,
     spoiler_groups: Vec<SpoilerGroup>,
     current_spoiler_group: SpoilerGroup,
-    price_range: Uniform<f32>,
-    random_progression: Bernoulli,
     rng: &'a mut R,
+    observer: &'a dyn GenerationObserver,
+    sharing: MultiworldSharing,
 }
 
 impl<R: Rng, I: Iterator<Item = u16>> GeneratorContext<'_, R, I> {
@@ -100,6 +169,39 @@ impl<R: Rng, I: Iterator<Item = u16>> GeneratorContext<'_, R, I> {
         self.spoiler_groups
             .push(mem::take(&mut self.current_spoiler_group));
     }
+
+    /// Whether taking the [`Item`] from a pool takes it from every world's pool and grants it to every world
+    fn shares_pool(&self, item: &Item) -> bool {
+        self.sharing == MultiworldSharing::SharedPool
+            && self.world_count > 1
+            && item.is_multiworld_spread()
+    }
+
+    /// Whether the target world's [`Item`] may be placed in other worlds
+    fn may_share(&self, item: &Item, target_world_context: &WorldContext) -> bool {
+        self.sharing
+            .may_share(item, target_world_context.world.player.settings.difficulty)
+    }
+}
+
+/// Removes the [`Item`] from the target world's pool and grants it to the target world's player
+///
+/// With [`MultiworldSharing::SharedPool`] this happens in every world
+fn take_from_pool<R, I>(
+    target_world_index: usize,
+    item: &Item,
+    world_contexts: &mut [WorldContext<'_, '_>],
+    context: &GeneratorContext<'_, R, I>,
+) where
+    R: Rng,
+    I: Iterator<Item = u16>,
+{
+    for (world_index, world_context) in world_contexts.iter_mut().enumerate() {
+        if world_index == target_world_index || context.shares_pool(item) {
+            world_context.world.pool.remove(item, 1);
+            world_context.world.grant_player(item.clone(), 1);
+        }
+    }
 }
 
 struct ReachContext<'a> {
@@ -175,12 +277,73 @@ where
     })
 }
 
+/// Returns the first [`PlacementConstraint`] of the target world that placing the [`Item`] on the [`Node`] would violate
+fn violated_constraint<'c>(
+    item: &Item,
+    node: &Node,
+    origin_world_index: usize,
+    target_world_index: usize,
+    world_contexts: &'c [WorldContext<'_, '_>],
+) -> Option<&'c PlacementConstraint> {
+    let origin_world_context = &world_contexts[origin_world_index];
+
+    world_contexts[target_world_index]
+        .world
+        .player
+        .settings
+        .placement_constraints
+        .iter()
+        .find(|constraint| match constraint {
+            PlacementConstraint::NotInZone {
+                item: constrained_item,
+                zone,
+            } => item == constrained_item && node.zone() == Some(*zone),
+            PlacementConstraint::NoSkillsInShops => {
+                matches!(item, Item::Skill(_))
+                    && node
+                        .trigger()
+                        .is_some_and(|trigger| trigger.identifier.is_shop())
+            }
+            PlacementConstraint::WithinFirstReachable {
+                item: constrained_item,
+                locations,
+            } => {
+                item == constrained_item
+                    && origin_world_context
+                        .reached_before
+                        .get(&node.index())
+                        .is_none_or(|reached_before| reached_before >= locations)
+            }
+        })
+}
+
+/// Returns the index of the last [`Node`] in `nodes` that the [`Item`] may be placed on without violating any [`PlacementConstraint`]s
+fn last_allowed_slot(
+    nodes: &[&Node],
+    item: &Item,
+    origin_world_index: usize,
+    target_world_index: usize,
+    world_contexts: &[WorldContext<'_, '_>],
+) -> Option<usize> {
+    nodes.iter().rposition(|node| {
+        violated_constraint(
+            item,
+            node,
+            origin_world_index,
+            target_world_index,
+            world_contexts,
+        )
+        .is_none()
+    })
+}
+
 fn place_item<'a, R, I>(
     origin_world_index: usize,
     target_world_index: usize,
     node: &'a Node,
     #[cfg_attr(not(feature = "log"), allow(unused_variables))] was_placeholder: bool,
     forced: bool,
+    from_pool: bool,
     item: Item,
     world_contexts: &mut [WorldContext<'a, '_>],
     context: &mut GeneratorContext<'_, R, I>,
@@ -189,6 +352,19 @@ where
     R: Rng,
     I: Iterator<Item = u16>,
 {
+    if let Some(constraint) = violated_constraint(
+        &item,
+        node,
+        origin_world_index,
+        target_world_index,
+        world_contexts,
+    ) {
+        return Err(format!(
+            "(World {}): Placing World {}'s {} at {} would violate the placement constraint \"{}\"",
+            origin_world_index, target_world_index, item, node, constraint
+        ));
+    }
+
     let trigger = node.trigger().unwrap();
     let is_shop = trigger.identifier.is_shop();
 
@@ -212,8 +388,10 @@ where
     let custom_name = origin_details.and_then(|details| details.name.clone());
     let display = origin_details.and_then(|details| details.display.clone()); // Using the display of other worlds is dangerous because it often relies on further information from the other world's headers
     let item_name = custom_name.clone().unwrap_or_else(|| item.to_string());
+    let shared = from_pool && context.shares_pool(&item);
+    let shared_name = if shared { custom_name.clone() } else { None };
 
-    if origin_world_index == target_world_index {
+    if origin_world_index == target_world_index || shared {
         let origin_world_context = &mut world_contexts[origin_world_index];
 
         log::trace!(
@@ -329,6 +507,17 @@ where
         });
     }
 
+    if shared {
+        send_to_all(
+            origin_world_index,
+            node,
+            &item,
+            shared_name,
+            world_contexts,
+            context,
+        );
+    }
+
     if let Some(icon) = world_contexts[target_world_index]
         .world
         .custom_items
@@ -366,6 +555,7 @@ where
         .push(SpoilerPlacement {
             origin_world_index,
             target_world_index,
+            shared,
             location,
             item,
             item_name,
@@ -374,6 +564,66 @@ where
     Ok(())
 }
 
+/// Makes every world except the origin world receive the [`Item`] when the origin world collects the [`Node`]
+fn send_to_all<'a, R, I>(
+    origin_world_index: usize,
+    node: &'a Node,
+    item: &Item,
+    custom_name: Option<String>,
+    world_contexts: &mut [WorldContext<'a, '_>],
+    context: &mut GeneratorContext<'_, R, I>,
+) where
+    R: Rng,
+    I: Iterator<Item = u16>,
+{
+    let state_index = context.multiworld_state_index.next().unwrap();
+    let identifier = UberIdentifier::new(12, state_index);
+
+    for (world_index, world_context) in world_contexts.iter_mut().enumerate() {
+        if world_index == origin_world_index {
+            world_context.placements.push(Placement {
+                node: Some(node),
+                trigger: node.trigger().unwrap().clone(),
+                item: UberStateItem::simple_setter(
+                    identifier,
+                    UberType::Bool,
+                    UberStateValue::Bool(true),
+                ),
+                hide_message: false,
+            });
+            continue;
+        }
+
+        // Same as for single targets, only the display of the receiving world is safe to use
+        let details = world_context.world.custom_items.get(item);
+        let display = details
+            .and_then(|details| details.display.clone())
+            .or_else(|| details.and_then(|details| details.name.clone()))
+            .or_else(|| custom_name.clone())
+            .unwrap_or_else(|| format!("$[{}]", item.code()));
+        let trigger = UberStateTrigger {
+            identifier,
+            condition: None,
+        };
+
+        world_context.placements.push(Placement {
+            node: None,
+            trigger: trigger.clone(),
+            item: item.clone(),
+            hide_message: true,
+        });
+        world_context.placements.push(Placement {
+            node: None,
+            trigger,
+            item: Item::Message(Message::new(format!(
+                "{} from $[15|5|{}]",
+                display, origin_world_index
+            ))),
+            hide_message: false,
+        });
+    }
+}
+
 fn shop_placement<R, I>(
     node: &Node,
     item: &Item,
@@ -405,16 +655,55 @@ where
         .or_else(|| target_details.and_then(|details| details.price))
         .unwrap_or_else(|| item.shop_price());
 
+    let is_progression = item.is_progression(
+        world_contexts[target_world_index]
+            .world
+            .player
+            .settings
+            .difficulty,
+    );
+    let origin_world_context = &world_contexts[origin_world_index];
+    let settings = &origin_world_context.world.player.settings;
+    let pricing = &settings.shop_pricing;
+
     if item.random_shop_price() {
-        let modified_price = price as f32 * context.price_range.sample(context.rng);
+        let factor = match pricing.model {
+            ShopPriceModel::Fixed => 1.,
+            ShopPriceModel::Random => {
+                Uniform::new_inclusive(pricing.min_factor, pricing.max_factor).sample(context.rng)
+            }
+            ShopPriceModel::Progression if is_progression => pricing.max_factor,
+            ShopPriceModel::Progression => pricing.min_factor,
+        };
+        let modified_price = price as f32 * factor;
         price = util::float_to_int(modified_price).map_err(|_| {
             format!(
-                "(World {}): Overflowed shop price for {} after adding a random amount to it",
-                origin_world_index, item
+                "(World {}): Overflowed shop price for {} after multiplying it by {}",
+                origin_world_index, item, factor
             )
         })?;
     }
 
+    // Every shop item costs something, even after scaling the price down
+    price = price.max(1);
+
+    if let Some(max_total) = pricing.max_total {
+        // Leave at least 1 for each remaining shop slot, validate_shop_pricing made sure max_total covers that
+        let allowance = max_total
+            .saturating_sub(origin_world_context.shop_price_total)
+            .saturating_sub(origin_world_context.shop_slots as u32);
+        price = price.min(allowance.max(1));
+    }
+    // The player has to be able to afford progression with the Spirit Light placed so far, after buying all the progression placed in shops before
+    let missing_spirit_light = if is_progression && pricing.affordable_progression {
+        let affordable = origin_world_context
+            .shop_currency()
+            .saturating_sub(origin_world_context.progression_shop_total);
+        price.saturating_sub(affordable)
+    } else {
+        0
+    };
+
     let price_setter = UberStateItem::simple_setter(
         *price_uber_identifier,
         UberType::Int,
@@ -436,8 +725,32 @@ where
         price_setter
     );
 
+    if missing_spirit_light > 0 {
+        log::trace!(
+            "(World {}): Force placing {} Spirit Light to afford {} for {}",
+            origin_world_index,
+            missing_spirit_light,
+            item,
+            price
+        );
+
+        // One stack, since there may be few slots available this early
+        forced_placement(
+            origin_world_index,
+            Item::SpiritLight(missing_spirit_light),
+            &mut Vec::new(),
+            world_contexts,
+            context,
+        )?;
+    }
+
     let origin_world_context = &mut world_contexts[origin_world_index];
 
+    origin_world_context.shop_price_total += price;
+    if is_progression {
+        origin_world_context.progression_shop_total += price;
+    }
+
     origin_world_context.placements.push(Placement {
         node: None,
         trigger: UberStateTrigger::load(),
@@ -527,6 +840,7 @@ where
                 location,
                 false,
                 false,
+                false,
                 Item::Relic(*zone),
                 world_contexts,
                 context,
@@ -548,8 +862,10 @@ where
     R: Rng,
     I: Iterator<Item = u16>,
 {
+    let keystone = Item::Resource(Resource::Keystone);
+
     for target_world_index in 0..context.world_count {
-        let world_context = &mut world_contexts[target_world_index];
+        let world_context = &world_contexts[target_world_index];
 
         let placed_keystones = world_context
             .world
@@ -573,7 +889,22 @@ where
             continue;
         }
 
-        let missing_keystones = required_keystones - placed_keystones;
+        // Keystones that may not be shared can only use the target world's slots
+        let local =
+            world_context.prevent_sharing > 0 || !context.may_share(&keystone, world_context);
+        let slots = reserved_slots
+            .iter()
+            .filter(|(world_index, _)| !local || *world_index == target_world_index)
+            .count()
+            + world_contexts
+                .iter()
+                .enumerate()
+                .filter(|(world_index, _)| !local || *world_index == target_world_index)
+                .map(|(_, world_context)| world_context.placeholders.len())
+                .sum::<usize>()
+            + world_context.spawn_slots;
+        // Forcing Keystones only prevents keylocks, so it's no reason to fail the attempt when running out of slots
+        let missing_keystones = (required_keystones - placed_keystones).min(slots as u32);
 
         log::trace!(
             "(World {}): Force placing {} keystones to avoid keylocks",
@@ -584,7 +915,7 @@ where
         for _ in 0..missing_keystones {
             forced_placement(
                 target_world_index,
-                Item::Resource(Resource::Keystone),
+                keystone.clone(),
                 reserved_slots,
                 world_contexts,
                 context,
@@ -606,90 +937,70 @@ where
     R: Rng,
     I: Iterator<Item = u16>,
 {
-    let prevent_sharing = &mut world_contexts[target_world_index].prevent_sharing;
-    let is_multiworld_spread = if *prevent_sharing > 0 {
-        *prevent_sharing -= 1;
+    let target_world_context = &mut world_contexts[target_world_index];
+    let is_multiworld_spread = if target_world_context.prevent_sharing > 0 {
+        target_world_context.prevent_sharing -= 1;
         false
     } else {
-        item.is_multiworld_spread()
+        context.may_share(&item, target_world_context)
     };
 
-    let mut choose_node = || {
-        if is_multiworld_spread {
-            let mut world_indices = (0..context.world_count).collect::<Vec<_>>();
-            world_indices.shuffle(context.rng);
+    let mut node = choose_forced_slot(
+        target_world_index,
+        &item,
+        is_multiworld_spread,
+        reserved_slots,
+        world_contexts,
+        context,
+    )?;
 
-            if let Some((origin_world_index, node)) = reserved_slots.pop() {
-                return Ok((origin_world_index, node, false));
-            }
-            for origin_world_index in world_indices {
-                let placeholders = &mut world_contexts[origin_world_index].placeholders;
-                if !placeholders.is_empty() {
-                    let index = context.rng.gen_range(0..placeholders.len());
-                    let node = placeholders.remove(index);
-                    return Ok((origin_world_index, node, true));
-                }
-            }
-        } else {
-            if let Some((index, _)) = reserved_slots
-                .iter()
-                .enumerate()
-                .find(|(_, (world_index, _))| world_index == &target_world_index)
-            {
-                let (_, node) = reserved_slots.remove(index);
-                return Ok((target_world_index, node, false));
-            }
+    // Don't place Spirit Light in shops or anything where it would violate a placement constraint
+    let is_spirit_light = matches!(item, Item::SpiritLight(_));
+    let mut skipped_slots = Vec::new();
 
-            let placeholders = &mut world_contexts[target_world_index].placeholders;
-            if !placeholders.is_empty() {
-                let index = context.rng.gen_range(0..placeholders.len());
-                let node = placeholders.remove(index);
-                return Ok((target_world_index, node, true));
-            }
-        }
-        let target_world_context = &mut world_contexts[target_world_index];
-        if target_world_context.spawn_slots > 0 {
-            target_world_context.spawn_slots -= 1;
-            return Ok((
-                target_world_index,
-                &target_world_context.world.graph.spawn_pickup_node,
-                false,
-            ));
+    loop {
+        let violated =
+            violated_constraint(&item, node.1, node.0, target_world_index, world_contexts)
+                .map(PlacementConstraint::to_string);
+        if violated.is_none()
+            && !(is_spirit_light && node.1.trigger().unwrap().identifier.is_purchasable())
+        {
+            break;
         }
-        Err(format!(
-            "(World {}): Not enough slots to place forced progression {}",
-            target_world_index, item
-        )) // due to the slot checks in missing_items this should only ever happen for forced keystone placements
-    };
-
-    let mut node = choose_node()?;
 
-    // Don't place Spirit Light in shops
-    if matches!(item, Item::SpiritLight(_)) {
-        let mut skipped_slots = Vec::new();
+        skipped_slots.push((node.0, node.1));
 
-        while node.1.trigger().unwrap().identifier.is_purchasable() {
-            skipped_slots.push((node.0, node.1));
-
-            node = choose_node()?;
-        }
+        node = choose_forced_slot(
+            target_world_index,
+            &item,
+            is_multiworld_spread,
+            reserved_slots,
+            world_contexts,
+            context,
+        )
+        .map_err(|err| match violated {
+            Some(constraint) => format!(
+                "(World {}): No slot left to place forced progression {} without violating the placement constraint \"{}\"",
+                target_world_index, item, constraint
+            ),
+            None => err,
+        })?;
+    }
 
-        for skipped_slot in skipped_slots {
-            world_contexts[skipped_slot.0]
-                .placeholders
-                .push(skipped_slot.1);
-        }
+    for skipped_slot in skipped_slots {
+        world_contexts[skipped_slot.0]
+            .placeholders
+            .push(skipped_slot.1);
     }
 
-    let world_context = &mut world_contexts[target_world_index];
-    world_context.world.pool.remove(&item, 1);
-    world_context.world.grant_player(item.clone(), 1);
+    take_from_pool(target_world_index, &item, world_contexts, context);
     place_item(
         node.0,
         target_world_index,
         node.1,
         node.2,
         true,
+        true,
         item,
         world_contexts,
         context,
@@ -698,13 +1009,76 @@ where
     Ok(())
 }
 
+/// Returns the origin world index, the slot and whether the slot was a placeholder
+fn choose_forced_slot<'a, R, I>(
+    target_world_index: usize,
+    item: &Item,
+    is_multiworld_spread: bool,
+    reserved_slots: &mut Vec<(usize, &'a Node)>,
+    world_contexts: &mut [WorldContext<'a, '_>],
+    context: &mut GeneratorContext<'_, R, I>,
+) -> Result<(usize, &'a Node, bool), String>
+where
+    R: Rng,
+    I: Iterator<Item = u16>,
+{
+    if is_multiworld_spread {
+        let mut world_indices = (0..context.world_count).collect::<Vec<_>>();
+        world_indices.shuffle(context.rng);
+
+        if let Some((origin_world_index, node)) = reserved_slots.pop() {
+            return Ok((origin_world_index, node, false));
+        }
+        for origin_world_index in world_indices {
+            let placeholders = &mut world_contexts[origin_world_index].placeholders;
+            if !placeholders.is_empty() {
+                let index = context.rng.gen_range(0..placeholders.len());
+                let node = placeholders.remove(index);
+                return Ok((origin_world_index, node, true));
+            }
+        }
+    } else {
+        if let Some((index, _)) = reserved_slots
+            .iter()
+            .enumerate()
+            .find(|(_, (world_index, _))| world_index == &target_world_index)
+        {
+            let (_, node) = reserved_slots.remove(index);
+            return Ok((target_world_index, node, false));
+        }
+
+        let placeholders = &mut world_contexts[target_world_index].placeholders;
+        if !placeholders.is_empty() {
+            let index = context.rng.gen_range(0..placeholders.len());
+            let node = placeholders.remove(index);
+            return Ok((target_world_index, node, true));
+        }
+    }
+    let target_world_context = &mut world_contexts[target_world_index];
+    if target_world_context.spawn_slots > 0 {
+        target_world_context.spawn_slots -= 1;
+        return Ok((
+            target_world_index,
+            &target_world_context.world.graph.spawn_pickup_node,
+            false,
+        ));
+    }
+    Err(format!(
+        "(World {}): Not enough slots to place forced progression {}",
+        target_world_index, item
+    )) // due to the slot checks in missing_items this should only ever happen for forced keystone placements
+}
+
 fn determine_progressions(
     world_index: usize,
     slots: usize,
     world_slots: usize,
     reach_context: &ReachContext,
     world_context: &WorldContext<'_, '_>,
+    sharing: MultiworldSharing,
 ) -> Vec<Inventory> {
+    let difficulty = world_context.world.player.settings.difficulty;
+
     let owned_states = reach_context.reachable_states[world_index]
         .iter()
         .map(|&node| node.index())
@@ -727,7 +1101,12 @@ fn determine_progressions(
                         solution.item_count() as usize <= slots
                             && solution.world_item_count() as usize <= world_slots
                     );
-                    if solution.items.is_empty() || !world_context.world.pool.contains(&solution) {
+                    if solution.items.is_empty()
+                        || solution.world_item_count() as usize
+                            + local_item_count(&solution, sharing, difficulty)
+                            > world_slots
+                        || !world_context.world.pool.contains(&solution)
+                    {
                         None
                     } else {
                         Some(solution)
@@ -737,10 +1116,25 @@ fn determine_progressions(
         .collect()
 }
 
+/// How many items of the [`Inventory`] aren't allowed to leave their world, besides Spirit Light which never does
+fn local_item_count(
+    inventory: &Inventory,
+    sharing: MultiworldSharing,
+    difficulty: Difficulty,
+) -> usize {
+    inventory
+        .items
+        .iter()
+        .filter(|(item, _)| item.is_multiworld_spread() && !sharing.may_share(item, difficulty))
+        .map(|(_, amount)| *amount as usize)
+        .sum()
+}
+
 fn pick_progression<'a, R, I>(
     target_world_index: usize,
     itemsets: &'a [Inventory],
     slots: usize,
+    world_slots: usize,
     reach_context: &ReachContext,
     world_contexts: &mut [WorldContext<'_, '_>],
     context: &mut GeneratorContext<'_, R, I>,
@@ -776,9 +1170,39 @@ where
 
         let mut weight = 1.0 / inventory.cost() as f32 * (newly_reached + 1) as f32;
 
-        let begrudgingly_used_slots = (inventory.item_count() as usize
+        let placement_weights = &target_world_context.world.player.settings.placement_weights;
+        if !placement_weights.has_neutral_items() {
+            let progress = target_world_context.progress();
+            let item_weight = inventory
+                .items
+                .iter()
+                .map(|(item, amount)| {
+                    placement_weights
+                        .item_weight(item, progress)
+                        .powi(*amount as i32)
+                })
+                .product::<f32>();
+            // Forced progression has to stay possible even if all options contain items weighted at 0
+            weight *= item_weight.max(f32::EPSILON);
+        }
+
+        let mut begrudgingly_used_slots = (inventory.item_count() as usize
             + (SPAWN_SLOTS - PREFERRED_SPAWN_SLOTS))
             .saturating_sub(slots);
+        // Items that may not be shared can only use the target world's slots
+        let local_items = local_item_count(
+            inventory,
+            context.sharing,
+            target_world_context.world.player.settings.difficulty,
+        );
+        if local_items > 0 {
+            begrudgingly_used_slots = begrudgingly_used_slots.max(
+                (inventory.world_item_count() as usize
+                    + local_items
+                    + (SPAWN_SLOTS - PREFERRED_SPAWN_SLOTS))
+                    .saturating_sub(world_slots),
+            );
+        }
         if begrudgingly_used_slots > 0 {
             weight *= (0.3_f32).powf(begrudgingly_used_slots as f32);
         }
@@ -838,9 +1262,10 @@ where
         let mut amount_placed = 0;
 
         while amount_placed < amount {
-            let stacked_amount = world_contexts[world_index]
+            let world_context = &mut world_contexts[world_index];
+            let stacked_amount = world_context
                 .spirit_light_rng
-                .sample(context.rng);
+                .sample(world_context.progress(), context.rng);
             amount_placed += stacked_amount;
             spirit_light_items.push(Item::SpiritLight(stacked_amount));
         }
@@ -856,7 +1281,7 @@ fn force_progression<'a, R, I>(
     reach_context: &mut ReachContext,
     world_contexts: &mut [WorldContext<'a, '_>],
     context: &mut GeneratorContext<'_, R, I>,
-) -> Result<(), String>
+) -> Result<(), AttemptFailure>
 where
     R: Rng,
     I: Iterator<Item = u16>,
@@ -870,7 +1295,7 @@ where
     let mut world_indices = (0..context.world_count).collect::<Vec<_>>();
     world_indices.shuffle(context.rng);
 
-    let (target_world_index, mut itemsets) = loop {
+    let (target_world_index, world_slots, mut itemsets) = loop {
         if let Some(chosen_world_index) = world_indices.pop() {
             let world_context = &mut world_contexts[chosen_world_index];
             let world_slots = reserved_slots
@@ -886,12 +1311,13 @@ where
                 world_slots,
                 reach_context,
                 world_context,
+                context.sharing,
             );
 
             if itemsets.is_empty() {
                 log::trace!("(World {}): No progressions found", chosen_world_index);
             } else {
-                break (chosen_world_index, itemsets);
+                break (chosen_world_index, world_slots, itemsets);
             }
         } else {
             if world_contexts
@@ -923,7 +1349,7 @@ where
                         world_context.spawn
                     );
                 }
-                return Err(String::from("Failed to reach anything from spawn location"));
+                return Err(String::from("Failed to reach anything from spawn location").into());
             }
 
             #[cfg(feature = "log")]
@@ -964,22 +1390,30 @@ where
                 );
             }
 
-            return Err(String::from("Failed to reach all locations"));
+            return Err(String::from("Failed to reach all locations").into());
         }
     };
 
+    observe(
+        context.observer,
+        GenerationEvent::ForcedProgression {
+            world_index: target_world_index,
+        },
+    )?;
+
     // TODO would it help variety if we didn't filter here? (Applies to spawn_progression as well)
     requirement::filter_redundancies(&mut itemsets);
     let progression = pick_progression(
         target_world_index,
         &itemsets,
         slots,
+        world_slots,
         reach_context,
         world_contexts,
         context,
     )?;
 
-    for (item, amount) in &progression.items {
+    for (item, amount) in progression.sorted_items() {
         let items =
             split_progression_item(target_world_index, item, *amount, world_contexts, context);
 
@@ -1030,24 +1464,46 @@ where
                     .inventory
                     .item_count() as usize
         {
-            let target_world_context = &mut world_contexts[target_world_index];
-
-            if let Some(item) = target_world_context
+            let target_world_context = &world_contexts[target_world_index];
+            let placement_weights = &target_world_context.world.player.settings.placement_weights;
+            let progress = target_world_context.progress();
+            let weight = |item: &Item| placement_weights.item_weight(item, progress);
+            let difficulty = target_world_context.world.player.settings.difficulty;
+            let item = target_world_context
                 .world
                 .pool
-                .choose_random(origin_world_index != target_world_index, context.rng)
-            {
-                let item = item.clone();
-                let is_progression =
-                    item.is_progression(target_world_context.world.player.settings.difficulty);
-                target_world_context.world.pool.remove(&item, 1);
-                target_world_context.world.grant_player(item.clone(), 1);
+                .choose_random(
+                    |item| {
+                        (origin_world_index == target_world_index
+                            || context.sharing.may_share(item, difficulty))
+                            && violated_constraint(
+                                item,
+                                node,
+                                origin_world_index,
+                                target_world_index,
+                                world_contexts,
+                            )
+                            .is_none()
+                    },
+                    if placement_weights.has_neutral_items() {
+                        None
+                    } else {
+                        Some(&weight)
+                    },
+                    context.rng,
+                )
+                .cloned();
+
+            if let Some(item) = item {
+                let is_progression = item.is_progression(difficulty);
+                take_from_pool(target_world_index, &item, world_contexts, context);
                 place_item(
                     origin_world_index,
                     target_world_index,
                     node,
                     false,
                     false,
+                    true,
                     item,
                     world_contexts,
                     context,
@@ -1065,10 +1521,11 @@ where
 
     let origin_world_context = &mut world_contexts[origin_world_index];
 
-    let amount = origin_world_context.spirit_light_rng.sample(context.rng);
-    let item = Item::SpiritLight(amount);
+    let item = origin_world_context.filler_item(Some(node), context.rng);
 
-    origin_world_context.world.pool.remove(&item, 1);
+    if let Item::SpiritLight(_) = item {
+        origin_world_context.world.pool.remove(&item, 1);
+    }
     origin_world_context.world.grant_player(item.clone(), 1);
     place_item(
         origin_world_index,
@@ -1076,6 +1533,7 @@ where
         node,
         false,
         false,
+        false,
         item,
         world_contexts,
         context,
@@ -1103,9 +1561,17 @@ where
     // force a couple placeholders at the start
     #[cfg(feature = "log")]
     let force = origin_world_context.placeholders.len() < 4;
-    if origin_world_context.placeholders.len() >= 4
-        && context.random_progression.sample(context.rng)
-    {
+    let random_progression = origin_world_context
+        .world
+        .player
+        .settings
+        .placement_weights
+        .progression
+        .random_placement_chance(
+            RANDOM_PROGRESSION,
+            f64::from(origin_world_context.progress()),
+        );
+    if origin_world_context.placeholders.len() >= 4 && context.rng.gen_bool(random_progression) {
         return random_item_placement(origin_world_index, node, world_contexts, context);
     }
 
@@ -1129,6 +1595,83 @@ where
     Ok(false)
 }
 
+fn place_plando<R, I>(
+    world_contexts: &mut [WorldContext<'_, '_>],
+    context: &mut GeneratorContext<'_, R, I>,
+) -> Result<(), String>
+where
+    R: Rng,
+    I: Iterator<Item = u16>,
+{
+    for world_index in 0..context.world_count {
+        let plando =
+            plando_nodes(&world_contexts[world_index].world).map_err(|err| err.to_string())?;
+
+        for (node, item) in plando {
+            log::trace!(
+                "(World {}): Placing plando {} at {}",
+                world_index,
+                item,
+                node
+            );
+
+            // Preplaced locations aren't counted as shop slots, but place_item assumes they are
+            if node.trigger().unwrap().identifier.is_purchasable() {
+                world_contexts[world_index].shop_slots += 1;
+            }
+
+            place_item(
+                world_index,
+                world_index,
+                node,
+                false,
+                false,
+                false,
+                item.clone(),
+                world_contexts,
+                context,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Makes sure the plando placements don't lock any locations that would be reachable otherwise
+fn check_plando_reach(world: &World) -> Result<(), SettingsError> {
+    let plando = plando_nodes(world)?;
+    if plando.is_empty() {
+        return Ok(());
+    }
+
+    let mut unlocked_world = world.clone();
+    for (node, item) in &plando {
+        unlocked_world.preplacements.remove(node.trigger().unwrap());
+        unlocked_world.pool.grant((*item).clone(), 1);
+    }
+
+    let reachable_locations = total_reach_check(world).map_err(SettingsError::InvalidPlando)?;
+    let locked_locations = total_reach_check(&unlocked_world)
+        .map_err(SettingsError::InvalidPlando)?
+        .into_iter()
+        .filter(|node| {
+            !reachable_locations
+                .iter()
+                .any(|reachable| reachable.index() == node.index())
+        })
+        .map(|node| node.identifier())
+        .collect::<Vec<_>>();
+
+    if locked_locations.is_empty() {
+        Ok(())
+    } else {
+        Err(SettingsError::InvalidPlando(format!(
+            "The plando placements make these locations unreachable: {}",
+            locked_locations.join(", ")
+        )))
+    }
+}
+
 #[inline]
 fn one_xp<R, I>(
     world_contexts: &mut [WorldContext<'_, '_>],
@@ -1160,6 +1703,7 @@ where
                 node,
                 false,
                 false,
+                false,
                 Item::SpiritLight(1),
                 world_contexts,
                 context,
@@ -1180,12 +1724,19 @@ where
  * this gives us a nice shallow parabola with some randomness but not so much that you can't tell approximately when a pickup was placed
  */
 struct SpiritLightAmounts {
+    mode: SpiritLightMode,
     factor: f32,
     noise: Uniform<f32>,
     index: usize,
+    /// Spirit Light left to distribute in [`SpiritLightMode::Budget`]
+    remaining: u32,
+    remaining_slots: usize,
+    /// Average amount per slot in [`SpiritLightMode::ZoneScaled`]
+    average: f32,
 }
 impl SpiritLightAmounts {
     fn new(
+        mode: SpiritLightMode,
         spirit_light_pool: f32,
         spirit_light_slots: f32,
         random_low: f32,
@@ -1197,20 +1748,48 @@ impl SpiritLightAmounts {
                 + spirit_light_slots / 6.0);
         let noise = Uniform::new_inclusive(random_low, random_high);
 
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
         SpiritLightAmounts {
+            mode,
             factor,
             noise,
             index: 0,
+            remaining: spirit_light_pool as u32,
+            remaining_slots: spirit_light_slots as usize,
+            average: spirit_light_pool / spirit_light_slots.max(1.),
         }
     }
-    fn sample(&mut self, rng: &mut impl Rng) -> u32 {
+    /// Corrects the estimated amount of slots that will still hold Spirit Light
+    fn set_remaining_slots(&mut self, slots: usize) {
+        self.remaining_slots = slots;
+    }
+    /// Chooses the next amount of Spirit Light to place
+    ///
+    /// `progress` is how far into the seed the location holding the Spirit Light was reached
+    fn sample(&mut self, progress: f32, rng: &mut impl Rng) -> u32 {
         #[allow(clippy::cast_precision_loss)]
-        let amount =
-            (self.factor * self.index.pow(2) as f32 + 50.0 * self.noise.sample(rng)).round();
+        let amount = match self.mode {
+            SpiritLightMode::Budget => {
+                let amount = if self.remaining_slots <= 1 {
+                    self.remaining as f32
+                } else {
+                    self.remaining as f32 / self.remaining_slots as f32 * self.noise.sample(rng)
+                };
+                amount.max(1.)
+            }
+            SpiritLightMode::ZoneScaled => {
+                (self.average * (0.25 + 1.5 * progress) * self.noise.sample(rng)).max(1.)
+            }
+            _ => self.factor * self.index.pow(2) as f32 + 50.0 * self.noise.sample(rng),
+        }
+        .round();
         self.index += 1;
 
         #[allow(clippy::cast_possible_truncation)]
-        util::float_to_int(amount).unwrap_or(u32::MAX)
+        let amount = util::float_to_int(amount).unwrap_or(u32::MAX);
+        self.remaining = self.remaining.saturating_sub(amount);
+        self.remaining_slots = self.remaining_slots.saturating_sub(1);
+        amount
     }
 }
 
@@ -1223,10 +1802,21 @@ where
     I: Iterator<Item = u16>,
 {
     let mut shop_placeholders = vec![Vec::new(); context.world_count];
+    // Postponed shop locations only open once every remaining placeholder holds its Spirit Light
+    let mut required_spirit_light = vec![0; context.world_count];
 
     for world_index in 0..context.world_count {
         let world_context = &mut world_contexts[world_index];
 
+        let spirit_light_settings = &world_context.world.player.settings.spirit_light;
+        required_spirit_light[world_index] = (0..world_context.postponed_shops.len())
+            .map(|index| spirit_light_settings.shop_minimum(world_context.opened_shops + index))
+            .max()
+            .unwrap_or_default();
+        world_context
+            .placeholders
+            .append(&mut world_context.postponed_shops);
+
         world_context.placeholders.retain(|&node| {
             if node.trigger().unwrap().identifier.is_purchasable() {
                 shop_placeholders[world_index].push(node);
@@ -1248,8 +1838,15 @@ where
             .inventory
             .items
             .drain()
+            .sorted_unstable()
             .flat_map(|(item, amount)| vec![item; amount as usize])
             .collect::<Vec<_>>();
+        // The other worlds shouldn't place these again
+        for item in remaining.iter().filter(|item| context.shares_pool(item)) {
+            for world_context in &mut *world_contexts {
+                world_context.world.pool.remove(item, 1);
+            }
+        }
         log::trace!(
             "(World {}): Placing the remaining {} items randomly",
             target_world_index,
@@ -1261,25 +1858,45 @@ where
         let mut space_remaining = true;
         'outer: for item in remaining {
             if space_remaining {
-                let origin_world_indices = if item.is_multiworld_spread() {
-                    let mut indices = (0..context.world_count).collect::<Vec<_>>();
-                    indices.shuffle(context.rng);
-                    indices
-                } else {
-                    vec![target_world_index]
-                };
+                let origin_world_indices =
+                    if context.may_share(&item, &world_contexts[target_world_index]) {
+                        let mut indices = (0..context.world_count).collect::<Vec<_>>();
+                        indices.shuffle(context.rng);
+                        indices
+                    } else {
+                        vec![target_world_index]
+                    };
 
-                for origin_world_index in origin_world_indices {
-                    if let Some(node) = shop_placeholders[origin_world_index]
-                        .pop()
-                        .or_else(|| world_contexts[origin_world_index].placeholders.pop())
-                    {
+                for &origin_world_index in &origin_world_indices {
+                    let shop_slot = last_allowed_slot(
+                        &shop_placeholders[origin_world_index],
+                        &item,
+                        origin_world_index,
+                        target_world_index,
+                        world_contexts,
+                    )
+                    .map(|index| shop_placeholders[origin_world_index].remove(index));
+                    if let Some(node) = shop_slot.or_else(|| {
+                        last_allowed_slot(
+                            &world_contexts[origin_world_index].placeholders,
+                            &item,
+                            origin_world_index,
+                            target_world_index,
+                            world_contexts,
+                        )
+                        .map(|index| {
+                            world_contexts[origin_world_index]
+                                .placeholders
+                                .remove(index)
+                        })
+                    }) {
                         place_item(
                             origin_world_index,
                             target_world_index,
                             node,
                             true,
                             false,
+                            true,
                             item,
                             world_contexts,
                             context,
@@ -1288,6 +1905,16 @@ where
                     }
                 }
 
+                if origin_world_indices.iter().any(|&origin_world_index| {
+                    !shop_placeholders[origin_world_index].is_empty()
+                        || !world_contexts[origin_world_index].placeholders.is_empty()
+                }) {
+                    return Err(format!(
+                        "(World {}): Unable to place {} anywhere without violating the placement constraints",
+                        target_world_index, item
+                    ));
+                }
+
                 space_remaining = false;
 
                 log::warning!(
@@ -1312,6 +1939,7 @@ where
                     world_shop_placeholder,
                     true,
                     false,
+                    false,
                     Item::Resource(Resource::GorlekOre),
                     world_contexts,
                     context,
@@ -1326,11 +1954,23 @@ where
             world_index
         );
 
+        let world_context = &mut world_contexts[world_index];
+        let spirit_light_slots = world_context.placeholders.len()
+            + world_context
+                .unreachable_locations
+                .iter()
+                .filter(|node| !node.trigger().unwrap().identifier.is_purchasable())
+                .count();
+        world_context
+            .spirit_light_rng
+            .set_remaining_slots(spirit_light_slots);
+
+        let mut spirit_light = world_context.shop_currency();
         while let Some(placeholder) = world_contexts[world_index].placeholders.pop() {
-            let amount = world_contexts[world_index]
-                .spirit_light_rng
-                .sample(context.rng);
-            let item = Item::SpiritLight(amount);
+            let item = world_contexts[world_index].filler_item(Some(placeholder), context.rng);
+            if let Item::SpiritLight(amount) = item {
+                spirit_light += amount;
+            }
 
             place_item(
                 world_index,
@@ -1338,12 +1978,20 @@ where
                 placeholder,
                 true,
                 false,
+                false,
                 item,
                 world_contexts,
                 context,
             )?;
         }
 
+        if spirit_light < required_spirit_light[world_index] {
+            return Err(format!(
+                "(World {}): Unable to reach the minimum of {} Spirit Light before the remaining shop locations",
+                world_index, required_spirit_light[world_index]
+            ));
+        }
+
         if !world_contexts[world_index].unreachable_locations.is_empty() {
             log::trace!("(World {}): Filling unreachable locations", world_index);
         }
@@ -1354,10 +2002,7 @@ where
             {
                 Item::Resource(Resource::GorlekOre)
             } else {
-                let amount = world_contexts[world_index]
-                    .spirit_light_rng
-                    .sample(context.rng);
-                Item::SpiritLight(amount)
+                world_contexts[world_index].filler_item(Some(unreachable), context.rng)
             };
 
             place_item(
@@ -1366,6 +2011,7 @@ where
                 unreachable,
                 false,
                 false,
+                false,
                 item,
                 world_contexts,
                 context,
@@ -1405,6 +2051,19 @@ fn total_reach_check<'a>(world: &World<'a, '_>) -> Result<Vec<&'a Node>, String>
         if new_reachable_count > total_reachable_count {
             total_reachable_count = new_reachable_count;
         } else {
+            for goal in &world.goals {
+                if let Goal::Custom(goal) = goal {
+                    let met =
+                        goal.met_states(|identifier| finished_world.get_uber_state(identifier));
+                    if met < goal.required {
+                        return Err(format!(
+                            "Goal {} requires {} of its states, but only {} can be reached on these settings",
+                            goal.name, goal.required, met
+                        ));
+                    }
+                }
+            }
+
             reachable_locations.retain(|&node| node.can_place());
             return Ok(reachable_locations);
         }
@@ -1446,28 +2105,32 @@ where
             .world
             .pool
             .inventory
-            .items
-            .iter()
+            .sorted_items()
+            .into_iter()
             .filter(|(item, _)| matches!(item, Item::UberState(_)))
             .flat_map(|(item, amount)| vec![item.clone(); *amount as usize])
             .collect::<Vec<_>>();
 
         'outer: for item in uber_state_items {
-            let mut origin_world_indices = (0..context.world_count).collect::<Vec<_>>();
-            origin_world_indices.shuffle(context.rng);
+            let origin_world_indices =
+                if context.may_share(&item, &world_contexts[target_world_index]) {
+                    let mut indices = (0..context.world_count).collect::<Vec<_>>();
+                    indices.shuffle(context.rng);
+                    indices
+                } else {
+                    vec![target_world_index]
+                };
 
             for origin_world_index in origin_world_indices {
                 if let Some(node) = world_contexts[origin_world_index].placeholders.pop() {
-                    let target_world_context = &mut world_contexts[target_world_index];
-
-                    target_world_context.world.pool.remove(&item, 1);
-                    target_world_context.world.grant_player(item.clone(), 1);
+                    take_from_pool(target_world_index, &item, world_contexts, context);
                     place_item(
                         origin_world_index,
                         target_world_index,
                         node,
                         true,
                         false,
+                        true,
                         item,
                         world_contexts,
                         context,
@@ -1482,14 +2145,94 @@ where
     Ok(())
 }
 
+/// Places items with a [`PlacementConstraint::WithinFirstReachable`] before the newly reached locations run past the limit
+fn place_early_items<'a, R, I>(
+    needs_placement: &mut [Vec<&'a Node>],
+    world_contexts: &mut [WorldContext<'a, '_>],
+    context: &mut GeneratorContext<'_, R, I>,
+) -> Result<(), String>
+where
+    R: Rng,
+    I: Iterator<Item = u16>,
+{
+    for world_index in 0..context.world_count {
+        let world_context = &world_contexts[world_index];
+        let reached_count = world_context.reached_count;
+        let reached_before = reached_count - needs_placement[world_index].len();
+
+        let early_items = world_context
+            .world
+            .player
+            .settings
+            .placement_constraints
+            .iter()
+            .filter_map(|constraint| match constraint {
+                PlacementConstraint::WithinFirstReachable { item, locations }
+                    if reached_before < *locations && reached_count >= *locations =>
+                {
+                    Some((item.clone(), *locations))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        for (item, locations) in early_items {
+            let amount = world_contexts[world_index].world.pool.inventory.get(&item);
+            for _ in 0..amount {
+                log::trace!(
+                    "(World {}): Placing {} before exceeding {} reachable locations",
+                    world_index,
+                    item,
+                    locations
+                );
+
+                let world_needs_placement = &needs_placement[world_index];
+                let index = (0..world_needs_placement.len())
+                    .filter(|&index| {
+                        violated_constraint(
+                            &item,
+                            world_needs_placement[index],
+                            world_index,
+                            world_index,
+                            world_contexts,
+                        )
+                        .is_none()
+                    })
+                    .choose(context.rng)
+                    .ok_or_else(|| {
+                        format!(
+                            "(World {}): Unable to place {} within the first {} reachable locations without violating the placement constraints",
+                            world_index, item, locations
+                        )
+                    })?;
+                let node = needs_placement[world_index].remove(index);
+
+                take_from_pool(world_index, &item, world_contexts, context);
+                place_item(
+                    world_index,
+                    world_index,
+                    node,
+                    false,
+                    false,
+                    true,
+                    item.clone(),
+                    world_contexts,
+                    context,
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
 fn generate_placements_from_spawn<'graph, 'settings>(
     worlds: Vec<World<'graph, 'settings>>,
+    sharing: MultiworldSharing,
     spawns: Vec<&'graph Node>,
+    observer: &dyn GenerationObserver,
     rng: &mut impl Rng,
-) -> Result<(Vec<SeedWorld<'graph, 'settings>>, SeedSpoiler), String> {
-    // TODO enforce a max total price for shops
-    let price_range = Uniform::new_inclusive(0.75, 1.25);
-
+) -> Result<(Vec<SeedWorld<'graph, 'settings>>, SeedSpoiler), AttemptFailure> {
     let mut world_contexts = build_world_contexts(worlds, &spawns, rng)?;
 
     let total_reachable_count: usize = world_contexts
@@ -1503,11 +2246,12 @@ fn generate_placements_from_spawn<'graph, 'settings>(
         multiworld_state_index: 0..,
         spoiler_groups: Vec::new(),
         current_spoiler_group: SpoilerGroup::default(),
-        price_range,
-        random_progression: Bernoulli::new(RANDOM_PROGRESSION).unwrap(),
         rng,
+        observer,
+        sharing,
     };
 
+    place_plando(&mut world_contexts, &mut context)?;
     one_xp(&mut world_contexts, &mut context)?;
     for world_index in 0..context.world_count {
         if let Some(amount) = world_contexts[world_index].world_tour {
@@ -1521,6 +2265,13 @@ fn generate_placements_from_spawn<'graph, 'settings>(
         context.finalize_spoiler_group();
 
         let mut reach_context = progression_check(&mut world_contexts, &mut context)?;
+        observe(
+            context.observer,
+            GenerationEvent::PlacementStep {
+                reached: total_reachable_count - reach_context.unreached_count,
+                total: total_reachable_count,
+            },
+        )?;
 
         force_keystones(
             &reach_context.reachable_states,
@@ -1538,10 +2289,12 @@ fn generate_placements_from_spawn<'graph, 'settings>(
                     let node_index = node.index();
 
                     node.trigger().map_or(false, |trigger| {
-                        !world_context
+                        // Plando locations already hold their placement, but still have to be collected like preplacements
+                        (!world_context
                             .placements
                             .iter()
                             .any(|placement| &placement.trigger == trigger)
+                            || world_context.world.preplacements.contains_key(trigger))
                             && !world_context
                                 .placeholders
                                 .iter()
@@ -1553,6 +2306,10 @@ fn generate_placements_from_spawn<'graph, 'settings>(
                             && !reserved_slots.iter().any(|&(reserved_world, node)| {
                                 reserved_world == world_index && node.index() == node_index
                             })
+                            && !world_context
+                                .postponed_shops
+                                .iter()
+                                .any(|&postponed| postponed.index() == node_index)
                     })
                 });
                 // We need to ensure that if multiple quest steps are unlocked at the same time, their uberState values are set in order.
@@ -1593,9 +2350,34 @@ fn generate_placements_from_spawn<'graph, 'settings>(
                     if preplaced {
                         world_context.collected_preplacements.push(node.index());
                     } else if node.can_place() {
-                        world_needs_placement.push(*node);
+                        if node.trigger().unwrap().identifier.is_purchasable()
+                            && !world_context
+                                .world
+                                .player
+                                .settings
+                                .spirit_light
+                                .shop_minimums
+                                .is_empty()
+                        {
+                            world_context.postponed_shops.push(*node);
+                        } else {
+                            world_needs_placement.push(*node);
+                        }
                     }
                 }
+                world_context.open_shops(&mut world_needs_placement);
+
+                let progress = world_context.progress();
+                for node in &world_needs_placement {
+                    world_context
+                        .reached_before
+                        .entry(node.index())
+                        .or_insert(world_context.reached_count);
+                    if let Some(zone) = node.zone() {
+                        world_context.zone_progress.entry(zone).or_insert(progress);
+                    }
+                }
+                world_context.reached_count += world_needs_placement.len();
 
                 let locations = world_needs_placement
                     .iter()
@@ -1610,6 +2392,8 @@ fn generate_placements_from_spawn<'graph, 'settings>(
             })
             .collect::<Vec<_>>();
 
+        place_early_items(&mut needs_placement, &mut world_contexts, &mut context)?;
+
         if reach_context.unreached_count == 0 {
             log::trace!("All locations reached");
 
@@ -1646,7 +2430,12 @@ fn generate_placements_from_spawn<'graph, 'settings>(
                 .unzip();
             let groups = context.spoiler_groups;
 
-            let spoiler = SeedSpoiler { spawns, groups };
+            let spoiler = SeedSpoiler {
+                spawns,
+                groups,
+                doors: vec![],
+                sharing,
+            };
             return Ok((seed_worlds, spoiler));
         }
 
@@ -1823,7 +2612,7 @@ fn build_world_contexts<'a, 'b>(
         if let Some(amount) = world_tour { spirit_light_slots -= amount; }
         log::trace!("(World {}): Estimated {}/{} slots for Spirit Light", world_index, spirit_light_slots, world_slots);
 
-        let spirit_light_rng = SpiritLightAmounts::new(world.pool.spirit_light as f32, spirit_light_slots as f32, 0.75, 1.25);
+        let spirit_light_rng = SpiritLightAmounts::new(world.player.settings.spirit_light.mode, world.pool.spirit_light as f32, spirit_light_slots as f32, 0.75, 1.25);
         let random_spirit_light = Bernoulli::new(spirit_light_slots as f64 / world_slots as f64).unwrap();
 
         let shop_slots = world.graph.nodes.iter().filter(|&node|
@@ -1832,6 +2621,10 @@ fn build_world_contexts<'a, 'b>(
                 && !world.preplacements.contains_key(trigger)
         )).count();
 
+        // The spawn slots are available from the very start
+        let mut reached_before = FxHashMap::default();
+        reached_before.insert(world.graph.spawn_pickup_node.index(), 0);
+
         Ok(WorldContext {
             world,
             spawn,
@@ -1846,6 +2639,13 @@ fn build_world_contexts<'a, 'b>(
             random_spirit_light,
             shop_slots,
             world_tour,
+            reached_before,
+            reached_count: 0,
+            zone_progress: FxHashMap::default(),
+            postponed_shops: Vec::new(),
+            opened_shops: 0,
+            shop_price_total: 0,
+            progression_shop_total: 0,
         })
     }).collect::<Result<Vec<_>, String>>()
 }
@@ -1854,7 +2654,7 @@ fn pick_spawn<'a>(
     graph: &'a Graph,
     world_settings: &WorldSettings,
     rng: &mut impl Rng,
-) -> Result<&'a Node, String> {
+) -> Result<&'a Node, SettingsError> {
     let spawn = match &world_settings.spawn {
         Spawn::Random => {
             let spawns = world_settings.difficulty.spawn_locations();
@@ -1863,22 +2663,22 @@ fn pick_spawn<'a>(
                 .iter()
                 .filter(|&node| spawns.contains(&node.identifier()))
                 .choose(rng)
-                .ok_or_else(|| String::from("No valid spawn locations available"))?
+                .ok_or(SettingsError::NoSpawnLocations)?
         }
         Spawn::FullyRandom => graph
             .nodes
             .iter()
             .filter(|node| node.can_spawn())
             .choose(rng)
-            .ok_or_else(|| String::from("No valid spawn locations available"))?,
+            .ok_or(SettingsError::NoSpawnLocations)?,
         Spawn::Set(spawn_loc) => {
             let node = graph
                 .nodes
                 .iter()
                 .find(|&node| node.identifier() == spawn_loc)
-                .ok_or_else(|| format!("Spawn {} not found", spawn_loc))?;
+                .ok_or_else(|| SettingsError::SpawnNotFound(spawn_loc.clone()))?;
             if !node.can_spawn() {
-                return Err(format!("{} is not a valid spawn", spawn_loc));
+                return Err(SettingsError::InvalidSpawn(spawn_loc.clone()));
             }
             node
         }
@@ -1886,46 +2686,373 @@ fn pick_spawn<'a>(
     Ok(spawn)
 }
 
+/// Rejects constraints that can be recognized as unsatisfiable before attempting to place items
+fn validate_placement_constraints(world: &World) -> Result<(), SettingsError> {
+    let settings = world.player.settings;
+    let unsatisfiable = |constraints: &[&PlacementConstraint]| {
+        SettingsError::UnsatisfiablePlacementConstraint(constraints.iter().join(" and "))
+    };
+    let location_zones = world
+        .graph
+        .nodes
+        .iter()
+        .chain(iter::once(&world.graph.spawn_pickup_node))
+        .filter(|node| node.can_place())
+        .filter_map(Node::zone)
+        .collect::<FxHashSet<_>>();
+
+    for constraint in &settings.placement_constraints {
+        let item = match constraint {
+            PlacementConstraint::NotInZone { item, .. } => item,
+            PlacementConstraint::NoSkillsInShops => continue,
+            PlacementConstraint::WithinFirstReachable { item, locations } => {
+                if *locations == 0 {
+                    return Err(unsatisfiable(&[constraint]));
+                }
+                item
+            }
+        };
+
+        let in_pool = match item {
+            Item::SpiritLight(_) => world.pool.spirit_light > 0,
+            _ => world.pool.inventory.has_any(item),
+        };
+        let plando = settings
+            .plando
+            .placements
+            .iter()
+            .any(|placement| &placement.item == item);
+        if !in_pool && !plando {
+            return Err(SettingsError::UnsatisfiablePlacementConstraint(format!(
+                "{constraint}, but there is no {item} in the item pool"
+            )));
+        }
+
+        // Together, the constraints for this item may forbid every zone
+        let item_constraints = settings
+            .placement_constraints
+            .iter()
+            .filter(|other| match other {
+                PlacementConstraint::NotInZone { item: other, .. } => other == item,
+                PlacementConstraint::NoSkillsInShops => matches!(item, Item::Skill(_)),
+                PlacementConstraint::WithinFirstReachable { .. } => false,
+            })
+            .collect::<Vec<_>>();
+        let allows_zone = |zone: &Zone| {
+            !item_constraints.iter().any(|other| match other {
+                PlacementConstraint::NotInZone { zone: other, .. } => other == zone,
+                PlacementConstraint::NoSkillsInShops => *zone == Zone::Shop,
+                PlacementConstraint::WithinFirstReachable { .. } => false,
+            })
+        };
+        if !location_zones.iter().any(allows_zone) {
+            return Err(unsatisfiable(&item_constraints));
+        }
+    }
+
+    Ok(())
+}
+
+/// Rejects shop pricing that can't be applied to the shop locations of this world
+fn validate_shop_pricing(world: &World) -> Result<(), SettingsError> {
+    let pricing = &world.player.settings.shop_pricing;
+    pricing.validate()?;
+
+    if let Some(max_total) = pricing.max_total {
+        // Every shop location costs at least 1
+        let shop_slots = world
+            .graph
+            .nodes
+            .iter()
+            .filter_map(Node::trigger)
+            .filter(|trigger| {
+                trigger.identifier.is_purchasable() && !world.preplacements.contains_key(trigger)
+            })
+            .count();
+        if (max_total as usize) < shop_slots {
+            return Err(SettingsError::InvalidShopPricing(format!(
+                "The maximum total price of {max_total} can't cover {shop_slots} shop locations costing at least 1 each"
+            )));
+        }
+    }
+
+    Ok(())
+}
+
 pub(super) fn generate_placements<'graph, 'settings>(
     graph: &'graph Graph,
     worlds: &[World<'graph, 'settings>],
+    sharing: MultiworldSharing,
+    options: &GeneratorOptions,
+    observer: &dyn GenerationObserver,
     rng: &mut impl Rng,
-) -> Result<(Vec<SeedWorld<'graph, 'settings>>, SeedSpoiler), String> {
-    let mut index = 0;
-    loop {
-        let spawn_locs = worlds
-            .iter()
-            .map(|world| pick_spawn(graph, world.player.settings, rng))
-            .collect::<Result<Vec<_>, String>>()?;
-        #[cfg(feature = "log")]
-        let identifiers = spawn_locs
-            .iter()
-            .map(|spawn_loc| spawn_loc.identifier())
-            .collect::<Vec<_>>();
-        log::trace!("Spawning on {}", identifiers.join(", "));
+) -> Result<(Vec<SeedWorld<'graph, 'settings>>, SeedSpoiler), SeedgenError> {
+    for world in worlds {
+        validate_placement_constraints(world)?;
+        world.player.settings.placement_weights.validate()?;
+        validate_shop_pricing(world)?;
+        check_plando_reach(world)?;
+    }
+
+    let retries = options.retries.get();
+    match options.parallel_attempts {
+        None => generate_placements_serial(graph, worlds, sharing, retries, observer, rng),
+        Some(threads) => {
+            generate_placements_parallel(graph, worlds, sharing, retries, threads, observer, rng)
+        }
+    }
+}
+
+type PlacementResult<'graph, 'settings> = (Vec<SeedWorld<'graph, 'settings>>, SeedSpoiler);
 
-        match generate_placements_from_spawn(worlds.to_owned(), spawn_locs, rng) {
+/// Why [`generate_placements_from_spawn`] failed
+enum AttemptFailure {
+    Failed(String),
+    Cancelled,
+}
+impl From<String> for AttemptFailure {
+    fn from(reason: String) -> AttemptFailure {
+        AttemptFailure::Failed(reason)
+    }
+}
+
+fn observe(
+    observer: &dyn GenerationObserver,
+    event: GenerationEvent,
+) -> Result<(), AttemptFailure> {
+    match observer.on_event(event) {
+        ObserverAction::Continue => Ok(()),
+        ObserverAction::Cancel => Err(AttemptFailure::Cancelled),
+    }
+}
+
+fn placement_attempt<'graph, 'settings>(
+    graph: &'graph Graph,
+    worlds: &[World<'graph, 'settings>],
+    sharing: MultiworldSharing,
+    (attempt, retries): (u16, u16),
+    observer: &dyn GenerationObserver,
+    rng: &mut impl Rng,
+) -> Result<Result<PlacementResult<'graph, 'settings>, PlacementAttempt>, SeedgenError> {
+    let cancelled = |_| SeedgenError::Cancelled;
+    if attempt > 0 {
+        observe(observer, GenerationEvent::RetryStarted { attempt, retries }).map_err(cancelled)?;
+    }
+
+    let spawn_locs = worlds
+        .iter()
+        .map(|world| pick_spawn(graph, world.player.settings, rng))
+        .collect::<Result<Vec<_>, _>>()?;
+    let spawns = spawn_locs
+        .iter()
+        .map(|spawn_loc| spawn_loc.identifier().to_string())
+        .collect::<Vec<_>>();
+    log::trace!("Spawning on {}", spawns.join(", "));
+    for (world_index, spawn) in spawns.iter().enumerate() {
+        observe(
+            observer,
+            GenerationEvent::SpawnChosen { world_index, spawn },
+        )
+        .map_err(cancelled)?;
+    }
+
+    match generate_placements_from_spawn(worlds.to_owned(), sharing, spawn_locs, observer, rng) {
+        Ok(seed) => Ok(Ok(seed)),
+        Err(AttemptFailure::Failed(reason)) => {
+            log::warning!("{}\nRetrying...", reason);
+            Ok(Err(PlacementAttempt { spawns, reason }))
+        }
+        Err(AttemptFailure::Cancelled) => Err(SeedgenError::Cancelled),
+    }
+}
+
+fn log_success(
+    index: u16,
+    #[cfg_attr(not(feature = "log"), allow(unused_variables))] retries: u16,
+) {
+    if index > 0 {
+        log::info!(
+            "Generated seed after {} tries{}",
+            index + 1,
+            if index < retries / 2 { "" } else { " (phew)" }
+        );
+    }
+}
+
+fn generate_placements_serial<'graph, 'settings>(
+    graph: &'graph Graph,
+    worlds: &[World<'graph, 'settings>],
+    sharing: MultiworldSharing,
+    retries: u16,
+    observer: &dyn GenerationObserver,
+    rng: &mut impl Rng,
+) -> Result<PlacementResult<'graph, 'settings>, SeedgenError> {
+    let mut attempts = vec![];
+    for index in 0..retries {
+        match placement_attempt(graph, worlds, sharing, (index, retries), observer, rng)? {
             Ok(seed) => {
-                if index > 0 {
-                    log::info!(
-                        "Generated seed after {} tries{}",
-                        index + 1,
-                        if index < RETRIES / 2 { "" } else { " (phew)" }
-                    );
+                log_success(index, retries);
+                return Ok(seed);
+            }
+            Err(attempt) => attempts.push(attempt),
+        }
+    }
+
+    Err(PlacementError { attempts }.into())
+}
+
+/// Runs attempts on up to `threads` threads, each attempt with its own random state derived from `rng`
+///
+/// Attempts are handed out in order and the successful attempt with the lowest index wins,
+/// so the result does not depend on the amount of threads or on which attempt finishes first.
+/// Errors other than a failed attempt, such as cancellation, end generation the same way a success does
+fn generate_placements_parallel<'graph, 'settings>(
+    graph: &'graph Graph,
+    worlds: &[World<'graph, 'settings>],
+    sharing: MultiworldSharing,
+    retries: u16,
+    threads: NonZeroUsize,
+    observer: &dyn GenerationObserver,
+    rng: &mut impl Rng,
+) -> Result<PlacementResult<'graph, 'settings>, SeedgenError> {
+    let base_seed: u64 = rng.gen();
+    let next_index = AtomicU16::new(0);
+    let first_finished = AtomicU16::new(u16::MAX);
+    let results = Mutex::new(FxHashMap::default());
+
+    thread::scope(|scope| {
+        for _ in 0..threads.get().min(retries as usize) {
+            scope.spawn(|| loop {
+                let index = next_index.fetch_add(1, Ordering::SeqCst);
+                // Attempts after a success can't win anymore, but every attempt before it has to finish
+                if index >= retries || index > first_finished.load(Ordering::SeqCst) {
+                    break;
                 }
 
+                let mut rng: StdRng = Seeder::from((base_seed, index)).make_rng();
+                let result =
+                    placement_attempt(graph, worlds, sharing, (index, retries), observer, &mut rng);
+                if !matches!(result, Ok(Err(_))) {
+                    first_finished.fetch_min(index, Ordering::SeqCst);
+                }
+                results.lock().unwrap().insert(index, result);
+            });
+        }
+    });
+
+    let mut results = results.into_inner().unwrap();
+    let mut attempts = vec![];
+    for index in 0..retries {
+        let Some(result) = results.remove(&index) else {
+            break;
+        };
+        match result? {
+            Ok(seed) => {
+                log_success(index, retries);
                 return Ok(seed);
             }
-            #[cfg_attr(not(feature = "log"), allow(unused_variables))]
-            Err(err) => log::warning!("{}\nRetrying...", err),
+            Err(attempt) => attempts.push(attempt),
         }
+    }
 
-        index += 1;
-        if index == RETRIES {
-            return Err(format!(
-                "All {} attempts to generate a seed failed :(",
-                RETRIES
-            ));
-        }
+    Err(PlacementError { attempts }.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::files::FILE_SYSTEM_ACCESS;
+    use crate::generate_seed;
+    use crate::generator::spoiler::SpoilerPlacement;
+    use crate::item::Skill;
+    use crate::settings::UniverseSettings;
+    use crate::tests::test_graph;
+
+    fn multiworld_seed(sharing: MultiworldSharing) -> (Vec<String>, Vec<SpoilerPlacement>) {
+        let universe_settings = UniverseSettings {
+            seed: format!("multiworld_sharing_{sharing}"),
+            world_settings: vec![WorldSettings::default(); 2],
+            multiworld_sharing: sharing,
+            ..UniverseSettings::default()
+        };
+        let graph = test_graph(&universe_settings);
+
+        let seed = generate_seed(&graph, &FILE_SYSTEM_ACCESS, &universe_settings).unwrap();
+        assert_eq!(seed.spoiler.sharing, sharing);
+        let placements = seed
+            .spoiler
+            .groups
+            .into_iter()
+            .flat_map(|group| group.placements)
+            .collect::<Vec<_>>();
+        // Spirit Light always stays in its own world
+        assert!(placements.iter().all(|placement| {
+            !matches!(placement.item, Item::SpiritLight(_))
+                || placement.origin_world_index == placement.target_world_index
+        }));
+        (seed.worlds[0].flags.clone(), placements)
+    }
+    fn cross_world(placements: &[SpoilerPlacement]) -> Vec<&SpoilerPlacement> {
+        placements
+            .iter()
+            .filter(|placement| placement.origin_world_index != placement.target_world_index)
+            .collect()
+    }
+
+    #[test]
+    fn shuffled_sharing() {
+        let (_, placements) = multiworld_seed(MultiworldSharing::Shuffled);
+        assert!(placements.iter().all(|placement| !placement.shared));
+        assert!(cross_world(&placements)
+            .iter()
+            .any(|placement| placement.item.is_progression(Difficulty::Moki)));
+        // Every world has its own Bash
+        let bash_targets = placements
+            .iter()
+            .filter(|placement| placement.item == Item::Skill(Skill::Bash))
+            .map(|placement| placement.target_world_index)
+            .sorted()
+            .collect::<Vec<_>>();
+        assert_eq!(bash_targets, [0, 1]);
+    }
+
+    #[test]
+    fn local_progression_sharing() {
+        let (_, placements) = multiworld_seed(MultiworldSharing::LocalProgression);
+        let cross_world = cross_world(&placements);
+        assert!(!cross_world.is_empty());
+        assert!(cross_world
+            .iter()
+            .all(|placement| !placement.item.is_progression(Difficulty::Moki)));
+    }
+
+    #[test]
+    fn local_skills_sharing() {
+        let (_, placements) = multiworld_seed(MultiworldSharing::LocalSkills);
+        let cross_world = cross_world(&placements);
+        assert!(cross_world
+            .iter()
+            .any(|placement| placement.item.is_progression(Difficulty::Moki)));
+        assert!(cross_world
+            .iter()
+            .all(|placement| !matches!(placement.item, Item::Skill(_))));
+    }
+
+    #[test]
+    fn shared_pool_sharing() {
+        let (flags, placements) = multiworld_seed(MultiworldSharing::SharedPool);
+        assert!(flags.iter().any(|flag| flag == "Shared Pool"));
+        assert!(placements
+            .iter()
+            .filter(|placement| placement.shared)
+            .all(|placement| placement.item.is_multiworld_spread()));
+        // Both worlds draw the one Bash from the shared pool
+        let bash = placements
+            .iter()
+            .filter(|placement| placement.item == Item::Skill(Skill::Bash))
+            .collect::<Vec<_>>();
+        assert_eq!(bash.len(), 1);
+        assert!(bash[0].shared);
     }
 }
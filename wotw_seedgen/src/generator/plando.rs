@@ -0,0 +1,51 @@
+use crate::{
+    error::SettingsError,
+    item::Item,
+    world::{graph::Node, World},
+};
+
+/// Looks up the logic [`Node`] for every placement of the world's [`Plando`](crate::settings::Plando)
+pub(super) fn plando_nodes<'graph, 'settings>(
+    world: &World<'graph, 'settings>,
+) -> Result<Vec<(&'graph Node, &'settings Item)>, SettingsError> {
+    let graph = world.graph;
+    let settings = world.player.settings;
+
+    settings
+        .plando
+        .placements
+        .iter()
+        .map(|placement| {
+            let node = graph
+                .nodes
+                .iter()
+                .find(|node| node.identifier() == placement.location)
+                .filter(|node| node.can_place())
+                .ok_or_else(|| {
+                    SettingsError::InvalidPlando(format!(
+                        "{} is not a pickup location",
+                        placement.location
+                    ))
+                })?;
+            Ok((node, &placement.item))
+        })
+        .collect()
+}
+
+/// Preplaces the plando items and removes them from the item pool
+pub(super) fn preplace_plando(world: &mut World) -> Result<(), SettingsError> {
+    for (node, item) in plando_nodes(world)? {
+        let trigger = node.trigger().unwrap();
+        if world.preplacements.contains_key(trigger) {
+            return Err(SettingsError::InvalidPlando(format!(
+                "{} already has an item placed on it",
+                node.identifier()
+            )));
+        }
+
+        world.pool.remove(item, 1);
+        world.preplace(trigger.clone(), item.clone());
+    }
+
+    Ok(())
+}
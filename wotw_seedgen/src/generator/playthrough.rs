@@ -0,0 +1,338 @@
+use std::{
+    cmp::Reverse,
+    fmt::{self, Display, Write},
+};
+
+use itertools::Itertools;
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    header::Pickup,
+    uber_state::UberIdentifier,
+    verify::{parse_pickups, Replay},
+    world::graph::Node,
+    Item,
+};
+
+use super::{Seed, SpoilerPlacement};
+
+/// The minimal set of placements needed to beat a [`Seed`], ordered by when they can be collected
+///
+/// Unlike the [`SeedSpoiler`](super::SeedSpoiler), which follows the generation process, this only contains the items actually required to finish the game
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub struct Playthrough {
+    /// Each [`Sphere`] contains the required items that can be collected once everything from the previous spheres has been collected
+    pub spheres: Vec<Sphere>,
+}
+/// One step of a [`Playthrough`]
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub struct Sphere {
+    pub placements: Vec<SpoilerPlacement>,
+}
+
+impl Playthrough {
+    /// Serialize into json format
+    pub fn to_json(&self) -> String {
+        // This is safe because the Playthrough struct is known to serialize successfully
+        serde_json::to_string(&self).unwrap()
+    }
+}
+
+impl Display for Playthrough {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let multiworld = self.spheres.iter().any(|sphere| {
+            sphere.placements.iter().any(|placement| {
+                placement.origin_world_index > 0 || placement.target_world_index > 0
+            })
+        });
+
+        let mut longest_pickup = 0;
+        let spheres = self
+            .spheres
+            .iter()
+            .map(|sphere| {
+                sphere
+                    .placements
+                    .iter()
+                    .map(|placement| {
+                        let mut pickup = String::new();
+                        if multiworld {
+                            write!(pickup, "[{}] ", placement.target_world_index)?;
+                        }
+                        write!(pickup, "{}", placement.item_name)?;
+                        longest_pickup = longest_pickup.max(pickup.len());
+
+                        let mut location = String::new();
+                        if multiworld {
+                            write!(location, "[{}] ", placement.origin_world_index)?;
+                        }
+                        write!(location, "{}", placement.location.identifier)?;
+
+                        Ok((pickup, location))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for (index, placements) in spheres.into_iter().enumerate() {
+            writeln!(f, "Sphere {index}")?;
+            for (pickup, location) in placements {
+                writeln!(f, "    {pickup:<longest_pickup$}  {location}")?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Seed<'_, '_> {
+    /// Computes the [`Playthrough`] for this seed
+    ///
+    /// Starting from the complete seed, every progression item is removed unless the seed would no longer be beatable without it.
+    /// Spirit Light is never removed, so shop purchases may be listed without the Spirit Light needed to afford them.
+    ///
+    /// This replays the seed many times and may take a while
+    pub fn playthrough(&self) -> Result<Playthrough, String> {
+        let seed_files = self.seed_files()?;
+        let pickups = seed_files
+            .iter()
+            .map(|seed_file| parse_pickups(seed_file))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Multiworld items are sent by setting a shared uberState, which grants the item in the receiving world
+        let received = pickups
+            .iter()
+            .enumerate()
+            .flat_map(|(world_index, pickups)| {
+                pickups
+                    .iter()
+                    .filter(|pickup| {
+                        pickup.trigger.identifier.uber_group == MULTIWORLD_UBER_GROUP
+                            && !matches!(pickup.item, Item::Message(_))
+                    })
+                    .map(move |pickup| (pickup.trigger.identifier, (world_index, &pickup.item)))
+            })
+            .collect::<FxHashMap<_, _>>();
+
+        let context = PlaythroughContext {
+            seed: self,
+            seed_files: &seed_files,
+            pickups: &pickups,
+            received: &received,
+        };
+
+        let mut excluded = vec![FxHashSet::default(); self.worlds.len()];
+        let spheres = context
+            .replay(&excluded)?
+            .ok_or("Failed to compute playthrough: The seed is not beatable")?;
+
+        // Identical items are interchangeable, so instead of trying to remove each of them we only need to find out how many are required
+        let mut groups = FxHashMap::<_, Vec<_>>::default();
+        for (order, &(world_index, pickup_index)) in spheres.iter().flatten().enumerate() {
+            let item = context.received_item(
+                world_index,
+                &context.pickups[world_index][pickup_index].item,
+            );
+            groups
+                .entry(item)
+                .or_default()
+                .push((order, world_index, pickup_index));
+        }
+        let groups = groups
+            .into_values()
+            .sorted_unstable_by_key(|group| Reverse(group.last().map(|(order, ..)| *order)));
+
+        for group in groups {
+            // Assuming that fewer copies of an item will never make the seed easier to beat, search for the amount of copies that can be removed
+            let mut removable = 0;
+            let mut upper = group.len();
+            while removable < upper {
+                let amount = (removable + upper).div_ceil(2);
+                for &(_, world_index, pickup_index) in &group[group.len() - amount..] {
+                    excluded[world_index].insert(pickup_index);
+                }
+                if context.replay(&excluded)?.is_some() {
+                    removable = amount;
+                } else {
+                    upper = amount - 1;
+                }
+                for &(_, world_index, pickup_index) in &group[..group.len() - removable] {
+                    excluded[world_index].remove(&pickup_index);
+                }
+            }
+        }
+
+        // This replay is known to be beatable since every exclusion was checked
+        let spheres = context.replay(&excluded)?.unwrap();
+        let spheres = spheres
+            .into_iter()
+            .map(|sphere| {
+                sphere
+                    .into_iter()
+                    .map(|(world_index, pickup_index)| context.placement(world_index, pickup_index))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|placements| !placements.is_empty())
+            .map(|placements| Sphere { placements })
+            .collect();
+
+        Ok(Playthrough { spheres })
+    }
+}
+
+const MULTIWORLD_UBER_GROUP: u16 = 12;
+
+struct PlaythroughContext<'a, 'graph, 'settings> {
+    seed: &'a Seed<'graph, 'settings>,
+    seed_files: &'a [String],
+    pickups: &'a [Vec<Pickup>],
+    received: &'a FxHashMap<UberIdentifier, (usize, &'a Item)>,
+}
+impl PlaythroughContext<'_, '_, '_> {
+    /// Replays all worlds without the excluded pickups
+    ///
+    /// Returns the (world index, pickup index) of all progression pickups per sphere, or `None` if the seed is not beatable
+    #[allow(clippy::type_complexity)]
+    fn replay(
+        &self,
+        excluded: &[FxHashSet<usize>],
+    ) -> Result<Option<Vec<Vec<(usize, usize)>>>, String> {
+        let mut replays = self
+            .seed
+            .worlds
+            .iter()
+            .enumerate()
+            .map(|(index, world)| {
+                Replay::new(
+                    self.seed.graph,
+                    &self.seed_files[index],
+                    world.world_settings,
+                    &self.pickups[index],
+                    &excluded[index],
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut reached = vec![vec![]; replays.len()];
+
+        let mut spheres = vec![];
+        loop {
+            let mut sphere = vec![];
+            let mut collected_any = false;
+            for (world_index, replay) in replays.iter_mut().enumerate() {
+                let (world_reached, new) = replay.step();
+                reached[world_index] = world_reached;
+                collected_any |= !new.is_empty();
+                for node in new {
+                    sphere.extend(
+                        self.progression_pickups(world_index, node)
+                            .filter(|pickup_index| !excluded[world_index].contains(pickup_index))
+                            .map(|pickup_index| (world_index, pickup_index)),
+                    );
+                }
+            }
+            if !sphere.is_empty() {
+                spheres.push(sphere);
+            }
+
+            let shared_any = share_multiworld_states(&mut replays);
+            if !collected_any && !shared_any {
+                break;
+            }
+        }
+
+        for (replay, reached) in replays.iter().zip(&reached) {
+            if !replay.verification(reached)?.beatable {
+                return Ok(None);
+            }
+        }
+
+        Ok(Some(spheres))
+    }
+
+    /// Returns the indices of pickups on this location that might be required to beat the seed
+    fn progression_pickups<'b>(
+        &'b self,
+        world_index: usize,
+        node: &'b Node,
+    ) -> impl Iterator<Item = usize> + 'b {
+        let trigger = node.trigger();
+        self.pickups[world_index]
+            .iter()
+            .enumerate()
+            .filter(move |(_, pickup)| node.can_place() && Some(&pickup.trigger) == trigger)
+            .filter(move |(_, pickup)| {
+                let (target_world_index, item) = self.received_item(world_index, &pickup.item);
+                let difficulty = self.seed.worlds[target_world_index]
+                    .world_settings
+                    .difficulty;
+                !matches!(item, Item::SpiritLight(_))
+                    && (item.is_progression(difficulty) || matches!(item, Item::Relic(_)))
+            })
+            .map(|(index, _)| index)
+    }
+
+    /// Returns the world and item a pickup will end up as, resolving items sent to other worlds
+    fn received_item<'b>(&'b self, world_index: usize, item: &'b Item) -> (usize, &'b Item) {
+        match item {
+            Item::UberState(command) => self
+                .received
+                .get(&command.identifier)
+                .copied()
+                .unwrap_or((world_index, item)),
+            _ => (world_index, item),
+        }
+    }
+
+    fn placement(&self, world_index: usize, pickup_index: usize) -> SpoilerPlacement {
+        let pickup = &self.pickups[world_index][pickup_index];
+        let (target_world_index, item) = self.received_item(world_index, &pickup.item);
+        let location = self
+            .seed
+            .graph
+            .nodes
+            .iter()
+            .find(|&node| node.can_place() && node.trigger() == Some(&pickup.trigger))
+            .map(Node::summary)
+            .unwrap(); // progression_pickups only returns pickups on locations
+
+        SpoilerPlacement {
+            origin_world_index: world_index,
+            target_world_index,
+            shared: false,
+            location,
+            item: item.clone(),
+            item_name: item.to_string(),
+        }
+    }
+}
+
+/// Sets any multiworld uberStates that have been set in one world in every world
+///
+/// Returns `true` if any uberState changed
+fn share_multiworld_states(replays: &mut [Replay]) -> bool {
+    let shared = replays
+        .iter()
+        .flat_map(|replay| {
+            replay
+                .world
+                .uber_states()
+                .iter()
+                .filter(|(identifier, _)| identifier.uber_group == MULTIWORLD_UBER_GROUP)
+                .map(|(identifier, value)| (*identifier, *value))
+        })
+        .sorted_unstable_by_key(|(identifier, _)| *identifier)
+        .collect::<Vec<_>>();
+
+    let mut changed = false;
+    for replay in replays {
+        for &(identifier, value) in &shared {
+            if replay.world.get_uber_state(identifier) < value {
+                replay.world.set_uber_state(identifier, value);
+                changed = true;
+            }
+        }
+    }
+    changed
+}
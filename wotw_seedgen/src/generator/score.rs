@@ -0,0 +1,107 @@
+use rustc_hash::FxHashSet;
+
+use crate::item::{Item, Resource};
+
+use super::{SeedSpoiler, SpoilerGroup, SpoilerPlacement};
+
+/// A rough estimate of how long and tedious a world of a seed is to play, based on its [`SeedSpoiler`]
+///
+/// Higher scores mean a longer and more tedious seed.
+/// Scores are only meaningful when comparing seeds generated with similar settings
+///
+/// # Examples
+///
+/// ```
+/// # use wotw_seedgen::generator::SeedScore;
+/// let score = SeedScore {
+///     spheres: 20,
+///     zone_spread: 6,
+///     keystone_pressure: 4,
+///     spirit_light_farming: 800,
+///     forced_progressions: 5,
+/// };
+/// assert_eq!(score.total(), 20 + 6 * 2 + 4 + 800 / 100 + 5 * 3);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SeedScore {
+    /// How many steps of placements made new locations reachable
+    pub spheres: u32,
+    /// How many different zones hold the forced progression items
+    pub zone_spread: u32,
+    /// How many Keystones had to be forced to open reachable Keystone doors
+    pub keystone_pressure: u32,
+    /// How much Spirit Light had to be forced to afford purchases in shops
+    pub spirit_light_farming: u32,
+    /// How many items other than Keystones and Spirit Light had to be forced as progression
+    pub forced_progressions: u32,
+}
+
+impl SeedScore {
+    /// Combine all parts of the score into one number
+    pub fn total(&self) -> u32 {
+        self.spheres
+            + self.zone_spread * 2
+            + self.keystone_pressure
+            + self.spirit_light_farming / 100
+            + self.forced_progressions * 3
+    }
+}
+
+impl SeedSpoiler {
+    /// Calculate the [`SeedScore`] of a world
+    pub fn score(&self, world_index: usize) -> SeedScore {
+        let mut score = SeedScore {
+            spheres: self
+                .groups
+                .iter()
+                .filter(|group| {
+                    group
+                        .reachable
+                        .get(world_index)
+                        .is_some_and(|reachable| !reachable.is_empty())
+                })
+                .count() as u32,
+            ..SeedScore::default()
+        };
+
+        let mut zones = FxHashSet::default();
+        for placement in self
+            .groups
+            .iter()
+            .flat_map(|group| forced_placements(group, world_index))
+        {
+            zones.extend(placement.location.zone);
+            match placement.item {
+                Item::Resource(Resource::Keystone) => score.keystone_pressure += 1,
+                Item::SpiritLight(amount) => score.spirit_light_farming += amount,
+                _ => score.forced_progressions += 1,
+            }
+        }
+        score.zone_spread = zones.len() as u32;
+
+        score
+    }
+}
+
+/// Placements in the group that were forced progression for the world
+// Forced items aren't recorded per world, so the first matching placements the world receives are used
+fn forced_placements(group: &SpoilerGroup, world_index: usize) -> Vec<&SpoilerPlacement> {
+    let mut remaining = group.forced_items.clone();
+    group
+        .placements
+        .iter()
+        .filter(|placement| placement.shared || placement.target_world_index == world_index)
+        .filter(|placement| {
+            // Forced Spirit Light is stacked into one amount of Spirit Light(1)
+            let (item, amount) = match placement.item {
+                Item::SpiritLight(amount) => (Item::SpiritLight(1), amount),
+                ref item => (item.clone(), 1),
+            };
+            let forced = remaining.has(&item, amount);
+            if forced {
+                remaining.remove(&item, amount);
+            }
+            forced
+        })
+        .collect()
+}
@@ -1,5 +1,7 @@
 use std::fmt::{self, Display};
 
+use itertools::{EitherOrBoth, Itertools};
+
 use crate::uber_state::UberStateTrigger;
 use crate::{
     header,
@@ -68,6 +70,46 @@ impl Seed<'_, '_> {
     }
 }
 
+/// Compares two seed files line by line, ignoring the generator version they were generated on
+///
+/// This can be used to check whether a previously generated seed still generates identically, see [`UniverseSettings::from_seed`] to obtain the settings needed to regenerate it
+///
+/// Returns an error describing the first difference if the seed files don't match
+pub fn compare_seed_files(expected: &str, actual: &str) -> Result<(), String> {
+    fn relevant_lines(seed: &str) -> impl Iterator<Item = (usize, &str)> {
+        seed.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.starts_with("// Generator Version: "))
+    }
+
+    for lines in relevant_lines(expected).zip_longest(relevant_lines(actual)) {
+        match lines {
+            EitherOrBoth::Both((_, expected_line), (_, actual_line))
+                if expected_line == actual_line => {}
+            EitherOrBoth::Both((index, expected_line), (_, actual_line)) => {
+                return Err(format!(
+                    "line {}: expected \"{expected_line}\", found \"{actual_line}\"",
+                    index + 1
+                ))
+            }
+            EitherOrBoth::Left((index, expected_line)) => {
+                return Err(format!(
+                    "line {}: expected \"{expected_line}\", found end of seed",
+                    index + 1
+                ))
+            }
+            EitherOrBoth::Right((index, actual_line)) => {
+                return Err(format!(
+                    "line {}: expected end of seed, found \"{actual_line}\"",
+                    index + 1
+                ))
+            }
+        }
+    }
+
+    Ok(())
+}
+
 impl Display for SeedWorld<'_, '_> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         if !self.flags.is_empty() {
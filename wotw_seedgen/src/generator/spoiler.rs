@@ -4,6 +4,8 @@ use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    generator::doors::DoorId,
+    settings::MultiworldSharing,
     util::{Position, Zone},
     Inventory, Item,
 };
@@ -15,6 +17,12 @@ pub struct SeedSpoiler {
     pub spawns: Vec<String>,
     /// Each [`SpoilerGroup`] represents one "step" of placements
     pub groups: Vec<SpoilerGroup>,
+    /// The door connections for each world, empty for worlds that don't randomize doors
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub doors: Vec<Vec<SpoilerDoor>>,
+    /// How items were distributed between the worlds
+    #[serde(default, skip_serializing_if = "MultiworldSharing::is_default")]
+    pub sharing: MultiworldSharing,
 }
 /// One "step" of placements in a [`SeedSpoiler`]
 #[derive(Debug, Default, Serialize, Deserialize, Clone)]
@@ -37,6 +45,9 @@ pub struct SpoilerPlacement {
     pub origin_world_index: usize,
     /// The "receiving" world
     pub target_world_index: usize,
+    /// Whether every world receives the [`Item`], as with [`MultiworldSharing::SharedPool`]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub shared: bool,
     /// The placement location
     pub location: NodeSummary,
     /// The placed [`Item`]
@@ -44,6 +55,19 @@ pub struct SpoilerPlacement {
     /// The name of the [`Item`], which may vary from the [`Item`]s [`Display`] implementation if a custom name for item was provided by headers
     pub item_name: String,
 }
+/// One randomized door connection
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SpoilerDoor {
+    /// The door being entered
+    pub door_id: DoorId,
+    /// Anchor identifier of the door being entered
+    pub anchor: String,
+    /// The door this leads to
+    pub target_door_id: DoorId,
+    /// Anchor identifier of the door this leads to
+    pub target_anchor: String,
+}
 /// Select data from a [`Node`](crate::world::graph::Node)
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct NodeSummary {
@@ -69,6 +93,9 @@ impl Display for SeedSpoiler {
         let multiworld = world_count > 1;
 
         if multiworld {
+            if !self.sharing.is_default() {
+                writeln!(f, "Item Sharing: {}", self.sharing)?;
+            }
             for (index, spawn) in self.spawns.iter().enumerate() {
                 writeln!(f, "Spawn for World [{index}]: {spawn}")?;
             }
@@ -77,6 +104,31 @@ impl Display for SeedSpoiler {
             writeln!(f, "Spawn: {spawn}")?;
         }
 
+        for (index, doors) in self.doors.iter().enumerate() {
+            if doors.is_empty() {
+                continue;
+            }
+
+            writeln!(f)?;
+            if multiworld {
+                writeln!(f, "Doors for World [{index}]:")?;
+            } else {
+                writeln!(f, "Doors:")?;
+            }
+            let longest_anchor = doors
+                .iter()
+                .map(|door| door.anchor.len())
+                .max()
+                .unwrap_or(0);
+            for door in doors {
+                writeln!(
+                    f,
+                    "    {:<longest_anchor$}  -> {}",
+                    door.anchor, door.target_anchor
+                )?;
+            }
+        }
+
         writeln!(f)?;
         writeln!(f)?;
 
@@ -92,7 +144,9 @@ impl Display for SeedSpoiler {
                     .iter()
                     .map(|placement| {
                         let mut pickup = String::new();
-                        if multiworld {
+                        if placement.shared {
+                            write!(pickup, "[*] ")?;
+                        } else if multiworld {
                             write!(pickup, "[{}] ", placement.target_world_index)?;
                         }
                         write!(pickup, "{}", placement.item_name)?;
@@ -1,7 +1,8 @@
 use std::fmt;
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, Serializer};
 
+use itertools::Itertools;
 use rustc_hash::FxHashMap;
 use smallvec::SmallVec;
 
@@ -15,9 +16,17 @@ use crate::{
 // TODO optimization idea inline max_health etc.
 #[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
 pub struct Inventory {
+    #[serde(serialize_with = "serialize_sorted")]
     pub items: FxHashMap<Item, u32>, // TODO what would a switch to usize do here? Often we need a usize and end up casting this
 }
 impl Inventory {
+    /// Returns the items in a stable order
+    ///
+    /// Iterating `items` directly yields an order that may differ between builds and platforms, use this whenever the order can influence the generated seed
+    pub fn sorted_items(&self) -> Vec<(&Item, &u32)> {
+        self.items.iter().sorted_unstable().collect()
+    }
+
     pub fn grant(&mut self, mut item: Item, mut amount: u32) {
         if amount == 0 {
             return;
@@ -428,7 +437,7 @@ impl fmt::Display for Inventory {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // TODO could try iter::intersperse here once it's stabilized
         let mut first = true;
-        for (item, amount) in &self.items {
+        for (item, amount) in self.sorted_items() {
             if first {
                 first = false
             } else {
@@ -443,6 +452,13 @@ impl fmt::Display for Inventory {
     }
 }
 
+fn serialize_sorted<S: Serializer>(
+    items: &FxHashMap<Item, u32>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(items.iter().sorted_unstable())
+}
+
 impl From<Item> for Inventory {
     fn from(item: Item) -> Inventory {
         let mut inventory = Inventory::default();
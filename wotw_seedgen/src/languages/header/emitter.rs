@@ -2,9 +2,14 @@ use std::fmt::Display;
 
 use rustc_hash::FxHashMap;
 
-use crate::{settings::Goal, util::Icon, Item, VItem};
+use crate::{
+    settings::{CustomGoal, Goal},
+    uber_state::UberStateTrigger,
+    util::Icon,
+    Item, VItem,
+};
 
-use super::{GoalmodeHack, HeaderCommand, HeaderContent, Pickup, VPickup, VResolve, VString, V};
+use super::{HeaderCommand, HeaderContent, HeaderGoal, Pickup, VPickup, VResolve, VString, V};
 
 /// Configurable details for how to treat an [`Item`] during seed generation
 #[derive(Debug, Clone, Default)]
@@ -43,6 +48,8 @@ pub struct HeaderBuild {
     /// Logical states to be set at the start of seed generation
     pub state_sets: Vec<String>,
     pub goals: Vec<Goal>,
+    /// Triggers on which the generator should place hint messages
+    pub hint_slots: Vec<UberStateTrigger>,
 }
 
 pub(super) fn build(
@@ -153,8 +160,9 @@ fn build_command(
             build_if(&parameter, &value, if_stack, parameters)?
         }
         HeaderCommand::EndIf => build_endif(if_stack)?,
-        HeaderCommand::GoalmodeHack(goalmode) => {
-            build_goalmode(goalmode, &mut header_build.goals, parameters)?
+        HeaderCommand::Goal(goal) => build_goal(goal, &mut header_build.goals, parameters)?,
+        HeaderCommand::Hint { trigger } => {
+            header_build.hint_slots.push(trigger.resolve(parameters)?)
         }
     }
 
@@ -267,16 +275,39 @@ fn change_item_details<T: Display>(
     }
 }
 
-fn build_goalmode(
-    goalmode: GoalmodeHack,
+fn build_goal(
+    goal: HeaderGoal,
     goals: &mut Vec<Goal>,
     parameters: &FxHashMap<String, String>,
 ) -> Result<(), String> {
-    let goal = match goalmode {
-        GoalmodeHack::Trees => Goal::Trees,
-        GoalmodeHack::Wisps => Goal::Wisps,
-        GoalmodeHack::Quests => Goal::Quests,
-        GoalmodeHack::Relics { chance, amount } => {
+    let goal = match goal {
+        HeaderGoal::Trees => Goal::Trees,
+        HeaderGoal::Wisps => Goal::Wisps,
+        HeaderGoal::Quests => Goal::Quests,
+        HeaderGoal::States {
+            required,
+            states,
+            name,
+        } => {
+            let required = required.resolve(parameters)?;
+            let states = states
+                .into_iter()
+                .map(|state| state.resolve(parameters))
+                .collect::<Result<Vec<_>, _>>()?;
+            if required > states.len() {
+                return Err(format!(
+                    "Goal {name} requires {required} states, but only lists {}",
+                    states.len()
+                ));
+            }
+
+            Goal::Custom(CustomGoal {
+                name,
+                required,
+                states,
+            })
+        }
+        HeaderGoal::Relics { chance, amount } => {
             let chance = chance.resolve(parameters)?.min(1.0);
             let amount = amount.resolve(parameters)?;
 
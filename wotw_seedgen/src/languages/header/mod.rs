@@ -522,15 +522,28 @@ pub enum HeaderCommand {
         value: String,
     },
     EndIf,
-    GoalmodeHack(GoalmodeHack),
+    Goal(HeaderGoal),
+    Hint {
+        trigger: VUberStateTrigger,
+    },
 }
 
+/// A goal declared with `!!goal`
 #[derive(Debug, Clone)]
-pub enum GoalmodeHack {
+pub enum HeaderGoal {
     Trees,
     Wisps,
     Quests,
-    Relics { chance: V<f64>, amount: V<usize> },
+    Relics {
+        chance: V<f64>,
+        amount: V<usize>,
+    },
+    /// `!!goal states <required> <trigger>, <trigger>, ...: <name>`
+    States {
+        required: V<usize>,
+        states: Vec<VUberStateTrigger>,
+        name: String,
+    },
 }
 
 /// Type and value of a parameter's default
@@ -2,9 +2,10 @@ use std::str::FromStr;
 
 use wotw_seedgen_derive::FromStr;
 
+use crate::uber_state::VUberStateTrigger;
 use crate::VItem;
 
-use crate::header::{GoalmodeHack, HeaderCommand, ParameterDefault, ParameterType, VString, V};
+use crate::header::{HeaderCommand, HeaderGoal, ParameterDefault, ParameterType, VString, V};
 use crate::languages::TokenKind;
 
 use super::{
@@ -30,8 +31,11 @@ enum HeaderCommandKind {
     #[Ident = "if"]
     StartIf,
     EndIf,
+    Goal,
+    /// Deprecated name of [`Goal`](HeaderCommandKind::Goal) kept for older headers
     #[Ident = "__goalmode_hack"]
     GoalmodeHack,
+    Hint,
 }
 
 impl HeaderCommand {
@@ -52,7 +56,8 @@ impl HeaderCommand {
             HeaderCommandKind::Set => parse_set(parser),
             HeaderCommandKind::StartIf => parse_if(parser),
             HeaderCommandKind::EndIf => Ok(HeaderCommand::EndIf),
-            HeaderCommandKind::GoalmodeHack => parse_goalmode(parser),
+            HeaderCommandKind::Goal | HeaderCommandKind::GoalmodeHack => parse_goal(parser),
+            HeaderCommandKind::Hint => parse_hint(parser),
         }
     }
 }
@@ -194,27 +199,52 @@ fn parse_if(parser: &mut Parser) -> Result<HeaderCommand, ParseError> {
 }
 #[derive(FromStr)]
 #[ParseFromIdentifier]
-enum Goalmode {
+enum GoalKind {
     Trees,
     Wisps,
     Quests,
     Relics,
+    States,
 }
-fn parse_goalmode(parser: &mut Parser) -> Result<HeaderCommand, ParseError> {
+fn parse_goal(parser: &mut Parser) -> Result<HeaderCommand, ParseError> {
     parser.eat_or_suggest(TokenKind::Whitespace, Suggestion::HeaderCommand)?;
 
-    let goal = match parse_ident!(parser, Suggestion::Integer)? {
-        Goalmode::Trees => GoalmodeHack::Trees,
-        Goalmode::Wisps => GoalmodeHack::Wisps,
-        Goalmode::Quests => GoalmodeHack::Quests,
-        Goalmode::Relics => {
+    let goal = match parse_ident!(parser, Suggestion::Identifier)? {
+        GoalKind::Trees => HeaderGoal::Trees,
+        GoalKind::Wisps => HeaderGoal::Wisps,
+        GoalKind::Quests => HeaderGoal::Quests,
+        GoalKind::Relics => {
             parser.eat(TokenKind::Whitespace)?;
             let chance = parse_v_number!(parser, Suggestion::Float);
             parser.eat(TokenKind::Whitespace)?;
             let amount = parse_v_number!(parser, Suggestion::Integer);
-            GoalmodeHack::Relics { chance, amount }
+            HeaderGoal::Relics { chance, amount }
+        }
+        GoalKind::States => {
+            parser.eat(TokenKind::Whitespace)?;
+            let required = parse_v_number!(parser, Suggestion::Integer);
+            parser.eat(TokenKind::Whitespace)?;
+            let mut states = vec![VUberStateTrigger::parse(parser)?];
+            while parser.current_token().kind == TokenKind::Comma {
+                parser.next_token();
+                parser.skip(TokenKind::Whitespace);
+                states.push(VUberStateTrigger::parse(parser)?);
+            }
+            parser.eat(TokenKind::Colon)?;
+            parser.eat(TokenKind::Whitespace)?;
+            let name = parse_string(parser).to_owned();
+            HeaderGoal::States {
+                required,
+                states,
+                name,
+            }
         }
     };
 
-    Ok(HeaderCommand::GoalmodeHack(goal))
+    Ok(HeaderCommand::Goal(goal))
+}
+fn parse_hint(parser: &mut Parser) -> Result<HeaderCommand, ParseError> {
+    parser.eat_or_suggest(TokenKind::Whitespace, Suggestion::HeaderCommand)?;
+    let trigger = VUberStateTrigger::parse(parser)?;
+    Ok(HeaderCommand::Hint { trigger })
 }
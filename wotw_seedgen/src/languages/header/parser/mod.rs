@@ -524,7 +524,7 @@ mod tests {
     use std::str::FromStr;
 
     use crate::header::parser;
-    use crate::header::VResolve;
+    use crate::header::{HeaderCommand, HeaderGoal, VResolve};
     use crate::item::*;
     use crate::uber_state::*;
     use crate::util::Position;
@@ -604,4 +604,24 @@ mod tests {
         assert!(Item::from_str("7|3").is_err());
         assert!(Item::from_str("-0|65").is_err());
     }
+
+    #[test]
+    fn goalmode_hack() {
+        assert!(matches!(
+            HeaderCommand::from_str("__goalmode_hack trees"),
+            Ok(HeaderCommand::Goal(HeaderGoal::Trees))
+        ));
+        assert!(matches!(
+            HeaderCommand::from_str("__goalmode_hack quests"),
+            Ok(HeaderCommand::Goal(HeaderGoal::Quests))
+        ));
+        let Ok(HeaderCommand::Goal(HeaderGoal::Relics { chance, amount })) =
+            HeaderCommand::from_str("__goalmode_hack relics 0.6 3")
+        else {
+            panic!("expected a relics goal");
+        };
+        assert_eq!(chance.resolve(&Default::default()), Ok(0.6));
+        assert_eq!(amount.resolve(&Default::default()), Ok(3));
+        assert!(HeaderCommand::from_str("__goalmode_hack").is_err());
+    }
 }
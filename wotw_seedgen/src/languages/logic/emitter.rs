@@ -7,7 +7,7 @@ use super::{
     states::NamedState,
 };
 
-use crate::generator::doors::DoorId;
+use crate::generator::doors::{DoorData, DoorId};
 use crate::item::Skill;
 use crate::logic::parser::{Door};
 use crate::uber_state::{UberIdentifier, UberStateComparator, UberStateCondition, UberStateTrigger};
@@ -830,7 +830,55 @@ pub fn build(
         );
     }
 
-    Ok(Graph::new(nodes, default_door_connections))
+    let door_data = build_door_data(&door_anchors)?;
+
+    Ok(Graph::new(nodes, default_door_connections, door_data))
+}
+
+fn build_door_data(door_anchors: &FxHashMap<&str, DoorAnchor>) -> Result<DoorData, String> {
+    let mut named_groups: FxHashMap<&str, Vec<DoorId>> = FxHashMap::default();
+    let mut groups = vec![];
+    let mut forbidden_targets: FxHashMap<DoorId, Vec<DoorId>> = FxHashMap::default();
+    let mut anchors = FxHashMap::default();
+
+    for door_anchor in door_anchors.values() {
+        let door = &door_anchor.door;
+        anchors.insert(door.door_id, door_anchor.identifier.clone());
+
+        // Doors without a group can't reach any other door
+        match door.group {
+            Some(group) => named_groups.entry(group).or_default().push(door.door_id),
+            None => groups.push(vec![door.door_id]),
+        }
+
+        for forbidden_target in &door.forbidden_targets {
+            let target = door_anchors.get(forbidden_target).ok_or_else(|| {
+                format!(
+                    "Door anchor {} forbids unknown door anchor {}",
+                    door_anchor.identifier, forbidden_target
+                )
+            })?;
+            forbidden_targets
+                .entry(door.door_id)
+                .or_default()
+                .push(target.door.door_id);
+        }
+    }
+
+    groups.extend(named_groups.into_values());
+    for group in &mut groups {
+        group.sort_unstable();
+    }
+    groups.sort_unstable();
+    for targets in forbidden_targets.values_mut() {
+        targets.sort_unstable();
+    }
+
+    Ok(DoorData {
+        groups,
+        forbidden_targets,
+        anchors,
+    })
 }
 
 #[cfg(test)]
@@ -865,4 +913,27 @@ mod tests {
             _ => panic!(),
         }
     }
+
+    #[test]
+    fn door_data() {
+        let areas = std::fs::read_to_string("areas.wotw").unwrap();
+        let locations = std::fs::read_to_string("loc_data.csv").unwrap();
+        let states = std::fs::read_to_string("state_data.csv").unwrap();
+        let graph = crate::logic::parse_logic(
+            &areas,
+            &locations,
+            &states,
+            &UniverseSettings::default(),
+            false,
+        )
+        .unwrap();
+
+        let groups = &graph.door_data.groups;
+        assert_eq!(groups.len(), 16);
+        assert_eq!(groups[0], (1..30).step_by(2).collect::<Vec<_>>());
+        assert!(groups.contains(&vec![16, 18]));
+        assert!(groups.contains(&vec![32]));
+        assert_eq!(graph.door_data.forbidden_targets[&21], [20]);
+        assert_eq!(graph.door_data.forbidden_targets[&9], [26]);
+    }
 }
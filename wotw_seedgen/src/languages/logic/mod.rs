@@ -9,6 +9,7 @@ pub use locations::{parse_locations, Location};
 pub use parser::Areas;
 pub use states::{parse_states, NamedState};
 
+use crate::error::LogicError;
 use crate::settings::UniverseSettings;
 use crate::world::Graph;
 
@@ -21,9 +22,9 @@ pub fn parse_logic(
     states: &str,
     universe_settings: &UniverseSettings,
     validate: bool,
-) -> Result<Graph, String> {
-    let areas = Areas::parse(areas).map_err(|err| err.verbose_display())?;
-    let locations = parse_locations(locations)?;
-    let named_states = parse_states(states)?;
-    build(areas, locations, named_states, universe_settings, validate)
+) -> Result<Graph, LogicError> {
+    let areas = Areas::parse(areas).map_err(LogicError::Areas)?;
+    let locations = parse_locations(locations).map_err(LogicError::Locations)?;
+    let named_states = parse_states(states).map_err(LogicError::States)?;
+    build(areas, locations, named_states, universe_settings, validate).map_err(LogicError::Build)
 }
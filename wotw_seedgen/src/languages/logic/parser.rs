@@ -65,6 +65,10 @@ pub struct Door<'a> {
     pub door_id: DoorId,
     pub target: &'a str,
     pub enter: Group<'a>,
+    /// Doors with the same group can reach each other without going through another door
+    pub group: Option<&'a str>,
+    /// Door anchors the door randomizer may never connect this door to
+    pub forbidden_targets: Vec<&'a str>,
 }
 #[derive(Debug, Clone)]
 pub struct Group<'a> {
@@ -354,11 +358,15 @@ enum DoorContentKind {
     Id,
     Target,
     Enter,
+    Group,
+    Forbid,
 }
 enum DoorContent<'a> {
     Id(DoorId),
     Target(&'a str),
     Enter(Group<'a>),
+    Group(&'a str),
+    Forbid(Vec<&'a str>),
 }
 fn parse_anchor_content<'a>(parser: &mut Parser<'a>) -> Result<AnchorContent<'a>, ParseError> {
     let kind = parse_ident!(parser, Suggestion::AnchorContent)?;
@@ -450,6 +458,8 @@ fn parse_door<'a>(parser: &mut Parser<'a>) -> Result<Door<'a>, ParseError> {
     let mut door_id: Option<DoorId> = None;
     let mut target: Option<&'a str> = None;
     let mut enter: Option<Group<'a>> = None;
+    let mut group: Option<&'a str> = None;
+    let mut forbidden_targets: Vec<&'a str> = Vec::new();
     let start = parser.current_token().range.start;
 
     loop {
@@ -457,6 +467,8 @@ fn parse_door<'a>(parser: &mut Parser<'a>) -> Result<Door<'a>, ParseError> {
             DoorContent::Id(id) => door_id = Some(id),
             DoorContent::Target(t) =>  target = Some(t),
             DoorContent::Enter(requirements) => enter = Some(requirements),
+            DoorContent::Group(g) => group = Some(g),
+            DoorContent::Forbid(mut targets) => forbidden_targets.append(&mut targets),
         }
 
         parser.skip(TokenKind::Whitespace);
@@ -487,6 +499,8 @@ fn parse_door<'a>(parser: &mut Parser<'a>) -> Result<Door<'a>, ParseError> {
         door_id,
         target,
         enter,
+        group,
+        forbidden_targets,
     })
 }
 fn parse_door_content<'a>(parser: &mut Parser<'a>) -> Result<DoorContent<'a>, ParseError> {
@@ -506,6 +520,22 @@ fn parse_door_content<'a>(parser: &mut Parser<'a>) -> Result<DoorContent<'a>, Pa
         DoorContentKind::Enter => {
             DoorContent::Enter(parse_group(parser)?)
         }
+        DoorContentKind::Group => {
+            parser.eat(TokenKind::Colon)?;
+            parser.skip(TokenKind::Whitespace);
+            DoorContent::Group(read_ident!(parser, Suggestion::Identifier)?)
+        }
+        DoorContentKind::Forbid => {
+            parser.eat(TokenKind::Colon)?;
+            parser.skip(TokenKind::Whitespace);
+            let mut targets = vec![read_ident!(parser, Suggestion::Identifier)?];
+            while parser.current_token().kind == TokenKind::Comma {
+                parser.next_token();
+                parser.skip(TokenKind::Whitespace);
+                targets.push(read_ident!(parser, Suggestion::Identifier)?);
+            }
+            DoorContent::Forbid(targets)
+        }
     };
 
     Ok(content)
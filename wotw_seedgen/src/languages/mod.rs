@@ -5,5 +5,5 @@ use cursor::Cursor;
 mod token;
 pub(crate) use token::{CommentKind, Token, TokenKind};
 mod parser;
-pub use parser::ParseError;
 pub(crate) use parser::Parser;
+pub use parser::{ParseError, ParseErrorCollection};
@@ -4,6 +4,8 @@ use std::iter::Peekable;
 use std::ops::{Deref, DerefMut, Range};
 use std::slice::SliceIndex;
 
+use serde::Serialize;
+
 use super::{Token, TokenKind};
 
 use crate::util::extensions::StrExtension;
@@ -121,7 +123,7 @@ impl<'a, TokenStream: Iterator<Item = Token>> Parser<'a, TokenStream> {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ParseError {
     pub message: String,
     pub source: String,
@@ -199,7 +201,8 @@ impl Display for ParseError {
 }
 impl Error for ParseError {}
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize)]
+#[serde(transparent)]
 pub struct ParseErrorCollection {
     errors: Vec<ParseError>,
 }
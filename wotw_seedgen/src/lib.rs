@@ -8,6 +8,7 @@
 #![allow(clippy::too_many_lines)]
 #![allow(clippy::struct_excessive_bools)]
 
+pub mod error;
 pub mod files;
 pub mod generator;
 pub mod inventory;
@@ -18,9 +19,11 @@ mod reach_check;
 pub mod settings;
 pub mod uber_state;
 pub mod util;
+pub mod verify;
 pub mod world;
 
-pub use generator::generate_seed;
+pub use error::SeedgenError;
+pub use generator::{generate_seed, generate_seed_with_observer, generate_seed_with_options};
 pub use inventory::Inventory;
 pub use item::{Item, VItem};
 pub use languages::{
@@ -28,6 +31,7 @@ pub use languages::{
     logic,
 };
 pub use reach_check::reach_check;
+pub use verify::verify_seed_file;
 pub use world::World;
 
 pub const VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), "-", env!("VERGEN_GIT_SHA"));
@@ -59,27 +63,48 @@ mod log {
 #[cfg(test)]
 mod tests {
     use crate::{
+        error::SettingsError,
         files::FILE_SYSTEM_ACCESS,
+        generator::{GenerationEvent, GenerationObserver, GeneratorOptions, ObserverAction},
+        item::{Item, Skill, UberStateItem, UberStateOperator},
         preset::{UniversePreset, WorldPreset},
-        settings::{Difficulty, UniverseSettings},
+        settings::{
+            self, Difficulty, InlineHeader, ItemWeight, PlacementConstraint, PlacementWeights,
+            Plando, PlandoPlacement, ScoreRange, ShopPriceModel, ShopPricing, SpiritLightMode,
+            UniverseSettings, WorldSettings,
+        },
+        util::{
+            constants::{SCORE_RETRIES, SHOP_PRICES},
+            Zone,
+        },
+    };
+    use itertools::Itertools;
+    use num_enum::FromPrimitive;
+    use std::{
+        num::{NonZeroU16, NonZeroUsize},
+        sync::atomic::{self, AtomicUsize},
     };
 
     use super::*;
 
-    #[test]
-    fn some_seeds() {
-        let mut universe_settings = UniverseSettings::default();
+    /// Parses the logic files with the settings
+    pub(crate) fn test_graph(universe_settings: &UniverseSettings) -> world::Graph {
         let areas = files::read_file("areas", "wotw", "logic").unwrap();
         let locations = files::read_file("loc_data", "csv", "logic").unwrap();
         let states = files::read_file("state_data", "csv", "logic").unwrap();
-        let mut graph =
-            logic::parse_logic(&areas, &locations, &states, &universe_settings, false).unwrap();
+        logic::parse_logic(&areas, &locations, &states, universe_settings, false).unwrap()
+    }
+
+    #[test]
+    fn some_seeds() {
+        let mut universe_settings = UniverseSettings::default();
+        let mut graph = test_graph(&universe_settings);
 
         eprintln!("Default settings ({})", universe_settings.seed);
         generate_seed(&graph, &FILE_SYSTEM_ACCESS, &universe_settings).unwrap();
 
         universe_settings.world_settings[0].difficulty = Difficulty::Unsafe;
-        graph = logic::parse_logic(&areas, &locations, &states, &universe_settings, false).unwrap();
+        graph = test_graph(&universe_settings);
         eprintln!("Unsafe ({})", universe_settings.seed);
         generate_seed(&graph, &FILE_SYSTEM_ACCESS, &universe_settings).unwrap();
 
@@ -120,4 +145,596 @@ mod tests {
         eprintln!("Gorlek with headers ({})", universe_settings.seed);
         generate_seed(&graph, &FILE_SYSTEM_ACCESS, &universe_settings).unwrap();
     }
+
+    /// Regenerates every seed in the golden_seeds folder and checks that it didn't change
+    ///
+    /// If a change in generation is intended, run with the `BLESS_GOLDEN_SEEDS` environment variable set to overwrite them
+    #[test]
+    fn golden_seeds() {
+        let bless = std::env::var_os("BLESS_GOLDEN_SEEDS").is_some();
+
+        let mut paths = std::fs::read_dir("golden_seeds")
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == "wotwr")
+            })
+            .collect::<Vec<_>>();
+        paths.sort();
+        assert!(!paths.is_empty());
+
+        for path in paths {
+            let golden = std::fs::read_to_string(&path).unwrap();
+            let universe_settings = UniverseSettings::from_seed(&golden).unwrap().unwrap();
+            let world_index = settings::world_index_from_seed(&golden).unwrap().unwrap();
+            let graph = test_graph(&universe_settings);
+
+            let seed = generate_seed(&graph, &FILE_SYSTEM_ACCESS, &universe_settings).unwrap();
+            let seed_file = &seed.seed_files().unwrap()[world_index];
+
+            if bless {
+                std::fs::write(&path, seed_file).unwrap();
+            } else if let Err(err) = generator::compare_seed_files(&golden, seed_file) {
+                panic!("{} changed: {err}", path.display());
+            }
+        }
+    }
+
+    #[test]
+    fn placement_constraints() {
+        let mut universe_settings = UniverseSettings::default();
+        universe_settings.world_settings[0].placement_constraints = vec![
+            PlacementConstraint::NotInZone {
+                item: Item::Skill(Skill::Launch),
+                zone: Zone::Wastes,
+            },
+            PlacementConstraint::NoSkillsInShops,
+            PlacementConstraint::WithinFirstReachable {
+                item: Item::Skill(Skill::Bash),
+                locations: 20,
+            },
+        ];
+        let graph = test_graph(&universe_settings);
+
+        for seed in 0..5 {
+            universe_settings.seed = format!("placement_constraints{seed}");
+            let seed = generate_seed(&graph, &FILE_SYSTEM_ACCESS, &universe_settings).unwrap();
+
+            let mut reached_before = 0;
+            for group in &seed.spoiler.groups {
+                for placement in &group.placements {
+                    match placement.item {
+                        Item::Skill(Skill::Launch) => {
+                            assert_ne!(placement.location.zone, Some(Zone::Wastes))
+                        }
+                        Item::Skill(Skill::Bash) => assert!(reached_before < 20),
+                        _ => {}
+                    }
+                    if let Item::Skill(_) = placement.item {
+                        assert_ne!(placement.location.zone, Some(Zone::Shop));
+                    }
+                }
+                reached_before += group.reachable.iter().map(Vec::len).sum::<usize>();
+            }
+        }
+
+        let mut unsatisfiable = |placement_constraints| {
+            universe_settings.world_settings[0].placement_constraints = placement_constraints;
+            matches!(
+                generate_seed(&graph, &FILE_SYSTEM_ACCESS, &universe_settings),
+                Err(SeedgenError::Settings(
+                    SettingsError::UnsatisfiablePlacementConstraint(_)
+                ))
+            )
+        };
+        assert!(unsatisfiable(vec![
+            PlacementConstraint::WithinFirstReachable {
+                item: Item::Skill(Skill::Bash),
+                locations: 0,
+            }
+        ]));
+        assert!(unsatisfiable(vec![PlacementConstraint::NotInZone {
+            item: Item::Skill(Skill::Seir),
+            zone: Zone::Wastes,
+        }]));
+        assert!(unsatisfiable(
+            (0..=14)
+                .map(|zone| PlacementConstraint::NotInZone {
+                    item: Item::Skill(Skill::Launch),
+                    zone: Zone::from_primitive(zone),
+                })
+                .collect()
+        ));
+    }
+
+    #[test]
+    fn plando() {
+        let mut universe_settings = UniverseSettings {
+            seed: "plando".to_string(),
+            ..UniverseSettings::default()
+        };
+        let graph = test_graph(&universe_settings);
+
+        universe_settings.world_settings[0].plando = Plando {
+            placements: vec![PlandoPlacement {
+                location: "MarshSpawn.RockHC".to_string(),
+                item: Item::Skill(Skill::Launch),
+            }],
+        };
+        let seed = generate_seed(&graph, &FILE_SYSTEM_ACCESS, &universe_settings).unwrap();
+        let launches = seed
+            .spoiler
+            .groups
+            .iter()
+            .flat_map(|group| &group.placements)
+            .filter(|placement| placement.item == Item::Skill(Skill::Launch))
+            .map(|placement| placement.location.identifier.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(launches, ["MarshSpawn.RockHC"]);
+
+        universe_settings.world_settings[0].plando.placements[0] = PlandoPlacement {
+            location: "WestPools.ForestsStrength".to_string(),
+            item: Item::Water,
+        };
+        assert!(generate_seed(&graph, &FILE_SYSTEM_ACCESS, &universe_settings).is_err());
+    }
+
+    #[test]
+    fn verify_seed_file() {
+        for name in ["moki", "gorlek_random_spawn", "doors"] {
+            let seed_file = std::fs::read_to_string(format!("golden_seeds/{name}.wotwr")).unwrap();
+            let universe_settings = UniverseSettings::from_seed(&seed_file).unwrap().unwrap();
+            let world_settings = WorldSettings::from_seed(&seed_file, universe_settings.clone())
+                .unwrap()
+                .unwrap();
+            let graph = test_graph(&universe_settings);
+
+            let verification =
+                verify::verify_seed_file(&graph, &seed_file, &world_settings).unwrap();
+            assert!(verification.beatable, "{name}: {verification}");
+            let unreachable_count = verification.unreachable_items.len();
+
+            // Without Double Jump and Launch, some items can't be reached anymore
+            let tampered = seed_file
+                .lines()
+                .filter(|line| !line.ends_with("|2|5") && !line.ends_with("|2|8"))
+                .join("\n");
+            let verification =
+                verify::verify_seed_file(&graph, &tampered, &world_settings).unwrap();
+            assert!(!verification.beatable, "{name}");
+            assert!(verification.unreachable_items.len() > unreachable_count);
+        }
+    }
+
+    #[test]
+    fn playthrough() {
+        let universe_settings = UniverseSettings {
+            seed: "playthrough".to_string(),
+            ..UniverseSettings::default()
+        };
+        let graph = test_graph(&universe_settings);
+
+        let seed = generate_seed(&graph, &FILE_SYSTEM_ACCESS, &universe_settings).unwrap();
+        let playthrough = seed.playthrough().unwrap();
+
+        let placements = playthrough
+            .spheres
+            .iter()
+            .flat_map(|sphere| &sphere.placements)
+            .collect::<Vec<_>>();
+        let spoiler_placements = seed
+            .spoiler
+            .groups
+            .iter()
+            .map(|group| group.placements.len())
+            .sum();
+        assert!(!placements.is_empty());
+        assert!(placements.len() < spoiler_placements);
+        assert!(placements
+            .iter()
+            .all(|placement| placement.item.is_progression(Difficulty::Moki)));
+    }
+
+    #[test]
+    fn parallel_attempts() {
+        let mut universe_settings = UniverseSettings {
+            seed: "parallel".to_string(),
+            ..UniverseSettings::default()
+        };
+        universe_settings.world_settings[0].spawn = settings::Spawn::Random;
+        let graph = test_graph(&universe_settings);
+
+        let seed_files = |parallel_attempts| {
+            let options = GeneratorOptions {
+                parallel_attempts: NonZeroUsize::new(parallel_attempts),
+                ..GeneratorOptions::default()
+            };
+            generate_seed_with_options(&graph, &FILE_SYSTEM_ACCESS, &universe_settings, &options)
+                .unwrap()
+                .seed_files()
+                .unwrap()
+        };
+        let single = seed_files(1);
+        assert_eq!(single, seed_files(4));
+        assert_eq!(single, seed_files(1));
+    }
+
+    #[test]
+    fn skill_depth() {
+        let graph = test_graph(&UniverseSettings::default());
+
+        // Average position of the skills among all placements
+        let skill_position = |skill_depth| {
+            let mut universe_settings = UniverseSettings::default();
+            universe_settings.world_settings[0]
+                .placement_weights
+                .skill_depth = skill_depth;
+            (0..4)
+                .map(|seed| {
+                    universe_settings.seed = format!("skill_depth{seed}");
+                    let seed =
+                        generate_seed(&graph, &FILE_SYSTEM_ACCESS, &universe_settings).unwrap();
+                    let placements = seed
+                        .spoiler
+                        .groups
+                        .iter()
+                        .flat_map(|group| &group.placements)
+                        .collect::<Vec<_>>();
+                    let skills = placements
+                        .iter()
+                        .positions(|placement| matches!(placement.item, Item::Skill(_)))
+                        .collect::<Vec<_>>();
+                    skills.iter().sum::<usize>() as f32
+                        / skills.len() as f32
+                        / placements.len() as f32
+                })
+                .sum::<f32>()
+        };
+
+        let early = skill_position(-5.);
+        let late = skill_position(5.);
+        assert!(early < late, "{early} >= {late}");
+    }
+
+    #[test]
+    fn invalid_placement_weights() {
+        let graph = test_graph(&UniverseSettings::default());
+
+        let invalid = |placement_weights| {
+            let mut universe_settings = UniverseSettings {
+                seed: "invalid_placement_weights".to_string(),
+                ..UniverseSettings::default()
+            };
+            universe_settings.world_settings[0].placement_weights = placement_weights;
+            assert!(matches!(
+                generate_seed(&graph, &FILE_SYSTEM_ACCESS, &universe_settings),
+                Err(SeedgenError::Settings(
+                    SettingsError::InvalidPlacementWeights(_)
+                ))
+            ));
+        };
+        for weight in [-1., f32::NAN, f32::INFINITY] {
+            invalid(PlacementWeights {
+                items: vec![ItemWeight {
+                    item: Item::Skill(Skill::Bash),
+                    weight,
+                }],
+                ..PlacementWeights::default()
+            });
+        }
+        for skill_depth in [f32::NAN, -1000.] {
+            invalid(PlacementWeights {
+                skill_depth,
+                ..PlacementWeights::default()
+            });
+        }
+    }
+
+    #[test]
+    fn spirit_light_budget() {
+        let mut universe_settings = UniverseSettings {
+            seed: "spirit_light_budget".to_string(),
+            ..UniverseSettings::default()
+        };
+        universe_settings.world_settings[0].spirit_light.mode = SpiritLightMode::Budget;
+        let graph = test_graph(&universe_settings);
+
+        let seed = generate_seed(&graph, &FILE_SYSTEM_ACCESS, &universe_settings).unwrap();
+        let spirit_light = seed.worlds[0]
+            .placements
+            .iter()
+            .filter_map(|placement| match placement.item {
+                Item::SpiritLight(amount) => Some(amount),
+                _ => None,
+            })
+            .sum::<u32>();
+        // The standard pool has 20000 Spirit Light, the 1 extra is the one_xp placement
+        assert_eq!(spirit_light, 20001);
+    }
+
+    #[test]
+    fn spirit_light_zone_scaled() {
+        let mut universe_settings = UniverseSettings {
+            seed: "spirit_light_zone_scaled".to_string(),
+            ..UniverseSettings::default()
+        };
+        universe_settings.world_settings[0].spirit_light.mode = SpiritLightMode::ZoneScaled;
+        let graph = test_graph(&universe_settings);
+
+        let seed = generate_seed(&graph, &FILE_SYSTEM_ACCESS, &universe_settings).unwrap();
+        // Zones in the order they are first reached
+        let zones = seed
+            .spoiler
+            .groups
+            .iter()
+            .filter_map(|group| group.reachable.first())
+            .flatten()
+            .filter_map(|location| location.zone)
+            .unique()
+            .collect::<Vec<_>>();
+        let average_spirit_light = |zone| {
+            let amounts = seed
+                .spoiler
+                .groups
+                .iter()
+                .flat_map(|group| &group.placements)
+                .filter(|placement| placement.location.zone == Some(zone))
+                .filter_map(|placement| match placement.item {
+                    Item::SpiritLight(amount) => Some(amount as f32),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            amounts.iter().sum::<f32>() / amounts.len().max(1) as f32
+        };
+        let first = average_spirit_light(zones[0]);
+        let last = average_spirit_light(*zones.last().unwrap());
+        assert!(first < last, "{first} >= {last}");
+    }
+
+    #[test]
+    fn shop_minimums() {
+        let mut universe_settings = UniverseSettings {
+            seed: "shop_minimums".to_string(),
+            ..UniverseSettings::default()
+        };
+        universe_settings.world_settings[0].spirit_light.mode = SpiritLightMode::Budget;
+        let graph = test_graph(&universe_settings);
+
+        universe_settings.world_settings[0]
+            .spirit_light
+            .shop_minimums = vec![200, 19000];
+        let seed = generate_seed(&graph, &FILE_SYSTEM_ACCESS, &universe_settings).unwrap();
+        let spirit_light = seed.worlds[0]
+            .placements
+            .iter()
+            .filter_map(|placement| match placement.item {
+                Item::SpiritLight(amount) if !placement.trigger.identifier.is_purchasable() => {
+                    Some(amount)
+                }
+                _ => None,
+            })
+            .sum::<u32>();
+        assert!(spirit_light >= 19000, "{spirit_light} < 19000");
+
+        // More than the 20000 Spirit Light in the standard pool and the one_xp placement
+        universe_settings.world_settings[0]
+            .spirit_light
+            .shop_minimums = vec![200, 20002];
+        assert!(generate_seed(&graph, &FILE_SYSTEM_ACCESS, &universe_settings).is_err());
+    }
+
+    #[test]
+    fn shop_price_total() {
+        let mut universe_settings = UniverseSettings {
+            seed: "shop_price_total".to_string(),
+            ..UniverseSettings::default()
+        };
+        let graph = test_graph(&universe_settings);
+
+        let prices = |universe_settings: &UniverseSettings| {
+            let seed = generate_seed(&graph, &FILE_SYSTEM_ACCESS, universe_settings).unwrap();
+            seed.worlds[0]
+                .placements
+                .iter()
+                .filter_map(|placement| match &placement.item {
+                    Item::UberState(UberStateItem {
+                        identifier,
+                        operator: UberStateOperator::Value(price),
+                        ..
+                    }) if SHOP_PRICES
+                        .iter()
+                        .any(|(_, _, price_identifier)| price_identifier == identifier) =>
+                    {
+                        Some(price.to_f32())
+                    }
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        universe_settings.world_settings[0].shop_pricing = ShopPricing {
+            max_total: Some(1000),
+            ..ShopPricing::default()
+        };
+        let capped = prices(&universe_settings);
+        assert!(!capped.is_empty());
+        assert!(capped.iter().sum::<f32>() <= 1000.);
+
+        // Just enough for every shop location to cost at least 1
+        let shop_slots = graph
+            .nodes
+            .iter()
+            .filter_map(|node| node.trigger())
+            .filter(|trigger| trigger.identifier.is_purchasable())
+            .count() as u32;
+        universe_settings.world_settings[0].shop_pricing.max_total = Some(shop_slots);
+        let minimal = prices(&universe_settings);
+        assert!(minimal.iter().all(|&price| price >= 1.), "{minimal:?}");
+        assert!(minimal.iter().sum::<f32>() <= shop_slots as f32);
+
+        universe_settings.world_settings[0].shop_pricing.max_total = Some(shop_slots - 1);
+        assert!(matches!(
+            generate_seed(&graph, &FILE_SYSTEM_ACCESS, &universe_settings),
+            Err(SeedgenError::Settings(SettingsError::InvalidShopPricing(_)))
+        ));
+    }
+
+    #[test]
+    fn invalid_shop_pricing() {
+        let graph = test_graph(&UniverseSettings::default());
+
+        for model in [
+            ShopPriceModel::Fixed,
+            ShopPriceModel::Random,
+            ShopPriceModel::Progression,
+        ] {
+            for (min_factor, max_factor) in
+                [(2., 1.), (-1., 1.), (0., f32::NAN), (0., f32::INFINITY)]
+            {
+                let mut universe_settings = UniverseSettings {
+                    seed: "invalid_shop_pricing".to_string(),
+                    ..UniverseSettings::default()
+                };
+                universe_settings.world_settings[0].shop_pricing = ShopPricing {
+                    model,
+                    min_factor,
+                    max_factor,
+                    ..ShopPricing::default()
+                };
+                assert!(matches!(
+                    generate_seed(&graph, &FILE_SYSTEM_ACCESS, &universe_settings),
+                    Err(SeedgenError::Settings(SettingsError::InvalidShopPricing(_)))
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn affordable_shop_progression() {
+        let mut universe_settings = UniverseSettings {
+            seed: "affordable_shop_progression".to_string(),
+            ..UniverseSettings::default()
+        };
+        universe_settings.world_settings[0].shop_pricing = ShopPricing {
+            model: ShopPriceModel::Progression,
+            min_factor: 0.,
+            max_factor: 3.,
+            max_total: None,
+            affordable_progression: true,
+        };
+        let graph = test_graph(&universe_settings);
+
+        let seed = generate_seed(&graph, &FILE_SYSTEM_ACCESS, &universe_settings).unwrap();
+        let prices = seed.worlds[0]
+            .placements
+            .iter()
+            .filter_map(|placement| match &placement.item {
+                Item::UberState(UberStateItem {
+                    identifier,
+                    operator: UberStateOperator::Value(price),
+                    ..
+                }) if SHOP_PRICES
+                    .iter()
+                    .any(|(_, _, price_identifier)| price_identifier == identifier) =>
+                {
+                    Some(price.to_f32())
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert!(!prices.is_empty());
+        assert!(prices.iter().all(|&price| price >= 1.));
+    }
+
+    #[test]
+    fn custom_goal() {
+        let mut universe_settings = UniverseSettings {
+            seed: "custom_goal".to_string(),
+            ..UniverseSettings::default()
+        };
+        let graph = test_graph(&universe_settings);
+
+        universe_settings.world_settings[0].inline_headers = vec![InlineHeader {
+            name: None,
+            content: "!!goal states 2 0|77, 0|121, 1|9999: Two Trees".to_string(),
+        }];
+        let seed = generate_seed(&graph, &FILE_SYSTEM_ACCESS, &universe_settings).unwrap();
+        assert!(seed.worlds[0].flags.iter().any(|flag| flag == "Two Trees"));
+        assert!(seed.seed_files().unwrap()[0]
+            .contains("// Goal: Two Trees: 2 of 0|77, 0|121, 1|9999\n"));
+
+        universe_settings.world_settings[0].inline_headers[0].content =
+            "!!goal states 3 0|77, 0|121, 1|9999: Two Trees".to_string();
+        assert!(generate_seed(&graph, &FILE_SYSTEM_ACCESS, &universe_settings).is_err());
+    }
+
+    #[test]
+    fn observer_cancel() {
+        struct CancelAfter(AtomicUsize);
+        impl GenerationObserver for CancelAfter {
+            fn on_event(&self, event: GenerationEvent) -> ObserverAction {
+                if matches!(event, GenerationEvent::PlacementStep { .. })
+                    && self.0.fetch_sub(1, atomic::Ordering::Relaxed) == 0
+                {
+                    ObserverAction::Cancel
+                } else {
+                    ObserverAction::Continue
+                }
+            }
+        }
+
+        let universe_settings = UniverseSettings {
+            seed: "observer_cancel".to_string(),
+            ..UniverseSettings::default()
+        };
+        let graph = test_graph(&universe_settings);
+
+        let result = generate_seed_with_observer(
+            &graph,
+            &FILE_SYSTEM_ACCESS,
+            &universe_settings,
+            &GeneratorOptions::default(),
+            &CancelAfter(AtomicUsize::new(3)),
+        );
+        assert!(matches!(result, Err(SeedgenError::Cancelled)));
+    }
+
+    #[test]
+    fn score_range() {
+        let mut universe_settings = UniverseSettings {
+            seed: "score_range".to_string(),
+            ..UniverseSettings::default()
+        };
+        let graph = test_graph(&universe_settings);
+
+        let seed = generate_seed(&graph, &FILE_SYSTEM_ACCESS, &universe_settings).unwrap();
+        let score = seed.spoiler.score(0).total();
+        assert!(score > 0);
+        let spoiler = seed.spoiler.to_json();
+
+        universe_settings.score_range = Some(ScoreRange {
+            min: Some(score),
+            max: Some(score),
+        });
+        let scored_seed = generate_seed(&graph, &FILE_SYSTEM_ACCESS, &universe_settings).unwrap();
+        assert_eq!(scored_seed.spoiler.to_json(), spoiler);
+
+        universe_settings.score_range = Some(ScoreRange {
+            min: None,
+            max: Some(0),
+        });
+        let result = generate_seed(&graph, &FILE_SYSTEM_ACCESS, &universe_settings);
+        assert!(
+            matches!(result, Err(SeedgenError::Score(err)) if err.scores.len() == SCORE_RETRIES.get() as usize)
+        );
+
+        let options = GeneratorOptions {
+            score_retries: NonZeroU16::new(3).unwrap(),
+            ..GeneratorOptions::default()
+        };
+        let result =
+            generate_seed_with_options(&graph, &FILE_SYSTEM_ACCESS, &universe_settings, &options);
+        assert!(matches!(result, Err(SeedgenError::Score(err)) if err.scores.len() == 3));
+    }
 }
@@ -7,7 +7,11 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     files::FileAccess,
-    settings::{CreateGame, Difficulty, GoalModes, HeaderConfig, InlineHeader, Spawn, Trick},
+    settings::{
+        CreateGame, Difficulty, GoalModes, HeaderConfig, HintSettings, InlineHeader, ItemPool,
+        MultiworldSharing, PlacementConstraint, PlacementWeights, Plando, ScoreRange, ShopPricing,
+        Spawn, SpiritLightSettings, Trick,
+    },
 };
 
 /// A collection of settings that can be applied to existing settings
@@ -75,6 +79,12 @@ pub struct UniversePreset {
     /// This exists for future compability, but does not have any effect currently
     #[serde(skip_serializing_if = "Option::is_none")]
     pub create_game: Option<CreateGame>,
+    /// How items are distributed between the worlds of a multiworld seed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multiworld_sharing: Option<MultiworldSharing>,
+    /// Reject seeds where any world's score falls outside this range
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score_range: Option<ScoreRange>,
 }
 
 impl UniversePreset {
@@ -179,6 +189,29 @@ pub struct WorldPreset {
     /// Inline header syntax
     #[serde(skip_serializing_if = "Option::is_none")]
     pub inline_headers: Option<Vec<InlineHeader>>,
+    /// Restrictions on where items may be placed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placement_constraints: Option<Vec<PlacementConstraint>>,
+    /// Items on fixed locations
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plando: Option<Plando>,
+    /// How many hints of each kind to generate
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hints: Option<HintSettings>,
+    /// Biases when choosing items for placements
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placement_weights: Option<PlacementWeights>,
+    /// Which items to place
+    ///
+    /// This may name another item pool as base and override some of its items, or define the pool from scratch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub item_pool: Option<ItemPool>,
+    /// How Spirit Light is distributed and what shops cost
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spirit_light: Option<SpiritLightSettings>,
+    /// How shop prices are chosen
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shop_pricing: Option<ShopPricing>,
 }
 
 impl WorldPreset {
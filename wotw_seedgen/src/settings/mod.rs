@@ -8,6 +8,7 @@ use std::fmt::{Display, Formatter};
 use std::ops::{Deref, DerefMut};
 use std::{collections::hash_map::DefaultHasher, error::Error, fmt, hash::Hasher, iter};
 
+use itertools::Itertools;
 use rand::distributions::{Distribution, Uniform};
 use rustc_hash::FxHashSet;
 use serde::de::Visitor;
@@ -15,11 +16,13 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use smallvec::{smallvec, SmallVec};
 use wotw_seedgen_derive::{Display, FromStr};
 
-use crate::item::Skill;
+use crate::item::{Item, Skill};
 use crate::{
+    error::SettingsError,
     files::FileAccess,
     preset::{UniversePreset, WorldPreset},
-    util::constants::DEFAULT_SPAWN,
+    uber_state::{UberIdentifier, UberStateTrigger},
+    util::{constants::DEFAULT_SPAWN, Zone},
 };
 
 use slugstrings::SLUGSTRINGS;
@@ -86,6 +89,12 @@ pub struct UniverseSettings {
     ///
     /// This exists for future compability, but does not have any effect currently
     pub create_game: CreateGame,
+    /// How items are distributed between the worlds of a multiworld seed
+    #[serde(default, skip_serializing_if = "MultiworldSharing::is_default")]
+    pub multiworld_sharing: MultiworldSharing,
+    /// If set, seeds where any world's [`SeedScore`](crate::generator::SeedScore) falls outside this range are rejected and generated again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score_range: Option<ScoreRange>,
 }
 
 impl UniverseSettings {
@@ -144,6 +153,8 @@ impl UniverseSettings {
             online,
             seed,
             create_game,
+            multiworld_sharing,
+            score_range,
         } = preset;
 
         if let Some(includes) = includes {
@@ -197,6 +208,12 @@ impl UniverseSettings {
         if let Some(create_game) = create_game {
             self.create_game = create_game;
         }
+        if let Some(multiworld_sharing) = multiworld_sharing {
+            self.multiworld_sharing = multiworld_sharing;
+        }
+        if let Some(score_range) = score_range {
+            self.score_range = Some(score_range);
+        }
 
         Ok(())
     }
@@ -326,6 +343,8 @@ impl Default for UniverseSettings {
             disable_logic_filter: false,
             online: false,
             create_game: CreateGame::default(),
+            multiworld_sharing: MultiworldSharing::default(),
+            score_range: None,
         }
     }
 }
@@ -353,6 +372,7 @@ pub struct WorldSettings {
     /// Logically expected difficulty
     pub difficulty: Difficulty,
     /// Logically expected tricks
+    #[serde(serialize_with = "serialize_sorted")]
     pub tricks: FxHashSet<Trick>,
     /// Logically assume hard in-game difficulty
     pub hard: bool,
@@ -363,11 +383,33 @@ pub struct WorldSettings {
     /// Names of headers to use
     ///
     /// When generating a seed with these settings, the headers will be searched as .wotwrh files in the current and /headers child directory
+    #[serde(serialize_with = "serialize_sorted")]
     pub headers: FxHashSet<String>,
     /// Configuration parameters to pass to headers
     pub header_config: Vec<HeaderConfig>,
     /// Fully qualified header syntax
     pub inline_headers: Vec<InlineHeader>,
+    /// Restrictions on where items may be placed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub placement_constraints: Vec<PlacementConstraint>,
+    /// Items on fixed locations, the remaining locations will be filled in logic
+    #[serde(default, skip_serializing_if = "Plando::is_empty")]
+    pub plando: Plando,
+    /// How many hints of each kind to generate for the hint slots declared by headers
+    #[serde(default, skip_serializing_if = "HintSettings::is_default")]
+    pub hints: HintSettings,
+    /// Biases when choosing items for random and forced placements
+    #[serde(default, skip_serializing_if = "PlacementWeights::is_default")]
+    pub placement_weights: PlacementWeights,
+    /// Which items to place, if not the standard item pool
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub item_pool: Option<ItemPool>,
+    /// How Spirit Light is distributed and what shops cost
+    #[serde(default, skip_serializing_if = "SpiritLightSettings::is_default")]
+    pub spirit_light: SpiritLightSettings,
+    /// How shop prices are chosen
+    #[serde(default, skip_serializing_if = "ShopPricing::is_default")]
+    pub shop_pricing: ShopPricing,
 }
 
 impl WorldSettings {
@@ -449,6 +491,13 @@ impl WorldSettings {
             headers,
             header_config,
             inline_headers,
+            placement_constraints,
+            plando,
+            hints,
+            placement_weights,
+            item_pool,
+            spirit_light,
+            shop_pricing,
         } = preset;
 
         if let Some(includes) = includes {
@@ -486,6 +535,30 @@ impl WorldSettings {
         if let Some(mut inline_headers) = inline_headers {
             self.inline_headers.append(&mut inline_headers);
         }
+        if let Some(mut placement_constraints) = placement_constraints {
+            self.placement_constraints
+                .append(&mut placement_constraints);
+        }
+        if let Some(mut plando) = plando {
+            self.plando.placements.append(&mut plando.placements);
+        }
+        if let Some(hints) = hints {
+            self.hints = hints;
+        }
+        if let Some(placement_weights) = placement_weights {
+            placement_weights.validate()?;
+            self.placement_weights = placement_weights;
+        }
+        if let Some(item_pool) = item_pool {
+            self.item_pool = Some(item_pool);
+        }
+        if let Some(spirit_light) = spirit_light {
+            self.spirit_light = spirit_light;
+        }
+        if let Some(shop_pricing) = shop_pricing {
+            shop_pricing.validate()?;
+            self.shop_pricing = shop_pricing;
+        }
 
         Ok(())
     }
@@ -677,7 +750,9 @@ pub mod logical_difficulty {
 /// This includes mostly Glitches but also other techniques that can be toggled for logic, such as damage boosting
 ///
 /// See the [Paths wiki page](https://wiki.orirando.com/seedgen/paths) for more information
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, FromStr)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, FromStr,
+)]
 #[ParseFromIdentifier]
 pub enum Trick {
     /// Grounded Sentry Jumps with Sword
@@ -768,6 +843,8 @@ pub enum Goal {
     /// Each zone of the game will have at most one Relic
     /// There are 11 zones that allow Relics, the specified chance represents how likely each single zone will have a relic
     RelicChance(f64),
+    /// Require a number of uber state conditions to be met before finishing the game
+    Custom(CustomGoal),
 }
 impl Display for Goal {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -777,6 +854,7 @@ impl Display for Goal {
             Goal::Quests => "Quests".fmt(f),
             Goal::Relics(count) => write!(f, "{} Relics", count),
             Goal::RelicChance(chance) => write!(f, "{}% Relic chance", chance * 100.),
+            Goal::Custom(goal) => goal.fmt(f),
         }
     }
 }
@@ -784,12 +862,13 @@ impl Goal {
     /// Returns the flag name representing this goal
     ///
     /// The flag name communicates to the randomizer client which restrictions to apply before allowing to finish the game
-    pub fn flag_name(&self) -> &'static str {
+    pub fn flag_name(&self) -> &str {
         match self {
             Goal::Wisps => "All Wisps",
             Goal::Trees => "All Trees",
             Goal::Quests => "All Quests",
             Goal::Relics(_) | Goal::RelicChance(_) => "Relics",
+            Goal::Custom(goal) => &goal.name,
         }
     }
 
@@ -798,6 +877,51 @@ impl Goal {
     }
 }
 
+/// A [`Goal`] defined by a set of uber state conditions, of which a number have to be met
+///
+/// Headers can declare these with `!!goal states`, which allows adding goal modes without changes to the generator
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomGoal {
+    /// Name of the goal, which is added to the seed flags for display
+    pub name: String,
+    /// How many of the `states` have to be met
+    pub required: usize,
+    pub states: Vec<UberStateTrigger>,
+}
+impl CustomGoal {
+    /// Counts how many of the states are met, using `uber_state` to look up current values
+    ///
+    /// States without a condition are met once their value is greater than 0
+    pub fn met_states(&self, uber_state: impl Fn(UberIdentifier) -> f32) -> usize {
+        self.states
+            .iter()
+            .filter(|state| {
+                let value = uber_state(state.identifier);
+                match &state.condition {
+                    Some(condition) => condition.met_by(value),
+                    None => value > 0.,
+                }
+            })
+            .count()
+    }
+    /// Checks whether enough states are met to fulfill the goal
+    pub fn is_met(&self, uber_state: impl Fn(UberIdentifier) -> f32) -> bool {
+        self.met_states(uber_state) >= self.required
+    }
+}
+impl Display for CustomGoal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} of {}",
+            self.name,
+            self.required,
+            self.states.iter().map(UberStateTrigger::code).format(", ")
+        )
+    }
+}
+
 /// A collection of non-redundant [`Goal`]s
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(transparent)]
@@ -816,6 +940,15 @@ impl GoalModes {
                 return Err(format!("Contradicting goal modes {} and {}", other, goal));
             }
         }
+        if let Goal::Custom(custom) = &goal {
+            if let Some(other) = self
+                .goals
+                .iter()
+                .find(|goal| goal.flag_name() == custom.name)
+            {
+                return Err(format!("Contradicting goal modes {} and {}", other, goal));
+            }
+        }
 
         self.goals.push(goal);
         Ok(())
@@ -847,6 +980,352 @@ impl DerefMut for GoalModes {
     }
 }
 
+/// Restriction on where the generator may place an item
+///
+/// Constraints apply to the items belonging to the world they are configured for, regardless which world's locations they end up in
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PlacementConstraint {
+    /// Never place the item in the zone
+    NotInZone { item: Item, zone: Zone },
+    /// Never place any skills in shops
+    NoSkillsInShops,
+    /// Place the item among the first locations that become reachable
+    ///
+    /// Locations that become reachable at the same time are considered equally early, so the item may end up in a location that
+    /// became reachable together with the nth location
+    WithinFirstReachable { item: Item, locations: usize },
+}
+impl Display for PlacementConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlacementConstraint::NotInZone { item, zone } => write!(f, "{item} not in {zone}"),
+            PlacementConstraint::NoSkillsInShops => write!(f, "No skills in shops"),
+            PlacementConstraint::WithinFirstReachable { item, locations } => {
+                write!(f, "{item} within the first {locations} reachable locations")
+            }
+        }
+    }
+}
+
+/// A partial plando, placing items on fixed locations
+///
+/// The generator will make sure these locations are reachable and fill the remaining locations in logic
+///
+/// # Examples
+///
+/// ```
+/// # use wotw_seedgen::settings::Plando;
+/// #
+/// let plando: Plando = serde_json::from_str("[{\"location\":\"MarshSpawn.RockHC\",\"item\":\"2|8\"}]").unwrap();
+/// assert_eq!(plando.placements[0].location, "MarshSpawn.RockHC");
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Plando {
+    /// The fixed placements
+    pub placements: Vec<PlandoPlacement>,
+}
+impl Plando {
+    /// Checks whether the plando contains no placements
+    pub fn is_empty(&self) -> bool {
+        self.placements.is_empty()
+    }
+}
+
+/// One fixed placement of a [`Plando`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PlandoPlacement {
+    /// Node identifier of the location from the logic file
+    pub location: String,
+    /// The [`Item`] to place
+    pub item: Item,
+}
+
+/// Amounts of hints to generate for each kind of hint
+///
+/// Hints are only generated if the active headers declare hint slots, if there are fewer slots than hints some of the hints will be left out
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct HintSettings {
+    /// Hints naming a zone which contains an item required to beat the seed
+    pub way_of_the_hero: usize,
+    /// Hints naming a zone which contains no items required to beat the seed
+    pub barren: usize,
+    /// Hints naming the zone of a specific item required to beat the seed
+    pub item_in_zone: usize,
+}
+impl Default for HintSettings {
+    fn default() -> HintSettings {
+        HintSettings {
+            way_of_the_hero: 3,
+            barren: 2,
+            item_in_zone: 3,
+        }
+    }
+}
+impl HintSettings {
+    /// Checks whether these are the default amounts
+    pub fn is_default(&self) -> bool {
+        *self == HintSettings::default()
+    }
+}
+
+/// Biases applied when the generator chooses items to place
+///
+/// The default weights don't bias anything
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PlacementWeights {
+    /// Multipliers on how likely specific items are chosen, items that aren't listed have a weight of 1
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub items: Vec<ItemWeight>,
+    /// Whether random placements should tend to happen early or late into the seed
+    #[serde(default)]
+    pub progression: ProgressionCurve,
+    /// How strongly skills are pushed towards the end of the seed
+    ///
+    /// 0 treats skills like any other item, negative values pull skills towards the start instead
+    #[serde(default)]
+    pub skill_depth: f32,
+}
+impl PlacementWeights {
+    /// Checks whether these weights don't bias anything
+    pub fn is_default(&self) -> bool {
+        *self == PlacementWeights::default()
+    }
+    /// Checks whether the weights for individual items are all neutral
+    ///
+    /// The [`ProgressionCurve`] may still be biased
+    pub fn has_neutral_items(&self) -> bool {
+        self.skill_depth == 0.
+            && self
+                .items
+                .iter()
+                .all(|item_weight| item_weight.weight == 1.)
+    }
+    /// Checks that every weight [`item_weight`](Self::item_weight) can return is a finite, non-negative number
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if let Some(item_weight) = self
+            .items
+            .iter()
+            .find(|item_weight| !(item_weight.weight.is_finite() && item_weight.weight >= 0.))
+        {
+            return Err(SettingsError::InvalidPlacementWeights(format!(
+                "The weight {} for {} has to be a finite number of at least 0",
+                item_weight.weight, item_weight.item
+            )));
+        }
+        // Skills are weighted strongest at the start of the seed
+        if let Some(item) = self
+            .items
+            .iter()
+            .map(|item_weight| &item_weight.item)
+            .chain(iter::once(&Item::Skill(Skill::Bash)))
+            .find(|item| !self.item_weight(item, 0.).is_finite())
+        {
+            return Err(SettingsError::InvalidPlacementWeights(format!(
+                "The skill depth {} makes the weight for {item} too large",
+                self.skill_depth
+            )));
+        }
+        Ok(())
+    }
+    /// The weight for choosing `item`, given how far into the seed the generator is
+    ///
+    /// `progress` ranges from 0 at the start to 1 once every location has been reached
+    pub fn item_weight(&self, item: &Item, progress: f32) -> f32 {
+        let mut weight = self
+            .items
+            .iter()
+            .filter(|item_weight| &item_weight.item == item)
+            .map(|item_weight| item_weight.weight)
+            .product::<f32>();
+        if matches!(item, Item::Skill(_)) {
+            weight *= (-self.skill_depth * (1. - progress)).exp();
+        }
+        weight
+    }
+}
+
+/// A weight multiplier for one [`Item`], used in [`PlacementWeights`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ItemWeight {
+    pub item: Item,
+    /// A weight of 2 makes the item twice as likely to be chosen, 0 prevents random placements of it
+    pub weight: f32,
+}
+
+/// How the chance to place items at random locations, instead of saving the location for later, changes over the course of the seed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, FromStr)]
+#[ParseFromIdentifier]
+#[derive(Default)]
+pub enum ProgressionCurve {
+    /// The chance stays the same throughout the seed
+    #[default]
+    Flat,
+    /// Items are likely to be placed in early locations, leaving fewer placements for late locations
+    Early,
+    /// Early locations tend to be saved for later, so items tend to be placed late
+    Late,
+}
+impl ProgressionCurve {
+    /// The chance to place an item into a random location, given how far into the seed the generator is
+    ///
+    /// `progress` ranges from 0 at the start to 1 once every location has been reached.
+    /// The curves have the same average chance, only its distribution over the seed differs
+    pub fn random_placement_chance(self, base_chance: f64, progress: f64) -> f64 {
+        let spread = base_chance.min(1. - base_chance) * 0.75;
+        match self {
+            ProgressionCurve::Flat => base_chance,
+            ProgressionCurve::Early => base_chance + spread * (1. - 2. * progress),
+            ProgressionCurve::Late => base_chance - spread * (1. - 2. * progress),
+        }
+    }
+}
+
+/// Settings for the distribution of Spirit Light
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SpiritLightSettings {
+    /// How the amounts of Spirit Light pickups are chosen
+    #[serde(default)]
+    pub mode: SpiritLightMode,
+    /// The minimum amount of Spirit Light the player must be able to collect before each further shop location becomes available in logic
+    ///
+    /// The first entry applies to the first shop location, the last entry applies to all remaining shop locations
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub shop_minimums: Vec<u32>,
+}
+impl SpiritLightSettings {
+    /// Checks whether these settings match the default distribution
+    pub fn is_default(&self) -> bool {
+        *self == SpiritLightSettings::default()
+    }
+    /// The minimum Spirit Light needed before opening another shop location, given how many shop locations were opened before
+    pub fn shop_minimum(&self, opened_shops: usize) -> u32 {
+        self.shop_minimums
+            .get(opened_shops)
+            .or_else(|| self.shop_minimums.last())
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// How the amounts of Spirit Light pickups are chosen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, FromStr)]
+#[ParseFromIdentifier]
+#[derive(Default)]
+pub enum SpiritLightMode {
+    /// Amounts grow over the course of the seed, with some random noise
+    #[default]
+    Standard,
+    /// Amounts are random, but always add up to exactly the Spirit Light in the item pool
+    Budget,
+    /// Amounts depend on how late into the seed the zone of the pickup becomes reachable
+    ZoneScaled,
+}
+
+/// Settings for the prices of shop items
+///
+/// Custom prices defined by headers are used as base prices
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields, default)]
+pub struct ShopPricing {
+    /// How the price of each item is derived from its base price
+    pub model: ShopPriceModel,
+    /// The lowest multiplier applied to base prices
+    pub min_factor: f32,
+    /// The highest multiplier applied to base prices
+    pub max_factor: f32,
+    /// Limit on the sum of all shop prices in this world
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_total: Option<u32>,
+    /// Whether to force enough Spirit Light before progression items in shops to afford all of them
+    pub affordable_progression: bool,
+}
+impl Default for ShopPricing {
+    fn default() -> ShopPricing {
+        ShopPricing {
+            model: ShopPriceModel::default(),
+            min_factor: 0.75,
+            max_factor: 1.25,
+            max_total: None,
+            affordable_progression: false,
+        }
+    }
+}
+impl ShopPricing {
+    /// Checks whether these settings match the default pricing
+    pub fn is_default(&self) -> bool {
+        *self == ShopPricing::default()
+    }
+    /// Checks that the factors are finite, at least 0 and [`min_factor`](Self::min_factor) isn't above [`max_factor`](Self::max_factor)
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if !(self.max_factor.is_finite() && (0. ..=self.max_factor).contains(&self.min_factor)) {
+            return Err(SettingsError::InvalidShopPricing(format!(
+                "The price factors {} to {} have to be finite numbers of at least 0, with the minimum not above the maximum",
+                self.min_factor, self.max_factor
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// How the price of a shop item is derived from its base price
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, FromStr)]
+#[ParseFromIdentifier]
+#[derive(Default)]
+pub enum ShopPriceModel {
+    /// Every item costs its base price
+    Fixed,
+    /// Base prices are multiplied with a random factor between [`ShopPricing::min_factor`] and [`ShopPricing::max_factor`]
+    #[default]
+    Random,
+    /// Progression items cost their base price times [`ShopPricing::max_factor`], all other items their base price times [`ShopPricing::min_factor`]
+    Progression,
+}
+
+/// Definition of the items to place in a world
+///
+/// Item pools are usually read from .json files in the current and /item_pools child directory, see the "standard" item pool for the default items
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ItemPool {
+    /// Name of another item pool to start from, otherwise the pool starts out empty
+    ///
+    /// When generating a seed, the item pool will be searched as .json file in the current and /item_pools child directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+    /// Total amount of Spirit Light to distribute, replacing the amount of the base pool
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spirit_light: Option<u32>,
+    /// Items in the pool, replacing their amount in the base pool
+    ///
+    /// An amount of 0 removes the item from the base pool
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub items: Vec<ItemPoolEntry>,
+}
+
+impl ItemPool {
+    /// An [`ItemPool`] using the named item pool without changes
+    pub fn from_base(base: String) -> ItemPool {
+        ItemPool {
+            base: Some(base),
+            ..ItemPool::default()
+        }
+    }
+}
+
+/// One item of an [`ItemPool`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ItemPoolEntry {
+    pub item: Item,
+    pub amount: u32,
+}
+
 /// Different types of online games that can be automatically created when generating the seed
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromStr)]
 #[ParseFromIdentifier]
@@ -865,6 +1344,69 @@ pub enum CreateGame {
     LockoutBingo,
 }
 
+/// How items are distributed between the worlds of a multiworld seed
+///
+/// Spirit Light always stays in its own world
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, FromStr, Display, Default)]
+#[ParseFromIdentifier]
+pub enum MultiworldSharing {
+    /// Items may be placed in any world
+    #[default]
+    Shuffled,
+    /// Progression items are only placed in their own world, other items may be placed in any world
+    LocalProgression,
+    /// Skills are only placed in their own world, resources and other items may be placed in any world
+    LocalSkills,
+    /// All worlds draw from one item pool and every item is granted to all worlds when collected
+    SharedPool,
+}
+impl MultiworldSharing {
+    /// Checks whether items are fully shuffled between worlds
+    pub fn is_default(&self) -> bool {
+        *self == MultiworldSharing::default()
+    }
+
+    /// Whether the [`Item`] may be placed in a different world than the one receiving it
+    pub fn may_share(self, item: &Item, difficulty: Difficulty) -> bool {
+        item.is_multiworld_spread()
+            && match self {
+                MultiworldSharing::Shuffled | MultiworldSharing::SharedPool => true,
+                MultiworldSharing::LocalProgression => !item.is_progression(difficulty),
+                MultiworldSharing::LocalSkills => !matches!(item, Item::Skill(_)),
+            }
+    }
+}
+
+/// Bounds for the total [`SeedScore`](crate::generator::SeedScore) of a seed's worlds
+///
+/// Both bounds are inclusive
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ScoreRange {
+    /// The lowest allowed score
+    pub min: Option<u32>,
+    /// The highest allowed score
+    pub max: Option<u32>,
+}
+impl ScoreRange {
+    /// Checks whether the score lies within the bounds
+    pub fn contains(&self, score: u32) -> bool {
+        self.min.is_none_or(|min| score >= min) && self.max.is_none_or(|max| score <= max)
+    }
+}
+impl Display for ScoreRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(min) = self.min {
+            write!(f, "{min}")?;
+        }
+        write!(f, "..")?;
+        if let Some(max) = self.max {
+            write!(f, "{max}")?;
+        }
+        Ok(())
+    }
+}
+
 /// Configuration parameter for a header
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -886,6 +1428,14 @@ pub struct InlineHeader {
     pub content: String,
 }
 
+/// Serializes a set in sorted order, so the settings don't depend on the iteration order of [`FxHashSet`]
+fn serialize_sorted<S: Serializer, T: Serialize + Ord>(
+    set: &FxHashSet<T>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(set.iter().sorted_unstable())
+}
+
 pub fn world_index_from_seed(seed: &str) -> Option<Result<usize, String>> {
     seed.lines()
         .find_map(|line| line.strip_prefix("// This World: "))
@@ -7,6 +7,7 @@ use std::{
 };
 
 use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
 use wotw_seedgen_derive::VVariant;
 
 use crate::header::{parser, vdisplay, CodeDisplay, VResolve};
@@ -106,7 +107,8 @@ impl Display for UberIdentifier {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, VVariant)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, VVariant, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "&str")]
 pub struct UberStateTrigger {
     pub identifier: UberIdentifier,
     #[VType]
@@ -164,6 +166,17 @@ impl FromStr for UberStateTrigger {
         Ok(item)
     }
 }
+impl From<UberStateTrigger> for String {
+    fn from(trigger: UberStateTrigger) -> String {
+        trigger.code().to_string()
+    }
+}
+impl TryFrom<&str> for UberStateTrigger {
+    type Error = String;
+    fn try_from(code: &str) -> Result<Self, Self::Error> {
+        UberStateTrigger::from_str(code)
+    }
+}
 impl UberStateTrigger {
     pub fn code(&self) -> CodeDisplay<UberStateTrigger> {
         CodeDisplay::new(self, |s, f| {
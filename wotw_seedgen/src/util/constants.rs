@@ -2,6 +2,8 @@ use super::Zone;
 use crate::item::{Item, Teleporter};
 use crate::uber_state::UberIdentifier;
 
+use std::num::NonZeroU16;
+
 use ansi_term::Colour;
 
 pub const DEFAULT_SPAWN: &str = "MarshSpawn.Main";
@@ -51,7 +53,8 @@ pub const PREFERRED_SPAWN_SLOTS: usize = 3;
 const _: usize = SPAWN_SLOTS - PREFERRED_SPAWN_SLOTS; // check that SPAWN_SLOTS >= PREFERRED_SPAWN_SLOTS
 pub const RESERVE_SLOTS: usize = 1; // how many slots to reserve after random placements for the next iteration
 pub const PLACEHOLDER_SLOTS: usize = 25; // how many slots to keep as placeholders for bigger progressions
-pub const RETRIES: u16 = 10; // How many retries to allow when generating a seed
+pub const RETRIES: NonZeroU16 = NonZeroU16::new(10).unwrap(); // Default for how many retries to allow when generating a seed
+pub const SCORE_RETRIES: NonZeroU16 = NonZeroU16::new(10).unwrap(); // Default for how many seeds to generate when they have to be within a score range
 pub const RANDOM_PROGRESSION: f64 = 0.4; // How likely to choose a progression item as random placement
 
 pub const HEADER_INDENT: usize = 24; // Which column to align header descriptions on
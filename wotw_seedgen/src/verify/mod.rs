@@ -0,0 +1,352 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use itertools::Itertools;
+use rand::{rngs::StdRng, SeedableRng};
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::{Deserialize, Serialize};
+
+use crate::generator::NodeSummary;
+use crate::header::Pickup;
+use crate::reach_check::apply_sets;
+use crate::settings::{CustomGoal, WorldSettings};
+use crate::uber_state::{UberIdentifier, UberStateTrigger};
+use crate::util::{self, constants::WISP_STATES};
+use crate::world::graph::{Graph, Node};
+use crate::world::World;
+use crate::{log, Header, Item};
+
+/// The anchor the final fight takes place at
+const FINAL_BOSS: &str = "WillowsEnd.ShriekArena";
+
+/// Result of verifying a seed with [`verify_seed_file`]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SeedVerification {
+    /// Whether the final boss and all goals are reachable
+    pub beatable: bool,
+    /// Whether the final boss can be reached
+    pub final_boss_reachable: bool,
+    /// The goals read from the seed's flags
+    pub goals: Vec<GoalVerification>,
+    /// Items placed on locations that can never be reached
+    pub unreachable_items: Vec<UnreachableItem>,
+}
+/// Whether one goal of the seed can be fulfilled
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GoalVerification {
+    /// The flag representing this goal, e.g. "All Wisps"
+    pub goal: String,
+    pub fulfilled: bool,
+}
+/// An item on a location that can never be reached
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UnreachableItem {
+    pub location: NodeSummary,
+    pub item: Item,
+}
+
+impl SeedVerification {
+    /// Serialize into json format
+    pub fn to_json(&self) -> String {
+        // This is safe because the SeedVerification struct is known to serialize successfully
+        serde_json::to_string(&self).unwrap()
+    }
+}
+
+impl Display for SeedVerification {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.beatable {
+            writeln!(f, "The seed is beatable")?;
+        } else {
+            writeln!(f, "The seed is not beatable")?;
+        }
+
+        let reachable = |reachable| {
+            if reachable {
+                "reachable"
+            } else {
+                "unreachable"
+            }
+        };
+        writeln!(f, "Final boss: {}", reachable(self.final_boss_reachable))?;
+        for goal in &self.goals {
+            let fulfilled = if goal.fulfilled {
+                "fulfilled"
+            } else {
+                "unfulfilled"
+            };
+            writeln!(f, "Goal {}: {fulfilled}", goal.goal)?;
+        }
+
+        if !self.unreachable_items.is_empty() {
+            writeln!(f, "\nUnreachable items:")?;
+            for unreachable in &self.unreachable_items {
+                writeln!(
+                    f,
+                    "{}: {}",
+                    unreachable.location.identifier, unreachable.item
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks whether a seed can be completed by replaying its placements through the logic
+///
+/// `seed_file` may be any seed file, the placements will be read from its pickup lines.
+/// Use [`WorldSettings::from_seed`] to use the same settings the seed was generated with, if available.
+///
+/// Items sent by other worlds in a multiworld seed are not part of the seed file and can't be taken into account
+pub fn verify_seed_file(
+    graph: &Graph,
+    seed_file: &str,
+    world_settings: &WorldSettings,
+) -> Result<SeedVerification, String> {
+    let pickups = parse_pickups(seed_file)?;
+    let excluded = FxHashSet::default();
+    let mut replay = Replay::new(graph, seed_file, world_settings, &pickups, &excluded)?;
+
+    let reached = replay.finish();
+    let verification = replay.verification(&reached)?;
+    log::trace!("Verified seed, beatable: {}", verification.beatable);
+
+    Ok(verification)
+}
+
+/// Reads all the pickups from a seed file
+pub(crate) fn parse_pickups(seed_file: &str) -> Result<Vec<Pickup>, String> {
+    // The preprocessor only needs randomness for header syntax that doesn't occur in generated seeds
+    let mut rng = StdRng::seed_from_u64(0);
+    // The spawn line isn't header syntax
+    let placements = seed_file
+        .lines()
+        .filter(|line| !line.starts_with("Spawn: "))
+        .join("\n");
+    let header = Header::parse(placements, &mut rng)
+        .map_err(|errors| format!("Failed to parse seed: {}", errors.verbose_display()))?;
+    Ok(header.build(FxHashMap::default())?.preplacements)
+}
+
+/// Parses the definition of a custom goal as written by the generator after `// Goal: `
+fn parse_custom_goal(definition: &str) -> Result<CustomGoal, String> {
+    let invalid = || format!("Invalid goal definition \"{definition}\"");
+    let (name, states) = definition.rsplit_once(": ").ok_or_else(invalid)?;
+    let (required, states) = states.split_once(" of ").ok_or_else(invalid)?;
+    let required = required.parse().map_err(|_| invalid())?;
+    let states = states
+        .split(", ")
+        .map(UberStateTrigger::from_str)
+        .collect::<Result<_, _>>()?;
+
+    Ok(CustomGoal {
+        name: name.to_string(),
+        required,
+        states,
+    })
+}
+
+/// Replays the pickups of a seed file, collecting everything reachable step by step
+pub(crate) struct Replay<'graph, 'settings, 'pickups> {
+    pub(crate) world: World<'graph, 'settings>,
+    spawn: &'graph Node,
+    seed_file: &'pickups str,
+    pickups: &'pickups [Pickup],
+    excluded: &'pickups FxHashSet<usize>,
+    collected: FxHashSet<usize>,
+}
+impl<'graph, 'settings, 'pickups> Replay<'graph, 'settings, 'pickups> {
+    /// Prepares the replay, the pickups with indices in `excluded` will never be granted
+    pub(crate) fn new(
+        graph: &'graph Graph,
+        seed_file: &'pickups str,
+        world_settings: &'settings WorldSettings,
+        pickups: &'pickups [Pickup],
+        excluded: &'pickups FxHashSet<usize>,
+    ) -> Result<Self, String> {
+        let mut world = World::new_spawn(graph, world_settings);
+        for (index, pickup) in pickups.iter().enumerate() {
+            if !excluded.contains(&index) {
+                world.preplace(pickup.trigger.clone(), pickup.item.clone());
+            }
+        }
+        apply_sets(&mut world, seed_file)?;
+
+        let spawn_identifier = util::spawn_from_seed(seed_file)?;
+        let spawn = graph.find_spawn(&spawn_identifier)?;
+
+        world.set_uber_state(UberIdentifier::spawn(), 1.);
+        world.set_uber_state(UberIdentifier::load(), 1.);
+        // Seeds with randomized doors mark all doors as unvisited, but seedgen assumes every connected door can be used
+        let doors = world
+            .uber_states()
+            .keys()
+            .filter(|identifier| identifier.uber_group == 27)
+            .map(|identifier| UberIdentifier::new(28, identifier.uber_id))
+            .collect::<Vec<_>>();
+        for door in doors {
+            world.set_uber_state(door, 1.);
+        }
+
+        Ok(Replay {
+            world,
+            spawn,
+            seed_file,
+            pickups,
+            excluded,
+            collected: FxHashSet::default(),
+        })
+    }
+
+    /// Collects everything that is currently reachable
+    ///
+    /// Returns all reached nodes and the nodes that have been newly collected
+    pub(crate) fn step(&mut self) -> (Vec<&'graph Node>, Vec<&'graph Node>) {
+        let world = &mut self.world;
+        let reached = world.graph.reached_locations(
+            &world.player,
+            self.spawn,
+            world.uber_states(),
+            &world.sets,
+        );
+
+        let mut new = reached
+            .iter()
+            .filter(|&&node| node.trigger().is_some() && !self.collected.contains(&node.index()))
+            .copied()
+            .collect::<Vec<_>>();
+        // Quest steps have to be set in order to collect the items on each of them
+        new.sort_unstable_by_key(|node| node.trigger().map_or(0, UberStateTrigger::set_value));
+
+        for node in &new {
+            self.collected.insert(node.index());
+            let trigger = node.trigger().unwrap();
+            world.set_incremental_uber_state(trigger.identifier, trigger.set_value() as f32);
+        }
+
+        (reached, new)
+    }
+
+    /// Collects everything reachable until no further progress can be made and returns all reached nodes
+    pub(crate) fn finish(&mut self) -> Vec<&'graph Node> {
+        loop {
+            let (reached, new) = self.step();
+            if new.is_empty() {
+                return reached;
+            }
+        }
+    }
+
+    /// Evaluates the final boss and goals based on the nodes reached after [`Replay::finish`]
+    pub(crate) fn verification(&self, reached: &[&Node]) -> Result<SeedVerification, String> {
+        let graph = self.world.graph;
+        let is_reached = |node: &Node| {
+            reached
+                .iter()
+                .any(|&reached| reached.index() == node.index())
+        };
+
+        let final_boss = graph
+            .nodes
+            .iter()
+            .find(|&node| node.identifier() == FINAL_BOSS)
+            .ok_or_else(|| format!("Final boss anchor {FINAL_BOSS} not found"))?;
+        let final_boss_reachable = graph.reaches_anchor(
+            &self.world.player,
+            self.spawn,
+            final_boss,
+            self.world.uber_states(),
+            &self.world.sets,
+        );
+
+        let locations = graph
+            .nodes
+            .iter()
+            .filter(|&node| node.can_place())
+            .filter_map(|node| node.trigger().map(|trigger| (trigger, node)))
+            .collect::<FxHashMap<_, _>>();
+        let mut unreachable_items = self
+            .pickups
+            .iter()
+            .filter(|pickup| {
+                !matches!(
+                    pickup.item,
+                    Item::Message(_) | Item::Command(_) | Item::ShopCommand(_)
+                )
+            })
+            .filter_map(|pickup| {
+                locations
+                    .get(&pickup.trigger)
+                    .filter(|&&node| !is_reached(node))
+                    .map(|node| UnreachableItem {
+                        location: node.summary(),
+                        item: pickup.item.clone(),
+                    })
+            })
+            .collect::<Vec<_>>();
+        unreachable_items.sort_by(|a, b| a.location.identifier.cmp(&b.location.identifier));
+
+        let custom_goals = self
+            .seed_file
+            .lines()
+            .filter_map(|line| line.strip_prefix("// Goal: "))
+            .map(parse_custom_goal)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let goals = self
+            .seed_file
+            .lines()
+            .find_map(|line| line.strip_prefix("Flags: "))
+            .into_iter()
+            .flat_map(|flags| flags.split(',').map(str::trim))
+            .filter_map(|flag| {
+                let fulfilled = match flag {
+                    "All Wisps" => WISP_STATES
+                        .iter()
+                        .all(|&wisp| self.world.get_uber_state(wisp) > 0.),
+                    "All Trees" => graph
+                        .nodes
+                        .iter()
+                        .filter(|&node| {
+                            matches!(node, Node::Pickup(_))
+                                && node
+                                    .trigger()
+                                    .is_some_and(|trigger| trigger.identifier.uber_group == 0)
+                        })
+                        .all(is_reached),
+                    "All Quests" => graph
+                        .nodes
+                        .iter()
+                        .filter(|&node| matches!(node, Node::Quest(_)))
+                        .all(is_reached),
+                    "Relics" => self.pickups.iter().enumerate().all(|(index, pickup)| {
+                        !matches!(pickup.item, Item::Relic(_))
+                            || !self.excluded.contains(&index)
+                                && locations
+                                    .get(&pickup.trigger)
+                                    .is_none_or(|&node| is_reached(node))
+                    }),
+                    _ => custom_goals
+                        .iter()
+                        .find(|goal| goal.name == flag)?
+                        .is_met(|identifier| self.world.get_uber_state(identifier)),
+                };
+                Some(GoalVerification {
+                    goal: flag.to_string(),
+                    fulfilled,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let beatable = final_boss_reachable && goals.iter().all(|goal| goal.fulfilled);
+
+        Ok(SeedVerification {
+            beatable,
+            final_boss_reachable,
+            goals,
+            unreachable_items,
+        })
+    }
+}
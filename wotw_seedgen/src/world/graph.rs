@@ -2,7 +2,7 @@ use std::collections::HashMap;
 use std::fmt;
 
 use super::{player::Player, requirement::Requirement};
-use crate::generator::doors::DoorId;
+use crate::generator::doors::{DoorData, DoorId};
 use crate::generator::NodeSummary;
 use crate::uber_state::{UberIdentifier, UberStateTrigger};
 use crate::util::{
@@ -10,6 +10,7 @@ use crate::util::{
     orbs::{self, OrbVariants},
     NodeKind, Position, RefillValue, Zone,
 };
+use itertools::Itertools;
 use rustc_hash::{FxHashMap, FxHashSet};
 use smallvec::smallvec;
 
@@ -181,9 +182,14 @@ pub struct Graph {
     pub nodes: Vec<Node>,
     pub spawn_pickup_node: Node,
     pub default_door_connections: HashMap<DoorId, DoorId>,
+    pub door_data: DoorData,
 }
 impl Graph {
-    pub fn new(nodes: Vec<Node>, default_door_connections: HashMap<DoorId, DoorId>) -> Graph {
+    pub fn new(
+        nodes: Vec<Node>,
+        default_door_connections: HashMap<DoorId, DoorId>,
+        door_data: DoorData,
+    ) -> Graph {
         let spawn_pickup_node = Node::Pickup(Pickup {
             identifier: String::from("Spawn"),
             zone: Zone::Spawn,
@@ -199,6 +205,7 @@ impl Graph {
             nodes,
             spawn_pickup_node,
             default_door_connections,
+            door_data,
         }
     }
 
@@ -396,6 +403,23 @@ impl Graph {
 
         context.reached
     }
+    /// Returns whether the given anchor can be reached from the spawn
+    pub fn reaches_anchor(
+        &self,
+        player: &Player,
+        spawn: &Node,
+        anchor: &Node,
+        extra_states: &FxHashMap<UberIdentifier, f32>,
+        sets: &[usize],
+    ) -> bool {
+        let mut context =
+            ReachContext::new(player, false, self.collect_extra_states(extra_states, sets));
+
+        self.reach_recursion(spawn, smallvec![player.max_orbs()], &mut context);
+        self.reached_by_teleporter(&mut context);
+
+        context.world_state.contains_key(&anchor.index())
+    }
     pub fn reached_and_progressions<'a>(
         &'a self,
         player: &Player,
@@ -410,7 +434,11 @@ impl Graph {
         self.reached_by_teleporter(&mut context);
 
         // add progressions containing states that were never met
-        for (_, state_progressions) in context.state_progressions {
+        for (_, state_progressions) in context
+            .state_progressions
+            .into_iter()
+            .sorted_unstable_by_key(|(state, _)| *state)
+        {
             for (from, connection) in state_progressions {
                 if !context.world_state.contains_key(&connection.to) {
                     context
@@ -8,6 +8,7 @@ pub use player::Player;
 pub use pool::Pool;
 pub use requirement::Requirement;
 
+use itertools::Itertools;
 use rustc_hash::FxHashMap;
 
 use crate::header::ItemDetails;
@@ -27,6 +28,7 @@ pub struct World<'graph, 'settings> {
     pub sets: Vec<usize>,
     pub custom_items: FxHashMap<Item, ItemDetails>,
     pub goals: Vec<Goal>,
+    pub hint_slots: Vec<UberStateTrigger>,
 }
 impl World<'_, '_> {
     /// Creates a new world with the given [`Graph`] and [`WorldSettings`]
@@ -44,6 +46,7 @@ impl World<'_, '_> {
             sets: Vec::default(),
             custom_items: FxHashMap::default(),
             goals: Vec::default(),
+            hint_slots: Vec::default(),
         }
     }
     /// Creates a new world with the given [`Graph`] and [`WorldSettings`]
@@ -181,6 +184,7 @@ impl World<'_, '_> {
         let collected = self
             .preplacements
             .iter()
+            .sorted_unstable_by_key(|(trigger, _)| *trigger)
             .filter_map(|(trigger, items)| {
                 if trigger.check(identifier, new)
                     && (trigger.condition.is_none() || !trigger.check_value(old))
@@ -264,11 +268,14 @@ mod tests {
         let graph =
             logic::parse_logic(&areas, &locations, &states, &universe_settings, false).unwrap();
         let mut world = World::new(&graph, &universe_settings.world_settings[0]);
-        world.player.inventory = Pool::preset().inventory;
+        world.player.inventory = Pool::standard().inventory;
         world.player.inventory.grant(Item::SpiritLight(1), 10000);
 
         for (from_door, to_door) in &graph.default_door_connections {
-            world.set_uber_state(UberIdentifier::new(27, (*from_door).into()), *to_door as f32);
+            world.set_uber_state(
+                UberIdentifier::new(27, (*from_door).into()),
+                *to_door as f32,
+            );
             world.set_uber_state(UberIdentifier::new(28, (*from_door).into()), 1f32);
         }
 
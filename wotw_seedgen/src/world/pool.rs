@@ -1,9 +1,12 @@
 use rand::{seq::SliceRandom, Rng};
-use rustc_hash::FxHashMap;
 
+use crate::files::{FileAccess, NO_FILE_ACCESS};
 use crate::inventory::Inventory;
-use crate::item::{BonusUpgrade, Item, Resource, Shard, Skill};
+use crate::item::Item;
 use crate::log;
+use crate::settings::{ItemPool, ItemPoolEntry};
+
+const STANDARD_ITEM_POOL: &str = include_str!("../../item_pools/standard.json");
 
 // TODO the pool could be an inventory probably
 #[derive(Default, Debug, Clone)]
@@ -12,78 +15,69 @@ pub struct Pool {
     pub spirit_light: u32,
 }
 impl Pool {
-    pub fn preset() -> Pool {
-        let mut items = FxHashMap::default();
-
-        items.insert(Item::Resource(Resource::HealthFragment), 24);
-        items.insert(Item::Resource(Resource::EnergyFragment), 24);
-        items.insert(Item::Resource(Resource::GorlekOre), 40);
-        items.insert(Item::Resource(Resource::Keystone), 34);
-        items.insert(Item::Resource(Resource::ShardSlot), 5);
-        items.insert(Item::Skill(Skill::Bash), 1);
-        items.insert(Item::Skill(Skill::DoubleJump), 1);
-        items.insert(Item::Skill(Skill::Launch), 1);
-        items.insert(Item::Skill(Skill::Glide), 1);
-        items.insert(Item::Skill(Skill::WaterBreath), 1);
-        items.insert(Item::Skill(Skill::Grenade), 1);
-        items.insert(Item::Skill(Skill::Grapple), 1);
-        items.insert(Item::Skill(Skill::Flash), 1);
-        items.insert(Item::Skill(Skill::Spear), 1);
-        items.insert(Item::Skill(Skill::Regenerate), 1);
-        items.insert(Item::Skill(Skill::Bow), 1);
-        items.insert(Item::Skill(Skill::Hammer), 1);
-        items.insert(Item::Skill(Skill::Sword), 1);
-        items.insert(Item::Skill(Skill::Burrow), 1);
-        items.insert(Item::Skill(Skill::Dash), 1);
-        items.insert(Item::Skill(Skill::WaterDash), 1);
-        items.insert(Item::Skill(Skill::Shuriken), 1);
-        items.insert(Item::Skill(Skill::Blaze), 1);
-        items.insert(Item::Skill(Skill::Sentry), 1);
-        items.insert(Item::Skill(Skill::Flap), 1);
-        items.insert(Item::Skill(Skill::GladesAncestralLight), 1);
-        items.insert(Item::Skill(Skill::InkwaterAncestralLight), 1);
-        items.insert(Item::Water, 1);
-        items.insert(Item::Shard(Shard::Overcharge), 1);
-        items.insert(Item::Shard(Shard::TripleJump), 1);
-        items.insert(Item::Shard(Shard::Wingclip), 1);
-        items.insert(Item::Shard(Shard::Bounty), 1);
-        items.insert(Item::Shard(Shard::Swap), 1);
-        items.insert(Item::Shard(Shard::Magnet), 1);
-        items.insert(Item::Shard(Shard::Splinter), 1);
-        items.insert(Item::Shard(Shard::Reckless), 1);
-        items.insert(Item::Shard(Shard::Quickshot), 1);
-        items.insert(Item::Shard(Shard::Resilience), 1);
-        items.insert(Item::Shard(Shard::SpiritLightHarvest), 1);
-        items.insert(Item::Shard(Shard::Vitality), 1);
-        items.insert(Item::Shard(Shard::LifeHarvest), 1);
-        items.insert(Item::Shard(Shard::EnergyHarvest), 1);
-        items.insert(Item::Shard(Shard::Energy), 1);
-        items.insert(Item::Shard(Shard::LifePact), 1);
-        items.insert(Item::Shard(Shard::LastStand), 1);
-        items.insert(Item::Shard(Shard::Sense), 1);
-        items.insert(Item::Shard(Shard::UltraBash), 1);
-        items.insert(Item::Shard(Shard::UltraGrapple), 1);
-        items.insert(Item::Shard(Shard::Overflow), 1);
-        items.insert(Item::Shard(Shard::Thorn), 1);
-        items.insert(Item::Shard(Shard::Catalyst), 1);
-        items.insert(Item::Shard(Shard::Turmoil), 1);
-        items.insert(Item::Shard(Shard::Sticky), 1);
-        items.insert(Item::Shard(Shard::Finesse), 1);
-        items.insert(Item::Shard(Shard::SpiritSurge), 1);
-        items.insert(Item::Shard(Shard::Lifeforce), 1);
-        items.insert(Item::Shard(Shard::Deflector), 1);
-        items.insert(Item::Shard(Shard::Fracture), 1);
-        items.insert(Item::Shard(Shard::Arcing), 1);
-        items.insert(Item::BonusUpgrade(BonusUpgrade::ExplodingSpike), 1);
-        items.insert(Item::BonusUpgrade(BonusUpgrade::HammerShockwave), 1);
-        items.insert(Item::BonusUpgrade(BonusUpgrade::StaticShuriken), 1);
-        items.insert(Item::BonusUpgrade(BonusUpgrade::ChargeBlaze), 1);
-        items.insert(Item::BonusUpgrade(BonusUpgrade::RapidSentry), 1);
-
-        Pool {
-            inventory: Inventory { items },
-            spirit_light: 20000,
+    /// The standard item pool, defined in "item_pools/standard.json"
+    pub fn standard() -> Pool {
+        // The standard item pool is part of the crate and known to be valid
+        let item_pool = serde_json::from_str(STANDARD_ITEM_POOL).unwrap();
+        Pool::from_item_pool(&item_pool, &NO_FILE_ACCESS).unwrap()
+    }
+
+    /// Build the pool described by an [`ItemPool`], reading its base pools using the [`FileAccess`]
+    ///
+    /// A base pool named "standard" always refers to [`Pool::standard`]
+    pub fn from_item_pool(
+        item_pool: &ItemPool,
+        file_access: &impl FileAccess,
+    ) -> Result<Pool, String> {
+        Pool::from_item_pool_guarded(item_pool, &mut vec![], file_access)
+    }
+
+    /// Inner method to memorize base pools to prevent cyclic patterns
+    fn from_item_pool_guarded(
+        item_pool: &ItemPool,
+        already_applied: &mut Vec<String>,
+        file_access: &impl FileAccess,
+    ) -> Result<Pool, String> {
+        let mut pool = match &item_pool.base {
+            None => Pool::default(),
+            Some(base) if base == "standard" => Pool::standard(),
+            Some(base) => {
+                if already_applied.contains(base) {
+                    return Err(format!("Item pool {base} uses itself as base"));
+                }
+                already_applied.push(base.clone());
+
+                let base_pool = file_access.read_item_pool(base)?;
+                let base_pool = serde_json::from_str(&base_pool)
+                    .map_err(|err| format!("Failed to parse item pool {base}: {err}"))?;
+                Pool::from_item_pool_guarded(&base_pool, already_applied, file_access)?
+            }
+        };
+
+        if let Some(spirit_light) = item_pool.spirit_light {
+            pool.spirit_light = spirit_light;
+        }
+        for (index, ItemPoolEntry { item, amount }) in item_pool.items.iter().enumerate() {
+            if let Item::SpiritLight(_) = item {
+                return Err(format!(
+                    "Use spiritLight to define the amount of {item} in the item pool"
+                ));
+            }
+            if item_pool.items[..index]
+                .iter()
+                .any(|entry| &entry.item == item)
+            {
+                return Err(format!("{item} is listed multiple times in the item pool"));
+            }
+
+            if *amount == 0 {
+                pool.inventory.items.remove(item);
+            } else {
+                pool.inventory.items.insert(item.clone(), *amount);
+            }
         }
+
+        Ok(pool)
     }
 
     pub fn grant(&mut self, item: Item, amount: u32) {
@@ -115,14 +109,24 @@ impl Pool {
         true
     }
 
-    pub fn choose_random(&self, multiworld_spread: bool, rng: &mut impl Rng) -> Option<&Item> {
-        let mut items = self.inventory.items.iter().collect::<Vec<_>>();
-        if multiworld_spread {
-            items.retain(|&(item, _)| item.is_multiworld_spread());
-        }
+    /// Chooses a random item that `is_allowed`, weighted by its amount in the pool and the optional `weight`
+    pub fn choose_random(
+        &self,
+        is_allowed: impl Fn(&Item) -> bool,
+        weight: Option<&dyn Fn(&Item) -> f32>,
+        rng: &mut impl Rng,
+    ) -> Option<&Item> {
+        let mut items = self.inventory.sorted_items();
+        items.retain(|&(item, _)| is_allowed(item));
 
         loop {
-            let (item, _) = *items.choose_weighted(rng, |&(_, amount)| amount).ok()?;
+            let (item, _) = *match weight {
+                None => items.choose_weighted(rng, |&(_, amount)| amount),
+                Some(weight) => {
+                    items.choose_weighted(rng, |&(item, amount)| *amount as f32 * weight(item))
+                }
+            }
+            .ok()?;
             let cost = item.cost();
 
             if cost > 10000 {
@@ -138,3 +142,164 @@ impl Pool {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::files::FILE_SYSTEM_ACCESS;
+    use crate::item::{Resource, Shard};
+    use crate::settings::UniverseSettings;
+    use crate::tests::test_graph;
+
+    fn named_pool(name: &str) -> Pool {
+        let item_pool = ItemPool {
+            base: Some(name.to_string()),
+            spirit_light: None,
+            items: vec![],
+        };
+        Pool::from_item_pool(&item_pool, &FILE_SYSTEM_ACCESS).unwrap()
+    }
+    fn shard_count(pool: &Pool) -> u32 {
+        pool.inventory
+            .items
+            .iter()
+            .filter(|(item, _)| matches!(item, Item::Shard(_)))
+            .map(|(_, amount)| amount)
+            .sum()
+    }
+
+    #[test]
+    fn standard_pool() {
+        let pool = Pool::standard();
+        assert_eq!(pool.spirit_light, 20000);
+        for (resource, amount) in [
+            (Resource::HealthFragment, 24),
+            (Resource::EnergyFragment, 24),
+            (Resource::GorlekOre, 40),
+            (Resource::Keystone, 34),
+            (Resource::ShardSlot, 5),
+        ] {
+            assert_eq!(pool.inventory.get(&Item::Resource(resource)), amount);
+        }
+        assert_eq!(shard_count(&pool), 31);
+    }
+
+    #[test]
+    fn base_pools() {
+        let standard = Pool::standard();
+
+        let no_shards = named_pool("no_shards");
+        assert_eq!(shard_count(&no_shards), 0);
+        assert!(!no_shards
+            .inventory
+            .has_any(&Item::Resource(Resource::ShardSlot)));
+        assert_eq!(
+            no_shards.inventory.item_count(),
+            standard.inventory.item_count() - 31 - 5
+        );
+        assert_eq!(no_shards.spirit_light, standard.spirit_light);
+
+        let extra_ore = named_pool("extra_ore");
+        assert_eq!(
+            extra_ore
+                .inventory
+                .get(&Item::Resource(Resource::GorlekOre)),
+            60
+        );
+        assert_eq!(
+            extra_ore.inventory.item_count(),
+            standard.inventory.item_count() + 20
+        );
+
+        let half_health = named_pool("half_health");
+        assert_eq!(
+            half_health
+                .inventory
+                .get(&Item::Resource(Resource::HealthFragment)),
+            12
+        );
+        assert_eq!(
+            half_health.inventory.item_count(),
+            standard.inventory.item_count() - 12
+        );
+    }
+
+    #[test]
+    fn pool_entries() {
+        let item_pool = ItemPool {
+            base: Some("no_shards".to_string()),
+            spirit_light: Some(5000),
+            items: vec![
+                ItemPoolEntry {
+                    item: Item::Shard(Shard::Overcharge),
+                    amount: 1,
+                },
+                ItemPoolEntry {
+                    item: Item::Resource(Resource::Keystone),
+                    amount: 0,
+                },
+            ],
+        };
+        let pool = Pool::from_item_pool(&item_pool, &FILE_SYSTEM_ACCESS).unwrap();
+        assert_eq!(pool.spirit_light, 5000);
+        assert_eq!(shard_count(&pool), 1);
+        assert_eq!(pool.inventory.get(&Item::Shard(Shard::Overcharge)), 1);
+        assert!(!pool.inventory.has_any(&Item::Resource(Resource::Keystone)));
+
+        let spirit_light = ItemPool {
+            base: None,
+            spirit_light: None,
+            items: vec![ItemPoolEntry {
+                item: Item::SpiritLight(100),
+                amount: 1,
+            }],
+        };
+        assert!(Pool::from_item_pool(&spirit_light, &NO_FILE_ACCESS).is_err());
+
+        let duplicate = ItemPool {
+            base: None,
+            spirit_light: None,
+            items: vec![
+                ItemPoolEntry {
+                    item: Item::Shard(Shard::Overcharge),
+                    amount: 1,
+                },
+                ItemPoolEntry {
+                    item: Item::Shard(Shard::Overcharge),
+                    amount: 2,
+                },
+            ],
+        };
+        assert!(Pool::from_item_pool(&duplicate, &NO_FILE_ACCESS).is_err());
+    }
+
+    #[test]
+    fn item_pool_placements() {
+        let mut universe_settings = UniverseSettings {
+            seed: "item_pool".to_string(),
+            ..UniverseSettings::default()
+        };
+        universe_settings.world_settings[0].item_pool = Some(ItemPool {
+            base: Some("no_shards".to_string()),
+            spirit_light: None,
+            items: vec![ItemPoolEntry {
+                item: Item::Shard(Shard::Overcharge),
+                amount: 1,
+            }],
+        });
+        let graph = test_graph(&universe_settings);
+
+        let seed = crate::generate_seed(&graph, &FILE_SYSTEM_ACCESS, &universe_settings).unwrap();
+        let placements = &seed.worlds[0].placements;
+        let shards = placements
+            .iter()
+            .filter(|placement| matches!(placement.item, Item::Shard(_)))
+            .collect::<Vec<_>>();
+        assert_eq!(shards.len(), 1);
+        assert_eq!(shards[0].item, Item::Shard(Shard::Overcharge));
+        assert!(!placements
+            .iter()
+            .any(|placement| placement.item == Item::Resource(Resource::ShardSlot)));
+    }
+}
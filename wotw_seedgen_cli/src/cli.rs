@@ -1,6 +1,7 @@
 use std::error::Error;
 use std::fmt;
-use std::num::NonZeroUsize;
+use std::fs;
+use std::num::{NonZeroU16, NonZeroUsize};
 use std::ops::Deref;
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -8,10 +9,16 @@ use std::str::FromStr;
 use rustc_hash::FxHashSet;
 use structopt::StructOpt;
 
-use wotw_seedgen::item::{Shard, Skill, Teleporter};
+use wotw_seedgen::item::{Item, Shard, Skill, Teleporter};
 use wotw_seedgen::preset::{PresetGroup, PresetInfo, UniversePreset, WorldPreset};
-use wotw_seedgen::settings::{Difficulty, Goal, HeaderConfig, InlineHeader, Spawn, Trick};
+use wotw_seedgen::settings::{
+    Difficulty, Goal, HeaderConfig, HintSettings, InlineHeader, ItemPool, ItemWeight,
+    MultiworldSharing, PlacementConstraint, PlacementWeights, ProgressionCurve, ScoreRange,
+    ShopPriceModel, ShopPricing, Spawn, SpiritLightMode, SpiritLightSettings, Trick,
+};
 use wotw_seedgen::util::Zone;
+use wotw_seedgen_stats::analyzers::CustomStats;
+use wotw_seedgen_stats::WorldScope;
 
 #[derive(StructOpt)]
 /// Generate seeds for the Ori 2 randomizer.
@@ -52,13 +59,15 @@ pub enum SeedGenCommand {
     ///
     /// The resulting statistics will be written into a "stats" folder, you can read them out there
     ///
-    /// This command also maintains a cache of seeds in a "seed_storage" folder, you do not need to interact with this folder (although you won't break anything either if you delete it or such)
+    /// This command also maintains a cache of seeds in a "seed_storage" folder or "seed_storage.pack" archive, you do not need to interact with these (although you won't break anything either if you delete them or such)
     Stats {
         #[structopt(flatten)]
         args: StatsArgs,
     },
     /// Deletes all cached seeds used to generate statistics
     CleanStatsCache,
+    /// Moves the cached seeds from the "seed_storage" folder into the "seed_storage.pack" archive
+    MigrateStatsCache,
     /// Check which locations are in logic
     ReachCheck {
         #[structopt(flatten)]
@@ -76,6 +85,11 @@ pub enum SeedGenCommand {
         #[structopt(flatten)]
         args: RegenerateArgs,
     },
+    /// Check whether a seed can be completed by replaying its placements through the logic
+    Verify {
+        #[structopt(flatten)]
+        args: VerifyArgs,
+    },
 }
 
 #[derive(StructOpt)]
@@ -109,6 +123,16 @@ pub struct SeedMetaArgs {
     /// skip validating the input files for a slight performance gain
     #[structopt(long)]
     pub trust: bool,
+    /// how many attempts to place items are allowed before giving up
+    ///
+    /// defaults to the generator's default amount of retries
+    #[structopt(long)]
+    pub retries: Option<NonZeroU16>,
+    /// run up to this many attempts to place items at once
+    ///
+    /// Each attempt uses its own random state derived from the seed, so the result differs from serial generation but is the same for any amount of parallel attempts
+    #[structopt(long)]
+    pub parallel_attempts: Option<NonZeroUsize>,
     /// write the seed to stdout instead of a file
     #[structopt(long)]
     pub tostdout: bool,
@@ -118,12 +142,18 @@ pub struct SeedMetaArgs {
     /// use json output where possible
     ///
     /// If --tostdout is enabled, a json object with all output data is written to stdout.
+    /// If generation fails in that case, a json object describing the error is written to stdout instead.
     /// If --tostdout is disabled, only spoilers will be written as json files.
     #[structopt(long)]
     pub json: bool,
     /// launch the seed after generating
     #[structopt(short, long)]
     pub launch: bool,
+    /// also write a playthrough listing only the items required to beat the seed
+    ///
+    /// This replays the seed many times and may take a while
+    #[structopt(long)]
+    pub playthrough: bool,
 }
 
 #[derive(StructOpt)]
@@ -377,6 +407,291 @@ impl FromStr for InlineHeaderOpt {
         Ok(InlineHeaderOpt(inline_header))
     }
 }
+/// Newtype to parse placement constraints
+#[derive(Clone)]
+pub struct PlacementConstraintOpt(PlacementConstraint);
+impl PlacementConstraintOpt {
+    fn into_inner(self) -> PlacementConstraint {
+        self.0
+    }
+}
+impl FromStr for PlacementConstraintOpt {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (identifier, details) = s.split_once(':').unwrap_or((s, ""));
+
+        let constraint = match identifier {
+            "noskillsinshops" => PlacementConstraint::NoSkillsInShops,
+            "notinzone" => {
+                let (item, zone) = details.rsplit_once(':').ok_or_else(|| {
+                    format!("Expected <item>:<zone> in details string for constraint {s}")
+                })?;
+                let item = Item::from_str(item)?;
+                let zone = Zone::from_str(zone)
+                    .map_err(|_| format!("Invalid zone in details string for constraint {s}"))?;
+                PlacementConstraint::NotInZone { item, zone }
+            }
+            "early" => {
+                let (item, locations) = details.rsplit_once(':').ok_or_else(|| {
+                    format!("Expected <item>:<amount> in details string for constraint {s}")
+                })?;
+                let item = Item::from_str(item)?;
+                let locations = locations
+                    .parse()
+                    .map_err(|_| format!("Invalid amount in details string for constraint {s}"))?;
+                PlacementConstraint::WithinFirstReachable { item, locations }
+            }
+            other => return Err(format!("Unknown constraint {other}")),
+        };
+
+        Ok(PlacementConstraintOpt(constraint))
+    }
+}
+/// Newtype to parse score ranges
+#[derive(Clone)]
+pub struct ScoreRangeOpt(ScoreRange);
+impl ScoreRangeOpt {
+    fn into_inner(self) -> ScoreRange {
+        self.0
+    }
+}
+impl FromStr for ScoreRangeOpt {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (min, max) = s
+            .split_once("..")
+            .ok_or_else(|| format!("Expected <min>..<max> in score range {s}"))?;
+        let parse_bound = |bound: &str| {
+            if bound.is_empty() {
+                Ok(None)
+            } else {
+                bound
+                    .parse()
+                    .map(Some)
+                    .map_err(|_| format!("Invalid bound {bound} in score range {s}"))
+            }
+        };
+
+        let score_range = ScoreRange {
+            min: parse_bound(min)?,
+            max: parse_bound(max)?,
+        };
+
+        Ok(ScoreRangeOpt(score_range))
+    }
+}
+/// Newtype to parse hint amounts
+#[derive(Clone)]
+pub struct HintsOpt(HintKind, usize);
+#[derive(Clone, Copy)]
+enum HintKind {
+    WayOfTheHero,
+    Barren,
+    ItemInZone,
+}
+impl HintsOpt {
+    /// Applies the given amounts on top of the default [`HintSettings`], or [`None`] if no amounts were given
+    fn into_hint_settings(hints: Vec<HintsOpt>) -> Option<HintSettings> {
+        if hints.is_empty() {
+            return None;
+        }
+
+        let mut hint_settings = HintSettings::default();
+        for HintsOpt(kind, amount) in hints {
+            match kind {
+                HintKind::WayOfTheHero => hint_settings.way_of_the_hero = amount,
+                HintKind::Barren => hint_settings.barren = amount,
+                HintKind::ItemInZone => hint_settings.item_in_zone = amount,
+            }
+        }
+        Some(hint_settings)
+    }
+}
+impl FromStr for HintsOpt {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (identifier, amount) = s
+            .split_once(':')
+            .ok_or_else(|| format!("Expected <kind>:<amount> in hint amount {s}"))?;
+
+        let kind = match identifier {
+            "woth" => HintKind::WayOfTheHero,
+            "barren" => HintKind::Barren,
+            "item" => HintKind::ItemInZone,
+            other => return Err(format!("Unknown hint kind {other}")),
+        };
+        let amount = amount
+            .parse()
+            .map_err(|_| format!("Invalid amount in hint amount {s}"))?;
+
+        Ok(HintsOpt(kind, amount))
+    }
+}
+
+/// Newtype to parse placement weights
+#[derive(Clone)]
+pub enum PlacementWeightOpt {
+    Item(ItemWeight),
+    Progression(ProgressionCurve),
+    SkillDepth(f32),
+}
+impl PlacementWeightOpt {
+    /// Applies the given weights on top of the default [`PlacementWeights`], or [`None`] if no weights were given
+    fn into_placement_weights(weights: Vec<PlacementWeightOpt>) -> Option<PlacementWeights> {
+        if weights.is_empty() {
+            return None;
+        }
+
+        let mut placement_weights = PlacementWeights::default();
+        for weight in weights {
+            match weight {
+                PlacementWeightOpt::Item(item_weight) => placement_weights.items.push(item_weight),
+                PlacementWeightOpt::Progression(curve) => placement_weights.progression = curve,
+                PlacementWeightOpt::SkillDepth(depth) => placement_weights.skill_depth = depth,
+            }
+        }
+        Some(placement_weights)
+    }
+}
+impl FromStr for PlacementWeightOpt {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (identifier, details) = s
+            .split_once(':')
+            .ok_or_else(|| format!("Expected <kind>:<details> in placement weight {s}"))?;
+
+        let weight = match identifier {
+            "item" => {
+                let (item, weight) = details.rsplit_once(':').ok_or_else(|| {
+                    format!("Expected item:<item>:<weight> in placement weight {s}")
+                })?;
+                let item = Item::from_str(item)?;
+                let weight = weight
+                    .parse()
+                    .map_err(|_| format!("Invalid weight in placement weight {s}"))?;
+                PlacementWeightOpt::Item(ItemWeight { item, weight })
+            }
+            "progression" => PlacementWeightOpt::Progression(
+                ProgressionCurve::from_str(details)
+                    .map_err(|_| format!("Unknown progression curve in placement weight {s}"))?,
+            ),
+            "skills" => PlacementWeightOpt::SkillDepth(
+                details
+                    .parse()
+                    .map_err(|_| format!("Invalid skill depth in placement weight {s}"))?,
+            ),
+            other => return Err(format!("Unknown placement weight {other}")),
+        };
+
+        Ok(weight)
+    }
+}
+
+/// Newtype to parse spirit light settings
+#[derive(Clone)]
+pub enum SpiritLightOpt {
+    Mode(SpiritLightMode),
+    ShopMinimum(u32),
+}
+impl SpiritLightOpt {
+    /// Applies the given options on top of the default [`SpiritLightSettings`], or [`None`] if no options were given
+    fn into_spirit_light_settings(options: Vec<SpiritLightOpt>) -> Option<SpiritLightSettings> {
+        if options.is_empty() {
+            return None;
+        }
+
+        let mut spirit_light = SpiritLightSettings::default();
+        for option in options {
+            match option {
+                SpiritLightOpt::Mode(mode) => spirit_light.mode = mode,
+                SpiritLightOpt::ShopMinimum(amount) => spirit_light.shop_minimums.push(amount),
+            }
+        }
+        Some(spirit_light)
+    }
+}
+impl FromStr for SpiritLightOpt {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some(("shop", amount)) => Ok(SpiritLightOpt::ShopMinimum(
+                amount
+                    .parse()
+                    .map_err(|_| format!("Invalid shop minimum in spirit light option {s}"))?,
+            )),
+            Some((other, _)) => Err(format!("Unknown spirit light option {other}")),
+            None => Ok(SpiritLightOpt::Mode(
+                SpiritLightMode::from_str(s)
+                    .map_err(|_| format!("Unknown spirit light mode {s}"))?,
+            )),
+        }
+    }
+}
+
+/// Newtype to parse shop pricing options
+#[derive(Clone)]
+pub enum ShopPricingOpt {
+    Model(ShopPriceModel),
+    Range(f32, f32),
+    MaxTotal(u32),
+    AffordableProgression,
+}
+impl ShopPricingOpt {
+    /// Applies the given options on top of the default [`ShopPricing`], or [`None`] if no options were given
+    fn into_shop_pricing(options: Vec<ShopPricingOpt>) -> Option<ShopPricing> {
+        if options.is_empty() {
+            return None;
+        }
+
+        let mut shop_pricing = ShopPricing::default();
+        for option in options {
+            match option {
+                ShopPricingOpt::Model(model) => shop_pricing.model = model,
+                ShopPricingOpt::Range(min, max) => {
+                    shop_pricing.min_factor = min;
+                    shop_pricing.max_factor = max;
+                }
+                ShopPricingOpt::MaxTotal(max_total) => shop_pricing.max_total = Some(max_total),
+                ShopPricingOpt::AffordableProgression => shop_pricing.affordable_progression = true,
+            }
+        }
+        Some(shop_pricing)
+    }
+}
+impl FromStr for ShopPricingOpt {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some(("range", range)) => {
+                let (min, max) = range
+                    .split_once(':')
+                    .ok_or_else(|| format!("Expected range:<min>:<max> in shop pricing {s}"))?;
+                let parse_factor = |factor: &str| {
+                    factor
+                        .parse()
+                        .map_err(|_| format!("Invalid factor in shop pricing {s}"))
+                };
+                Ok(ShopPricingOpt::Range(parse_factor(min)?, parse_factor(max)?))
+            }
+            Some(("max", max_total)) => Ok(ShopPricingOpt::MaxTotal(
+                max_total
+                    .parse()
+                    .map_err(|_| format!("Invalid total in shop pricing {s}"))?,
+            )),
+            Some((other, _)) => Err(format!("Unknown shop pricing option {other}")),
+            None if s == "affordable" => Ok(ShopPricingOpt::AffordableProgression),
+            None => Ok(ShopPricingOpt::Model(
+                ShopPriceModel::from_str(s).map_err(|_| format!("Unknown shop price model {s}"))?,
+            )),
+        }
+    }
+}
 
 #[derive(StructOpt)]
 pub struct SeedSettings {
@@ -434,6 +749,36 @@ pub struct SeedSettings {
     /// Inline header syntax
     #[structopt(short, long = "inline")]
     pub inline_headers: Vec<WorldOpt<InlineHeaderOpt>>,
+    /// Restrictions on where items may be placed
+    ///
+    /// Available constraints are notinzone:<item>:<zone>, noskillsinshops and early:<item>:<amount> to place an item within the first <amount> reachable locations
+    #[structopt(long)]
+    pub constraints: Vec<WorldOpt<PlacementConstraintOpt>>,
+    /// How many hints of each kind to generate for the hint slots declared by headers
+    ///
+    /// Format for one kind: <kind>:<amount>. Available kinds are woth, barren and item, unspecified kinds use their default amounts
+    #[structopt(long)]
+    pub hints: Vec<WorldOpt<HintsOpt>>,
+    /// Biases when choosing items for placements
+    ///
+    /// Available weights are item:<item>:<weight>, progression:<curve> with the curves flat, early and late, and skills:<depth> to push skills towards the end of the seed
+    #[structopt(long)]
+    pub weights: Vec<WorldOpt<PlacementWeightOpt>>,
+    /// Item pool to use instead of the standard pool
+    ///
+    /// The item pool will be searched as .json file in the current and /item_pools child directory
+    #[structopt(long)]
+    pub item_pool: Vec<WorldOpt<String>>,
+    /// How Spirit Light is distributed and what shops cost
+    ///
+    /// Available modes are standard, budget and zonescaled. Each shop:<amount> adds a tier of minimum Spirit Light that has to be reachable before opening further shop locations
+    #[structopt(long)]
+    pub spirit_light: Vec<WorldOpt<SpiritLightOpt>>,
+    /// How shop prices are chosen
+    ///
+    /// Available models are fixed, random and progression. range:<min>:<max> sets the price multipliers, max:<total> limits the sum of all shop prices and affordable forces enough Spirit Light to buy all progression items in shops
+    #[structopt(long)]
+    pub shop_prices: Vec<WorldOpt<ShopPricingOpt>>,
     /// Disallow the use of the In-Logic filter while playing the seed
     #[structopt(short = "L", long)]
     pub disable_logic_filter: bool,
@@ -442,6 +787,16 @@ pub struct SeedSettings {
     /// This is needed for Co-op, Multiworld and Bingo
     #[structopt(short, long)]
     pub online: bool,
+    /// How items are distributed between the worlds of a multiworld seed
+    ///
+    /// Available modes are shuffled, localprogression, localskills and sharedpool
+    #[structopt(long)]
+    pub sharing: Option<MultiworldSharing>,
+    /// Reject seeds where any world's score falls outside this range and generate them again
+    ///
+    /// Format: <min>..<max>, either bound may be left out. Higher scores estimate longer and more tedious seeds
+    #[structopt(long)]
+    pub score_range: Option<ScoreRangeOpt>,
     /// Seed the random number generator
     ///
     /// Without this flag, the rng seed will be randomly generated
@@ -472,8 +827,16 @@ impl SeedSettings {
             headers,
             header_config,
             inline_headers,
+            constraints,
+            hints,
+            weights,
+            item_pool,
+            spirit_light,
+            shop_prices,
             disable_logic_filter,
             online,
+            sharing,
+            score_range,
             seed,
         } = self;
 
@@ -487,6 +850,12 @@ impl SeedSettings {
         let world_headers = resolve_world_opts(headers, worlds)?;
         let world_header_configs = resolve_world_opts(header_config, worlds)?;
         let world_inline_headers = resolve_world_opts(inline_headers, worlds)?;
+        let world_constraints = resolve_world_opts(constraints, worlds)?;
+        let world_hints = resolve_world_opts(hints, worlds)?;
+        let world_weights = resolve_world_opts(weights, worlds)?;
+        let world_item_pools = resolve_nonduplicate_world_opts(item_pool, worlds)?;
+        let world_spirit_light = resolve_world_opts(spirit_light, worlds)?;
+        let world_shop_prices = resolve_world_opts(shop_prices, worlds)?;
 
         let disable_logic_filter = if disable_logic_filter {
             Some(true)
@@ -506,13 +875,37 @@ impl SeedSettings {
             .zip(world_headers)
             .zip(world_header_configs)
             .zip(world_inline_headers)
+            .zip(world_constraints)
+            .zip(world_hints)
+            .zip(world_weights)
+            .zip(world_item_pools)
+            .zip(world_spirit_light)
+            .zip(world_shop_prices)
             .map(
                 |(
                     (
-                        (((((((world_presets, spawn), difficulty), tricks), hard), randomize_doors), goals), headers),
-                        header_config,
+                        (
+                            (
+                                (
+                                    (
+                                        (
+                                            (
+                                                (((((((world_presets, spawn), difficulty), tricks), hard), randomize_doors), goals), headers),
+                                                header_config,
+                                            ),
+                                            inline_headers,
+                                        ),
+                                        constraints,
+                                    ),
+                                    hints,
+                                ),
+                                weights,
+                            ),
+                            item_pool,
+                        ),
+                        spirit_light,
                     ),
-                    inline_headers,
+                    shop_prices,
                 )| {
                     WorldPreset {
                         info: None,
@@ -538,6 +931,18 @@ impl SeedSettings {
                                 .map(InlineHeaderOpt::into_inner)
                                 .collect(),
                         ),
+                        placement_constraints: slice_in_option(
+                            constraints
+                                .into_iter()
+                                .map(PlacementConstraintOpt::into_inner)
+                                .collect(),
+                        ),
+                        plando: None,
+                        hints: HintsOpt::into_hint_settings(hints),
+                        placement_weights: PlacementWeightOpt::into_placement_weights(weights),
+                        item_pool: item_pool.map(ItemPool::from_base),
+                        spirit_light: SpiritLightOpt::into_spirit_light_settings(spirit_light),
+                        shop_pricing: ShopPricingOpt::into_shop_pricing(shop_prices),
                     }
                 },
             )
@@ -551,6 +956,8 @@ impl SeedSettings {
             seed,
             online,
             create_game: None,
+            multiworld_sharing: sharing,
+            score_range: score_range.map(ScoreRangeOpt::into_inner),
         })
     }
 }
@@ -613,6 +1020,36 @@ pub struct WorldPresetSettings {
     /// Inline header syntax
     #[structopt(short, long = "inline")]
     pub inline_headers: Option<Vec<InlineHeaderOpt>>,
+    /// Restrictions on where items may be placed
+    ///
+    /// Available constraints are notinzone:<item>:<zone>, noskillsinshops and early:<item>:<amount> to place an item within the first <amount> reachable locations
+    #[structopt(long)]
+    pub constraints: Option<Vec<PlacementConstraintOpt>>,
+    /// How many hints of each kind to generate for the hint slots declared by headers
+    ///
+    /// Format for one kind: <kind>:<amount>. Available kinds are woth, barren and item, unspecified kinds use their default amounts
+    #[structopt(long)]
+    pub hints: Option<Vec<HintsOpt>>,
+    /// Biases when choosing items for placements
+    ///
+    /// Available weights are item:<item>:<weight>, progression:<curve> with the curves flat, early and late, and skills:<depth> to push skills towards the end of the seed
+    #[structopt(long)]
+    pub weights: Option<Vec<PlacementWeightOpt>>,
+    /// Item pool to use instead of the standard pool
+    ///
+    /// The item pool will be searched as .json file in the current and /item_pools child directory
+    #[structopt(long)]
+    pub item_pool: Option<String>,
+    /// How Spirit Light is distributed and what shops cost
+    ///
+    /// Available modes are standard, budget and zonescaled. Each shop:<amount> adds a tier of minimum Spirit Light that has to be reachable before opening further shop locations
+    #[structopt(long)]
+    pub spirit_light: Option<Vec<SpiritLightOpt>>,
+    /// How shop prices are chosen
+    ///
+    /// Available models are fixed, random and progression. range:<min>:<max> sets the price multipliers, max:<total> limits the sum of all shop prices and affordable forces enough Spirit Light to buy all progression items in shops
+    #[structopt(long)]
+    pub shop_prices: Option<Vec<ShopPricingOpt>>,
 }
 
 impl WorldPresetSettings {
@@ -629,6 +1066,12 @@ impl WorldPresetSettings {
             headers,
             header_config,
             inline_headers,
+            constraints,
+            hints,
+            weights,
+            item_pool,
+            spirit_light,
+            shop_prices,
         } = self;
 
         WorldPreset {
@@ -653,6 +1096,18 @@ impl WorldPresetSettings {
                     .map(InlineHeaderOpt::into_inner)
                     .collect()
             }),
+            placement_constraints: constraints.map(|constraints| {
+                constraints
+                    .into_iter()
+                    .map(PlacementConstraintOpt::into_inner)
+                    .collect()
+            }),
+            plando: None,
+            hints: hints.and_then(HintsOpt::into_hint_settings),
+            placement_weights: weights.and_then(PlacementWeightOpt::into_placement_weights),
+            item_pool: item_pool.map(ItemPool::from_base),
+            spirit_light: spirit_light.and_then(SpiritLightOpt::into_spirit_light_settings),
+            shop_pricing: shop_prices.and_then(ShopPricingOpt::into_shop_pricing),
         }
     }
 }
@@ -704,10 +1159,54 @@ pub struct StatsArgs {
     /// cleans the cache for the provided settings and generates new seeds from scratch
     #[structopt(short = "O", long)]
     pub overwrite_cache: bool,
+    /// Which worlds of multiworld seeds to analyze
+    ///
+    /// "aggregated" counts the results of all worlds together, "per-world" keeps them apart and a world index only analyzes that world
+    #[structopt(long, default_value = "aggregated")]
+    pub world_scope: WorldScope,
+    /// Where to cache seeds between runs
+    ///
+    /// "folder" stores one file per seed in a "seed_storage" folder, "archive" stores all seeds in a single "seed_storage.pack" file
+    #[structopt(long, default_value = "folder")]
+    pub seed_storage: SeedStorage,
+    /// Also write a json summary next to each csv
+    ///
+    /// The summary contains percentages with confidence intervals and distributions (mean, median, percentiles) of numeric results
+    #[structopt(long)]
+    pub summary: bool,
+    /// Compare the stats against a variant using this logic file instead
+    ///
+    /// The comparison reports the difference for each result and whether it is significant
+    #[structopt(parse(from_os_str), long)]
+    pub compare_areas: Option<PathBuf>,
+    /// Compare the stats against a variant that additionally applies these universe presets
+    ///
+    /// The comparison reports the difference for each result and whether it is significant
+    #[structopt(long)]
+    pub compare_presets: Option<Vec<String>>,
     #[structopt(flatten)]
     pub settings: SeedSettings,
 }
 
+#[derive(Clone, Copy)]
+pub enum SeedStorage {
+    Folder,
+    Archive,
+}
+impl FromStr for SeedStorage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "folder" => Ok(SeedStorage::Folder),
+            "archive" => Ok(SeedStorage::Archive),
+            _ => Err(format!(
+                "Invalid seed storage \"{s}\", expected \"folder\" or \"archive\""
+            )),
+        }
+    }
+}
+
 pub struct ChainedAnalyzers(pub Vec<Analyzer>);
 #[derive(StructOpt)]
 pub enum Analyzer {
@@ -738,6 +1237,12 @@ pub enum Analyzer {
     LocationItem { location: String },
     /// Analyzes which items get placed as forced progression
     Progression,
+    /// Analyzes the seed score, which estimates how long and tedious a seed is
+    /// You can optionally pass seed-score:<result-bucket-size> to group results together in buckets
+    SeedScore {
+        #[structopt(default_value = "1")]
+        result_bucket_size: NonZeroUsize,
+    },
     /// Analyzes the amount of spawn items. Mostly makes sense with random spawn, since with the default spawn usually no spawn items are given
     SpawnItemCount,
     /// Analyzes the spawn items. Mostly makes sense with random spawn, since with the default spawn usually no spawn items are given
@@ -770,6 +1275,28 @@ pub enum Analyzer {
         #[structopt(default_value = "1")]
         result_bucket_size: NonZeroUsize,
     },
+    /// Analyzes whatever is described in a json file.
+    /// Pass custom:<path> to specify the file (Example: "custom:launch_step.json"). Since "+" chains analyzers, the path can't contain "+"
+    /// The file contains a "title", a "filter" for placements, e.g. {"item": "Launch"}, and a "result" such as "step", "item", "itemType", "location", "region", "zone" or "count".
+    /// Optionally, "placements" may be "received" or "placed", "first" may be true to only use the first matching placement and "resultBucketSize" groups numeric results together in buckets
+    /// Filters can match by "item", "itemRegex", "itemType", "location", "locationRegex", "zone", "fromStep" and "beforeStep"
+    Custom { analyzer: CustomAnalyzerOpt },
+}
+/// Newtype to read custom analyzers from files
+pub struct CustomAnalyzerOpt(CustomStats);
+impl CustomAnalyzerOpt {
+    pub fn into_inner(self) -> CustomStats {
+        self.0
+    }
+}
+impl FromStr for CustomAnalyzerOpt {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let json =
+            fs::read_to_string(s).map_err(|err| format!("Failed to read {s}: {err}"))?;
+        CustomStats::from_json(&json).map(CustomAnalyzerOpt)
+    }
 }
 impl FromStr for ChainedAnalyzers {
     type Err = structopt::clap::Error;
@@ -779,6 +1306,10 @@ impl FromStr for ChainedAnalyzers {
             .map(|s| {
                 match s.split_once(':') {
                     None => Analyzer::from_iter_safe(["--analyzers", s]), // The first arg is the "executable name"
+                    // Paths may contain commas
+                    Some(("custom", path)) => {
+                        Analyzer::from_iter_safe(["--analyzers", "custom", path])
+                    }
                     Some((identifier, args)) => Analyzer::from_iter_safe(
                         ["--analyzers", identifier]
                             .into_iter()
@@ -819,6 +1350,25 @@ pub struct ReachCheckArgs {
     pub items: Vec<ReachData>,
 }
 
+#[derive(StructOpt)]
+pub struct VerifyArgs {
+    /// the seed file to verify
+    #[structopt(parse(from_os_str))]
+    pub seed_file: PathBuf,
+    /// the input file representing the logic
+    #[structopt(parse(from_os_str), default_value = "areas.wotw", short, long)]
+    pub areas: PathBuf,
+    /// the input file representing pickup locations
+    #[structopt(parse(from_os_str), default_value = "loc_data.csv", short, long)]
+    pub locations: PathBuf,
+    /// the input file representing state namings
+    #[structopt(parse(from_os_str), default_value = "state_data.csv", short, long)]
+    pub uber_states: PathBuf,
+    /// write the result as json instead of text
+    #[structopt(long)]
+    pub json: bool,
+}
+
 pub enum ReachData {
     Skill(Skill),
     Teleporter(Teleporter),
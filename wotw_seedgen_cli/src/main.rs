@@ -2,10 +2,12 @@ mod cli;
 mod headers;
 mod log_init;
 mod play;
+mod progress;
 mod reach_check;
 mod seed;
 mod stats;
 mod universe_preset;
+mod verify;
 mod world_preset;
 
 use std::process::ExitCode;
@@ -30,12 +32,14 @@ fn main() -> ExitCode {
         cli::SeedGenCommand::WorldPreset { args } => world_preset::create_world_preset(args),
         cli::SeedGenCommand::Stats { args } => stats::generate_stats(args),
         cli::SeedGenCommand::CleanStatsCache => stats::clean_stats_cache(),
+        cli::SeedGenCommand::MigrateStatsCache => stats::migrate_stats_cache(),
         cli::SeedGenCommand::Headers {
             headers,
             subcommand,
         } => headers::headers(headers, subcommand),
         cli::SeedGenCommand::ReachCheck { args } => reach_check::reach_check(args),
         cli::SeedGenCommand::Regenerate { args } => seed::regenerate_seed(args),
+        cli::SeedGenCommand::Verify { args } => verify::verify(args),
     }
     .map_or_else(
         |err| {
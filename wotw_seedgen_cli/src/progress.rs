@@ -0,0 +1,57 @@
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicU16, Ordering};
+
+use wotw_seedgen::generator::{GenerationEvent, GenerationObserver, ObserverAction};
+
+/// Shows the progress of the current placement attempt on a single stderr line
+pub struct ProgressIndicator {
+    attempt: AtomicU16,
+    retries: u16,
+}
+
+impl ProgressIndicator {
+    pub fn new(retries: u16) -> Self {
+        Self {
+            attempt: AtomicU16::new(0),
+            retries,
+        }
+    }
+
+    /// Remove the progress line so following output starts on a clean line
+    pub fn finish(&self) {
+        eprint!("\r\x1b[2K");
+        io::stderr().flush().ok();
+    }
+}
+
+impl GenerationObserver for ProgressIndicator {
+    fn on_event(&self, event: GenerationEvent) -> ObserverAction {
+        let attempt = match event {
+            GenerationEvent::RetryStarted { attempt, .. } => {
+                self.attempt.fetch_max(attempt, Ordering::Relaxed);
+                return ObserverAction::Continue;
+            }
+            GenerationEvent::ScoreRejected { attempt, .. } => {
+                // Placement attempts start counting again for the next seed
+                self.attempt.store(0, Ordering::Relaxed);
+                format!("rejected seed {attempt} for its score, generating again")
+            }
+            GenerationEvent::PlacementStep { reached, total } => {
+                let percent = reached * 100 / total.max(1);
+                format!("placing items, {percent}% of locations reached")
+            }
+            GenerationEvent::SpawnChosen { .. } | GenerationEvent::ForcedProgression { .. } => {
+                return ObserverAction::Continue;
+            }
+        };
+
+        let attempt_index = self.attempt.load(Ordering::Relaxed) + 1;
+        eprint!(
+            "\r\x1b[2KAttempt {attempt_index}/{}: {attempt}",
+            self.retries
+        );
+        io::stderr().flush().ok();
+
+        ObserverAction::Continue
+    }
+}
@@ -33,7 +33,8 @@ pub fn reach_check(mut args: cli::ReachCheckArgs) -> Result<(), String> {
         .map_err(|err| format!("Failed to read {}: {}", args.locations.display(), err))?;
     let states = fs::read_to_string(&args.uber_states)
         .map_err(|err| format!("Failed to read {}: {}", args.uber_states.display(), err))?;
-    let graph = logic::parse_logic(&areas, &locations, &states, &universe_settings, false)?;
+    let graph = logic::parse_logic(&areas, &locations, &states, &universe_settings, false)
+        .map_err(|err| err.to_string())?;
 
     let mut inventory = Inventory::default();
     inventory.grant(Item::Resource(Resource::HealthFragment), args.health / 5);
@@ -4,6 +4,7 @@ use crate::cli::SeedMetaArgs;
 use super::cli;
 use super::log_init;
 use super::play;
+use super::progress::ProgressIndicator;
 
 use std::fs;
 use std::io::{self, Read, Write};
@@ -14,11 +15,15 @@ use log::LevelFilter;
 use serde::{Deserialize, Serialize};
 
 use wotw_seedgen::files::FILE_SYSTEM_ACCESS;
-use wotw_seedgen::generator::{Seed, SeedSpoiler};
+use wotw_seedgen::generator::{
+    self, GenerationObserver, GeneratorOptions, Playthrough, Seed, SeedSpoiler,
+};
 use wotw_seedgen::logic;
 use wotw_seedgen::settings;
 use wotw_seedgen::settings::UniverseSettings;
+use wotw_seedgen::util::constants::RETRIES;
 use wotw_seedgen::world::Graph;
+use wotw_seedgen::SeedgenError;
 
 pub fn generate_seeds(args: cli::SeedArgs) -> Result<(), String> {
     let now = Instant::now();
@@ -40,8 +45,7 @@ pub fn generate_seeds(args: cli::SeedArgs) -> Result<(), String> {
 
     let graph = read_input_files(&args.meta, &universe_settings)?;
 
-    let seed = wotw_seedgen::generate_seed(&graph, &FILE_SYSTEM_ACCESS, &universe_settings)
-        .map_err(|err| format!("Error generating seed: {}", err))?;
+    let seed = generate_seed(&args.meta, &graph, &universe_settings)?;
 
     let worlds = universe_settings.world_count();
     if worlds == 1 {
@@ -53,6 +57,36 @@ pub fn generate_seeds(args: cli::SeedArgs) -> Result<(), String> {
     write_seeds(args.meta, seed)
 }
 
+fn generate_seed<'graph, 'settings>(
+    args: &SeedMetaArgs,
+    graph: &'graph Graph,
+    universe_settings: &'settings UniverseSettings,
+) -> Result<Seed<'graph, 'settings>, String> {
+    let options = GeneratorOptions {
+        retries: args.retries.unwrap_or(RETRIES),
+        parallel_attempts: args.parallel_attempts,
+        ..GeneratorOptions::default()
+    };
+
+    // The progress line would only get in the way of logs that aren't read by a person
+    let show_progress = !args.json_stderr && atty::is(atty::Stream::Stderr);
+    let progress = ProgressIndicator::new(options.retries.get());
+    let observer: &dyn GenerationObserver = if show_progress { &progress } else { &() };
+
+    let seed = wotw_seedgen::generate_seed_with_observer(
+        graph,
+        &FILE_SYSTEM_ACCESS,
+        universe_settings,
+        &options,
+        observer,
+    );
+    if show_progress {
+        progress.finish();
+    }
+
+    seed.map_err(|err| report_error(args, "Error generating seed", err))
+}
+
 fn initialize_log(args: &SeedMetaArgs) {
     let use_file = if args.verbose {
         Some("generator.log")
@@ -111,7 +145,8 @@ fn read_input_files(
         .map_err(|err| format!("Failed to read {}: {}", args.locations.display(), err))?;
     let states = fs::read_to_string(&args.uber_states)
         .map_err(|err| format!("Failed to read {}: {}", args.uber_states.display(), err))?;
-    let graph = logic::parse_logic(&areas, &locations, &states, universe_settings, !args.trust)?;
+    let graph = logic::parse_logic(&areas, &locations, &states, universe_settings, !args.trust)
+        .map_err(|err| report_error(args, "Error parsing logic", err.into()))?;
 
     log::info!("Parsed logic in {:?}", now.elapsed());
 
@@ -119,12 +154,27 @@ fn read_input_files(
 }
 
 fn write_seeds(args: SeedMetaArgs, seed: Seed<'_, '_>) -> Result<(), String> {
+    let playthrough = if args.playthrough {
+        let now = Instant::now();
+        let playthrough = seed.playthrough()?;
+        log::info!("Computed playthrough in {:?}", now.elapsed());
+        Some(playthrough)
+    } else {
+        None
+    };
+
     if args.tostdout {
-        write_seeds_to_stdout(seed, args.json)?;
+        write_seeds_to_stdout(seed, playthrough, args.json)?;
     } else {
         let filename = args.filename.unwrap_or_else(|| String::from("seed"));
 
-        write_seeds_to_files(&seed, &filename, args.seed_folder, args.json)?;
+        write_seeds_to_files(
+            &seed,
+            playthrough.as_ref(),
+            &filename,
+            args.seed_folder,
+            args.json,
+        )?;
     }
 
     if args.launch {
@@ -140,6 +190,7 @@ fn write_seeds(args: SeedMetaArgs, seed: Seed<'_, '_>) -> Result<(), String> {
 
 fn write_seeds_to_files(
     seed: &Seed,
+    playthrough: Option<&Playthrough>,
     filename: &str,
     mut folder: PathBuf,
     json_spoiler: bool,
@@ -179,7 +230,7 @@ fn write_seeds_to_files(
         }
     }
 
-    let mut path = folder;
+    let mut path = folder.clone();
     path.push(format!("{filename}_spoiler"));
 
     let contents = match json_spoiler {
@@ -197,6 +248,26 @@ fn write_seeds_to_files(
         create_seedfile(path, &contents).map_err(|err| format!("Error writing spoiler: {err}"))?;
     log::info!("Wrote spoiler to {}", file.display());
 
+    if let Some(playthrough) = playthrough {
+        let mut path = folder;
+        path.push(format!("{filename}_playthrough"));
+
+        let contents = match json_spoiler {
+            true => {
+                path.set_extension("json");
+                playthrough.to_json()
+            }
+            false => {
+                path.set_extension("txt");
+                playthrough.to_string()
+            }
+        };
+
+        let file = create_seedfile(path, &contents)
+            .map_err(|err| format!("Error writing playthrough: {err}"))?;
+        log::info!("Wrote playthrough to {}", file.display());
+    }
+
     Ok(())
 }
 
@@ -248,15 +319,22 @@ fn create_multiworld_folder(path: PathBuf) -> Result<PathBuf, io::Error> {
     }
 }
 
-fn write_seeds_to_stdout(seed: Seed, json: bool) -> Result<(), String> {
+fn write_seeds_to_stdout(
+    seed: Seed,
+    playthrough: Option<Playthrough>,
+    json: bool,
+) -> Result<(), String> {
     let files = seed.seed_files()?;
 
     if json {
         let spoiler_text = seed.spoiler.to_string();
+        let playthrough_text = playthrough.as_ref().map(Playthrough::to_string);
         let output = SeedgenCliJsonOutput {
             seed_files: files,
             spoiler: seed.spoiler,
             spoiler_text,
+            playthrough,
+            playthrough_text,
         };
 
         println!("{}", output.to_json())
@@ -273,6 +351,11 @@ fn write_seeds_to_stdout(seed: Seed, json: bool) -> Result<(), String> {
         println!();
         println!("======= Spoiler =======");
         println!("{}", seed.spoiler);
+
+        if let Some(playthrough) = playthrough {
+            println!("======= Playthrough =======");
+            println!("{playthrough}");
+        }
     }
 
     Ok(())
@@ -288,6 +371,12 @@ struct SeedgenCliJsonOutput {
     pub spoiler: SeedSpoiler,
     /// Text representation of the spoiler
     pub spoiler_text: String,
+    /// Playthrough for this seed, if requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub playthrough: Option<Playthrough>,
+    /// Text representation of the playthrough, if requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub playthrough_text: Option<String>,
 }
 
 impl SeedgenCliJsonOutput {
@@ -297,6 +386,39 @@ impl SeedgenCliJsonOutput {
     }
 }
 
+/// Struct that is used for JSON output to stdout if generation failed
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SeedgenCliJsonError {
+    /// What went wrong
+    pub error: SeedgenError,
+    /// Text representation of the error
+    pub error_text: String,
+}
+
+impl SeedgenCliJsonError {
+    /// Serialize into json format
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self).unwrap()
+    }
+}
+
+/// Writes the error to stdout if json output was requested and returns the message to log
+fn report_error(args: &SeedMetaArgs, context: &str, error: SeedgenError) -> String {
+    let error_text = error.to_string();
+
+    if args.json && args.tostdout {
+        let output = SeedgenCliJsonError {
+            error,
+            error_text: error_text.clone(),
+        };
+
+        println!("{}", output.to_json());
+    }
+
+    format!("{context}: {error_text}")
+}
+
 pub fn regenerate_seed(args: RegenerateArgs) -> Result<(), String> {
     let now = Instant::now();
 
@@ -312,8 +434,7 @@ pub fn regenerate_seed(args: RegenerateArgs) -> Result<(), String> {
 
     let graph = read_input_files(&args.meta, &universe_settings)?;
 
-    let seed = wotw_seedgen::generate_seed(&graph, &FILE_SYSTEM_ACCESS, &universe_settings)
-        .map_err(|err| format!("Error generating seed: {}", err))?;
+    let seed = generate_seed(&args.meta, &graph, &universe_settings)?;
 
     match verify_seed(&model, &seed) {
         Ok(()) => log::info!("Seed passed verification. Yay!"),
@@ -347,9 +468,6 @@ fn verify_seed(model: &str, seed: &Seed) -> Result<(), String> {
     let world = settings::world_index_from_seed(model).ok_or("no world index found in seed")??;
     let world_seed = seeds.get(world).ok_or("world index out of bounds")?;
 
-    if model == world_seed {
-        Ok(())
-    } else {
-        Err("Regenerated seed does not match".to_string())
-    }
+    generator::compare_seed_files(model, world_seed)
+        .map_err(|err| format!("Regenerated seed does not match: {err}"))
 }
@@ -6,11 +6,13 @@ use sanitize_filename::sanitize;
 use wotw_seedgen::settings::Spawn;
 use wotw_seedgen::util::constants::DEFAULT_SPAWN;
 use wotw_seedgen::world::Graph;
-use wotw_seedgen::{files::FILE_SYSTEM_ACCESS, logic, settings::UniverseSettings};
+use wotw_seedgen::{
+    files::FILE_SYSTEM_ACCESS, logic, preset::UniversePreset, settings::UniverseSettings,
+};
 use wotw_seedgen_stats::analyzers::{self, Analyzer};
 use wotw_seedgen_stats::{
-    files::{FileAccess, FileSystemAccess},
-    StatsArgs,
+    files::{ArchiveAccess, FileAccess, FileSystemAccess},
+    CompareArgs, StatsArgs, Variant,
 };
 
 use crate::{cli, log_init};
@@ -29,6 +31,11 @@ pub fn generate_stats(args: cli::StatsArgs) -> Result<(), String> {
         tolerated_errors,
         error_message_limit,
         overwrite_cache,
+        world_scope,
+        seed_storage,
+        summary,
+        compare_areas,
+        compare_presets,
         settings: settings_args,
     } = args;
 
@@ -46,10 +53,44 @@ pub fn generate_stats(args: cli::StatsArgs) -> Result<(), String> {
         .map_err(|err| format!("Failed to read {}: {}", locations.display(), err))?;
     let states = fs::read_to_string(&uber_states)
         .map_err(|err| format!("Failed to read {}: {}", uber_states.display(), err))?;
-    let graph = logic::parse_logic(&areas, &locations, &states, &settings, false)?;
+    let graph = logic::parse_logic(&areas, &locations, &states, &settings, false)
+        .map_err(|err| err.to_string())?;
+
+    let changed = if compare_areas.is_some() || compare_presets.is_some() {
+        let mut changed_settings = settings.clone();
+        if let Some(compare_presets) = compare_presets {
+            let preset = UniversePreset {
+                includes: Some(compare_presets.into_iter().collect()),
+                ..UniversePreset::default()
+            };
+            changed_settings
+                .apply_preset(preset, &FILE_SYSTEM_ACCESS)
+                .map_err(|err| err.to_string())?;
+        }
+        let changed_areas = match compare_areas {
+            Some(compare_areas) => fs::read_to_string(&compare_areas)
+                .map_err(|err| format!("Failed to read {}: {}", compare_areas.display(), err))?,
+            None => areas.clone(),
+        };
+        let changed_graph = logic::parse_logic(
+            &changed_areas,
+            &locations,
+            &states,
+            &changed_settings,
+            false,
+        )
+        .map_err(|err| err.to_string())?;
+
+        Some((changed_settings, changed_graph))
+    } else {
+        None
+    };
 
     let settings_json = settings.to_json();
-    let settings_summary = folder_name.unwrap_or_else(|| summarize_settings(&settings, &graph));
+    let mut settings_summary = folder_name.unwrap_or_else(|| summarize_settings(&settings, &graph));
+    if changed.is_some() {
+        settings_summary.push_str(" Comparison");
+    }
 
     let mut path = PathBuf::from("stats");
 
@@ -117,6 +158,9 @@ pub fn generate_stats(args: cli::StatsArgs) -> Result<(), String> {
                         box_analyzer(analyzers::LocationItemStats { location })
                     }
                     cli::Analyzer::Progression => box_analyzer(analyzers::ProgressionStats),
+                    cli::Analyzer::SeedScore { result_bucket_size } => {
+                        box_analyzer(analyzers::SeedScoreStats { result_bucket_size })
+                    }
                     cli::Analyzer::SpawnItemCount => box_analyzer(analyzers::SpawnItemCountStats),
                     cli::Analyzer::SpawnItems => box_analyzer(analyzers::SpawnItemStats),
                     cli::Analyzer::SpawnLocation => box_analyzer(analyzers::SpawnLocationStats),
@@ -138,21 +182,47 @@ pub fn generate_stats(args: cli::StatsArgs) -> Result<(), String> {
                         zone,
                         result_bucket_size,
                     }),
+                    cli::Analyzer::Custom { analyzer } => box_analyzer(analyzer.into_inner()),
                 })
                 .collect()
         })
         .collect();
 
-    let args = StatsArgs {
-        settings,
-        sample_size,
-        analyzers,
-        graph: &graph,
-        tolerated_errors,
-        error_message_limit: Some(error_message_limit),
-        overwrite_seed_storage: overwrite_cache,
+    let changed_settings_json = changed
+        .as_ref()
+        .map(|(changed_settings, _)| changed_settings.to_json());
+    let run = match &changed {
+        None => StatsRun::Stats(StatsArgs {
+            settings,
+            sample_size,
+            analyzers,
+            graph: &graph,
+            tolerated_errors,
+            error_message_limit: Some(error_message_limit),
+            overwrite_seed_storage: overwrite_cache,
+            world_scope,
+        }),
+        Some((changed_settings, changed_graph)) => StatsRun::Compare(CompareArgs {
+            baseline: Variant {
+                settings,
+                graph: &graph,
+            },
+            changed: Variant {
+                settings: changed_settings.clone(),
+                graph: changed_graph,
+            },
+            sample_size,
+            analyzers,
+            tolerated_errors,
+            error_message_limit: Some(error_message_limit),
+            overwrite_seed_storage: overwrite_cache,
+            world_scope,
+        }),
+    };
+    let outputs = match seed_storage {
+        cli::SeedStorage::Folder => generate::<FileSystemAccess>(run, summary)?,
+        cli::SeedStorage::Archive => generate::<ArchiveAccess>(run, summary)?,
     };
-    let stats = wotw_seedgen_stats::stats::<FileSystemAccess>(args)?;
 
     fs::create_dir_all(&path).map_err(|err| {
         format!(
@@ -161,10 +231,9 @@ pub fn generate_stats(args: cli::StatsArgs) -> Result<(), String> {
             err
         )
     })?; // It might've been a while ago that we created this folder, lets check if we need to recreate it in case the user deleted it in the meantime
-    for stats in stats {
-        let csv = stats.csv();
+    for StatsOutput { title, csv, json } in outputs {
         let mut path = path.clone();
-        path.push(format!("{}.csv", sanitize(stats.title())));
+        path.push(format!("{}.csv", sanitize(title)));
         fs::write(&path, csv).map_err(|err| {
             format!(
                 "failed to write statistics to \"{}\": {}",
@@ -173,6 +242,30 @@ pub fn generate_stats(args: cli::StatsArgs) -> Result<(), String> {
             )
         })?;
         eprintln!("Wrote statistics to \"{}\"", path.display());
+
+        if let Some(json) = json {
+            path.set_extension("json");
+            fs::write(&path, json).map_err(|err| {
+                format!(
+                    "failed to write statistics summary to \"{}\": {}",
+                    path.display(),
+                    err
+                )
+            })?;
+            eprintln!("Wrote statistics summary to \"{}\"", path.display());
+        }
+    }
+
+    if let Some(changed_settings_json) = changed_settings_json {
+        let mut path = path.clone();
+        path.push("changed_settings.json");
+        fs::write(&path, changed_settings_json).map_err(|err| {
+            format!(
+                "failed to write settings to \"{}\": {}",
+                path.display(),
+                err
+            )
+        })?;
     }
 
     path.push("settings.json");
@@ -187,11 +280,58 @@ pub fn generate_stats(args: cli::StatsArgs) -> Result<(), String> {
     Ok(())
 }
 
+enum StatsRun<'graph> {
+    Stats(StatsArgs<'graph>),
+    Compare(CompareArgs<'graph>),
+}
+struct StatsOutput {
+    title: String,
+    csv: String,
+    json: Option<String>,
+}
+fn generate<F: FileAccess>(run: StatsRun, summary: bool) -> Result<Vec<StatsOutput>, String> {
+    let outputs = match run {
+        StatsRun::Stats(args) => wotw_seedgen_stats::stats::<F>(args)?
+            .into_iter()
+            .map(|stats| StatsOutput {
+                title: stats.title(),
+                csv: stats.csv(),
+                json: summary.then(|| stats.summary().to_json()),
+            })
+            .collect(),
+        StatsRun::Compare(args) => wotw_seedgen_stats::compare::<F>(args)?
+            .into_iter()
+            .map(|comparison| StatsOutput {
+                title: comparison.title(),
+                csv: comparison.csv(),
+                json: summary.then(|| comparison.summary().to_json()),
+            })
+            .collect(),
+    };
+
+    Ok(outputs)
+}
+
 pub fn clean_stats_cache() -> Result<(), String> {
     log_init::initialize_log(None, LevelFilter::Warn, false)
         .unwrap_or_else(|err| eprintln!("Failed to initialize log: {err}"));
 
-    wotw_seedgen_stats::files::FileSystemAccess::clean_all_seeds()
+    FileSystemAccess::clean_all_seeds()?;
+    ArchiveAccess::clean_all_seeds()
+}
+
+pub fn migrate_stats_cache() -> Result<(), String> {
+    log_init::initialize_log(None, LevelFilter::Warn, false)
+        .unwrap_or_else(|err| eprintln!("Failed to initialize log: {err}"));
+
+    let migrated = ArchiveAccess::migrate_folder_storage()?;
+    eprintln!(
+        "Moved {} seed{} into the seed archive",
+        migrated,
+        if migrated == 1 { "" } else { "s" }
+    );
+
+    Ok(())
 }
 
 fn summarize_settings(settings: &UniverseSettings, graph: &Graph) -> String {
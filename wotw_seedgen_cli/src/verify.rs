@@ -0,0 +1,51 @@
+use super::cli;
+use super::log_init;
+
+use std::fs;
+
+use log::LevelFilter;
+
+use wotw_seedgen::logic;
+use wotw_seedgen::settings::{UniverseSettings, WorldSettings};
+
+pub fn verify(mut args: cli::VerifyArgs) -> Result<(), String> {
+    log_init::initialize_log(None, LevelFilter::Info, false)
+        .unwrap_or_else(|err| eprintln!("Failed to initialize log: {}", err));
+
+    args.seed_file.set_extension("wotwr");
+    let contents =
+        fs::read_to_string(&args.seed_file).map_err(|err| format!("Error reading seed: {err}"))?;
+
+    let universe_settings = UniverseSettings::from_seed(&contents)
+        .unwrap_or_else(|| {
+            log::warn!("No settings found in seed, verifying with default settings");
+            Ok(UniverseSettings::default())
+        })
+        .map_err(|err| format!("Error reading settings: {err}"))?;
+    let world_settings = WorldSettings::from_seed(&contents, universe_settings.clone())
+        .unwrap_or_else(|| Ok(universe_settings.world_settings[0].clone()))
+        .map_err(|err| format!("Error reading settings: {err}"))?;
+
+    let areas = fs::read_to_string(&args.areas)
+        .map_err(|err| format!("Failed to read {}: {}", args.areas.display(), err))?;
+    let locations = fs::read_to_string(&args.locations)
+        .map_err(|err| format!("Failed to read {}: {}", args.locations.display(), err))?;
+    let states = fs::read_to_string(&args.uber_states)
+        .map_err(|err| format!("Failed to read {}: {}", args.uber_states.display(), err))?;
+    let graph = logic::parse_logic(&areas, &locations, &states, &universe_settings, false)
+        .map_err(|err| err.to_string())?;
+
+    let verification = wotw_seedgen::verify_seed_file(&graph, &contents, &world_settings)?;
+
+    if args.json {
+        println!("{}", verification.to_json());
+    } else {
+        print!("{verification}");
+    }
+
+    if verification.beatable {
+        Ok(())
+    } else {
+        Err(format!("{} is not beatable", args.seed_file.display()))
+    }
+}
@@ -11,6 +11,21 @@ pub(super) fn order_index(s: &str, order: &[&str]) -> usize {
 pub(super) fn compare_fixed_order<T: FixedOrder>(a: &str, b: &str) -> Ordering {
     order_index(a, T::ORDER).cmp(&order_index(b, T::ORDER))
 }
+/// Compares numbers and buckets like "10-19" by value and anything else alphabetically
+pub(super) fn compare_numeric(a: &str, b: &str) -> Ordering {
+    match a
+        .split('-')
+        .next()
+        .unwrap()
+        .parse::<u32>()
+        .into_iter()
+        .zip(b.split('-').next().unwrap().parse::<u32>())
+        .next()
+    {
+        None => a.cmp(b),
+        Some((a, b)) => a.cmp(&b),
+    }
+}
 pub(super) fn compare_location(a: &str, b: &str) -> Ordering {
     let (a_region, a_identifier) = a.split_once('.').unwrap_or((a, ""));
     let (b_region, b_identifier) = b.split_once('.').unwrap_or((b, ""));
@@ -0,0 +1,451 @@
+use std::{cmp::Ordering, num::NonZeroUsize};
+
+use regex::Regex;
+use serde::{Deserialize, Deserializer};
+use wotw_seedgen::{
+    generator::{SeedSpoiler, SpoilerPlacement},
+    item::Item,
+    util::Zone,
+};
+
+use super::Analyzer;
+
+/// An analyzer defined by data instead of code, which allows reading it from json
+///
+/// It looks at the placements of a world matching the `filter` and categorizes the world by the `result` of each of them
+///
+/// # Examples
+///
+/// ```
+/// # use wotw_seedgen_stats::analyzers::CustomStats;
+/// // The step at which Launch is placed
+/// let analyzer = CustomStats::from_json(r#"{
+///     "title": "Launch step",
+///     "filter": { "item": "Launch" },
+///     "result": "step"
+/// }"#);
+/// assert!(analyzer.is_ok());
+///
+/// // The zone of the locations holding any Ancestral Light
+/// let analyzer = CustomStats::from_json(r#"{
+///     "title": "Ancestral Light zones",
+///     "filter": { "itemRegex": "Ancestral Light$" },
+///     "result": "zone"
+/// }"#);
+/// assert!(analyzer.is_ok());
+///
+/// // How many skills are placed before step 5
+/// let analyzer = CustomStats::from_json(r#"{
+///     "title": "Skills before step 5",
+///     "filter": { "itemType": "Skill", "beforeStep": 5 },
+///     "result": "count"
+/// }"#);
+/// assert!(analyzer.is_ok());
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CustomStats {
+    pub title: String,
+    /// Whether to look at the placements the world receives or the placements on its locations
+    #[serde(default)]
+    pub placements: PlacementSelection,
+    /// Which placements to look at
+    #[serde(default)]
+    pub filter: PlacementFilter,
+    /// Only look at the first placement matching the `filter`
+    #[serde(default)]
+    pub first: bool,
+    /// What to categorize the world by
+    pub result: CustomResult,
+    /// Group numeric results together in buckets of this size
+    #[serde(default = "default_bucket_size")]
+    pub result_bucket_size: NonZeroUsize,
+}
+fn default_bucket_size() -> NonZeroUsize {
+    NonZeroUsize::new(1).unwrap()
+}
+impl CustomStats {
+    /// Parse the analyzer from json
+    pub fn from_json(input: &str) -> Result<Self, String> {
+        serde_json::from_str(input).map_err(|err| format!("Invalid custom analyzer: {err}"))
+    }
+}
+
+/// Which placements of a world a [`CustomStats`] looks at
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PlacementSelection {
+    /// Placements of items the world receives, no matter which world they were placed in
+    #[default]
+    Received,
+    /// Placements on locations in the world, no matter which world receives them
+    Placed,
+}
+
+/// Conditions a placement has to fulfill to be looked at by a [`CustomStats`]
+///
+/// Every condition that is present has to match
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PlacementFilter {
+    /// The item name, as displayed in the spoiler
+    pub item: Option<String>,
+    /// A regular expression the item name has to match
+    #[serde(default, deserialize_with = "deserialize_regex")]
+    pub item_regex: Option<Regex>,
+    /// The kind of item, e.g. "Skill", "Shard" or "SpiritLight"
+    pub item_type: Option<String>,
+    /// The location identifier, e.g. "MarshSpawn.RockHC"
+    pub location: Option<String>,
+    /// A regular expression the location identifier has to match
+    #[serde(default, deserialize_with = "deserialize_regex")]
+    pub location_regex: Option<Regex>,
+    /// The zone of the location
+    pub zone: Option<Zone>,
+    /// Only placements in this or later steps
+    pub from_step: Option<usize>,
+    /// Only placements in steps before this one
+    pub before_step: Option<usize>,
+}
+fn deserialize_regex<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Regex>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|regex| Regex::new(&regex).map_err(serde::de::Error::custom))
+        .transpose()
+}
+impl PlacementFilter {
+    fn matches(&self, step: usize, placement: &SpoilerPlacement) -> bool {
+        self.item
+            .as_ref()
+            .is_none_or(|item| &placement.item_name == item)
+            && self
+                .item_regex
+                .as_ref()
+                .is_none_or(|regex| regex.is_match(&placement.item_name))
+            && self
+                .item_type
+                .as_ref()
+                .is_none_or(|item_type| item_type == item_type_name(&placement.item))
+            && self
+                .location
+                .as_ref()
+                .is_none_or(|location| &placement.location.identifier == location)
+            && self
+                .location_regex
+                .as_ref()
+                .is_none_or(|regex| regex.is_match(&placement.location.identifier))
+            && self
+                .zone
+                .is_none_or(|zone| placement.location.zone == Some(zone))
+            && self.from_step.is_none_or(|from_step| step >= from_step)
+            && self
+                .before_step
+                .is_none_or(|before_step| step < before_step)
+    }
+}
+
+/// What a [`CustomStats`] categorizes the world by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CustomResult {
+    /// The index of the step each placement happened in, where every [`SpoilerGroup`](wotw_seedgen::generator::SpoilerGroup) is one step
+    Step,
+    /// The name of each placed item
+    Item,
+    /// The kind of each placed item
+    ItemType,
+    /// The identifier of each location
+    Location,
+    /// The region of each location
+    Region,
+    /// The zone of each location
+    Zone,
+    /// How many placements matched, this will always categorize the world once
+    Count,
+}
+
+impl Analyzer for CustomStats {
+    fn title(&self) -> String {
+        self.title.clone()
+    }
+
+    fn analyze(&self, seed: &SeedSpoiler, world_index: usize) -> Vec<String> {
+        let placements = seed
+            .groups
+            .iter()
+            .enumerate()
+            .flat_map(|(step, group)| {
+                group
+                    .placements
+                    .iter()
+                    .map(move |placement| (step, placement))
+            })
+            .filter(|(_, placement)| match self.placements {
+                PlacementSelection::Received => {
+                    placement.shared || placement.target_world_index == world_index
+                }
+                PlacementSelection::Placed => placement.origin_world_index == world_index,
+            })
+            .filter(|(step, placement)| self.filter.matches(*step, placement))
+            .take(if self.first { 1 } else { usize::MAX });
+
+        match self.result {
+            CustomResult::Count => vec![super::group_result(
+                placements.count(),
+                self.result_bucket_size,
+            )],
+            CustomResult::Step => placements
+                .map(|(step, _)| super::group_result(step, self.result_bucket_size))
+                .collect(),
+            CustomResult::Item => placements
+                .map(|(_, placement)| placement.item_name.clone())
+                .collect(),
+            CustomResult::ItemType => placements
+                .map(|(_, placement)| item_type_name(&placement.item).to_string())
+                .collect(),
+            CustomResult::Location => placements
+                .map(|(_, placement)| placement.location.identifier.clone())
+                .collect(),
+            CustomResult::Region => placements
+                .map(|(_, placement)| {
+                    placement
+                        .location
+                        .identifier
+                        .split('.')
+                        .next()
+                        .unwrap()
+                        .to_string()
+                })
+                .collect(),
+            CustomResult::Zone => placements
+                .map(|(_, placement)| {
+                    placement
+                        .location
+                        .zone
+                        .map_or("Unknown".to_string(), |zone| zone.to_string())
+                })
+                .collect(),
+        }
+    }
+
+    fn compare_keys(&self) -> fn(&str, &str) -> Ordering {
+        match self.result {
+            CustomResult::Location => super::compare_location,
+            CustomResult::Region => super::compare_fixed_order::<super::RegionFixedOrder>,
+            CustomResult::Zone => super::compare_fixed_order::<super::ZoneFixedOrder>,
+            CustomResult::Step
+            | CustomResult::Item
+            | CustomResult::ItemType
+            | CustomResult::Count => super::compare_numeric,
+        }
+    }
+}
+
+fn item_type_name(item: &Item) -> &'static str {
+    match item {
+        Item::Relic(_) => "Relic",
+        Item::Water => "Water",
+        Item::RemoveWater => "RemoveWater",
+        Item::Skill(_) => "Skill",
+        Item::RemoveSkill(_) => "RemoveSkill",
+        Item::Teleporter(_) => "Teleporter",
+        Item::RemoveTeleporter(_) => "RemoveTeleporter",
+        Item::Resource(_) => "Resource",
+        Item::Shard(_) => "Shard",
+        Item::RemoveShard(_) => "RemoveShard",
+        Item::BonusItem(_) => "BonusItem",
+        Item::BonusUpgrade(_) => "BonusUpgrade",
+        Item::SpiritLight(_) => "SpiritLight",
+        Item::RemoveSpiritLight(_) => "RemoveSpiritLight",
+        Item::Message(_) => "Message",
+        Item::UberState(_) => "UberState",
+        Item::Command(_) => "Command",
+        Item::WheelCommand(_) => "WheelCommand",
+        Item::ShopCommand(_) => "ShopCommand",
+        Item::SysMessage(_) => "SysMessage",
+        Item::SetMapMessage(_) => "SetMapMessage",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use wotw_seedgen::{
+        generator::{NodeSummary, SpoilerGroup},
+        item::{Resource, Skill},
+        settings::MultiworldSharing,
+        Inventory,
+    };
+
+    fn placement(
+        origin_world_index: usize,
+        target_world_index: usize,
+        location: &str,
+        zone: Zone,
+        item: Item,
+    ) -> SpoilerPlacement {
+        SpoilerPlacement {
+            origin_world_index,
+            target_world_index,
+            shared: false,
+            location: NodeSummary {
+                identifier: location.to_string(),
+                position: None,
+                zone: Some(zone),
+            },
+            item_name: item.to_string(),
+            item,
+        }
+    }
+    fn seed() -> SeedSpoiler {
+        let group = |placements| SpoilerGroup {
+            reachable: vec![],
+            forced_items: Inventory::default(),
+            placements,
+        };
+        let mut shared = placement(
+            1,
+            1,
+            "MarshPastOpher.BowArea",
+            Zone::Marsh,
+            Item::SpiritLight(50),
+        );
+        shared.shared = true;
+
+        SeedSpoiler {
+            spawns: vec!["MarshSpawn.Main".to_string(); 2],
+            groups: vec![
+                group(vec![
+                    placement(
+                        0,
+                        0,
+                        "MarshSpawn.RockHC",
+                        Zone::Marsh,
+                        Item::Skill(Skill::Launch),
+                    ),
+                    placement(
+                        0,
+                        1,
+                        "MarshSpawn.CaveEX",
+                        Zone::Marsh,
+                        Item::SpiritLight(100),
+                    ),
+                ]),
+                group(vec![
+                    placement(
+                        1,
+                        0,
+                        "WestHollow.LeafPile",
+                        Zone::Hollow,
+                        Item::Resource(Resource::Keystone),
+                    ),
+                    placement(
+                        0,
+                        0,
+                        "EastHollow.RockEX",
+                        Zone::Hollow,
+                        Item::Skill(Skill::Bash),
+                    ),
+                ]),
+                group(vec![shared]),
+            ],
+            doors: vec![],
+            sharing: MultiworldSharing::SharedPool,
+        }
+    }
+    fn analyze(json: &str, world_index: usize) -> Vec<String> {
+        CustomStats::from_json(json)
+            .unwrap()
+            .analyze(&seed(), world_index)
+    }
+
+    #[test]
+    fn results() {
+        let analyze =
+            |result: &str| analyze(&format!(r#"{{ "title": "", "result": "{result}" }}"#), 0);
+
+        assert_eq!(analyze("step"), ["0", "1", "1", "2"]);
+        assert_eq!(
+            analyze("item"),
+            ["Launch", "Keystone", "Bash", "50 Spirit Light"]
+        );
+        assert_eq!(
+            analyze("itemType"),
+            ["Skill", "Resource", "Skill", "SpiritLight"]
+        );
+        assert_eq!(
+            analyze("location"),
+            [
+                "MarshSpawn.RockHC",
+                "WestHollow.LeafPile",
+                "EastHollow.RockEX",
+                "MarshPastOpher.BowArea"
+            ]
+        );
+        assert_eq!(
+            analyze("region"),
+            ["MarshSpawn", "WestHollow", "EastHollow", "MarshPastOpher"]
+        );
+        assert_eq!(analyze("zone"), ["Marsh", "Hollow", "Hollow", "Marsh"]);
+        assert_eq!(analyze("count"), ["4"]);
+    }
+
+    #[test]
+    fn placement_selection() {
+        // World 1 receives the Spirit Light placed in world 0 and the shared placement
+        let received = r#"{ "title": "", "result": "location" }"#;
+        assert_eq!(
+            analyze(received, 1),
+            ["MarshSpawn.CaveEX", "MarshPastOpher.BowArea"]
+        );
+
+        let placed = r#"{ "title": "", "placements": "placed", "result": "location" }"#;
+        assert_eq!(
+            analyze(placed, 1),
+            ["WestHollow.LeafPile", "MarshPastOpher.BowArea"]
+        );
+        assert_eq!(
+            analyze(placed, 0),
+            [
+                "MarshSpawn.RockHC",
+                "MarshSpawn.CaveEX",
+                "EastHollow.RockEX"
+            ]
+        );
+    }
+
+    #[test]
+    fn filters() {
+        let skills = r#"{ "title": "", "filter": { "itemType": "Skill" }, "result": "item" }"#;
+        assert_eq!(analyze(skills, 0), ["Launch", "Bash"]);
+
+        let first = r#"{ "title": "", "filter": { "itemType": "Skill" }, "first": true, "result": "item" }"#;
+        assert_eq!(analyze(first, 0), ["Launch"]);
+
+        let steps =
+            r#"{ "title": "", "filter": { "fromStep": 1, "beforeStep": 2 }, "result": "item" }"#;
+        assert_eq!(analyze(steps, 0), ["Keystone", "Bash"]);
+
+        let zone = r#"{ "title": "", "filter": { "zone": "Hollow", "locationRegex": "^East" }, "result": "item" }"#;
+        assert_eq!(analyze(zone, 0), ["Bash"]);
+
+        let regex =
+            r#"{ "title": "", "filter": { "itemRegex": "Spirit Light$" }, "result": "count" }"#;
+        assert_eq!(analyze(regex, 0), ["1"]);
+        assert_eq!(analyze(regex, 1), ["2"]);
+
+        let none = r#"{ "title": "", "filter": { "item": "Grapple" }, "result": "count" }"#;
+        assert_eq!(analyze(none, 0), ["0"]);
+    }
+
+    #[test]
+    fn buckets() {
+        let steps = r#"{ "title": "", "result": "step", "resultBucketSize": 2 }"#;
+        assert_eq!(analyze(steps, 0), ["0-1", "0-1", "0-1", "2-3"]);
+
+        let count = r#"{ "title": "", "result": "count", "resultBucketSize": 3 }"#;
+        assert_eq!(analyze(count, 0), ["3-5"]);
+    }
+}
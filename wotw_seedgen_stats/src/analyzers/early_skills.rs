@@ -12,7 +12,7 @@ impl Analyzer for EarlySkillsStats {
         format!("Skills within {} reachables", self.reachable_limit)
     }
 
-    fn analyze(&self, seed: &SeedSpoiler) -> Vec<String> {
+    fn analyze(&self, seed: &SeedSpoiler, world_index: usize) -> Vec<String> {
         #[inline]
         fn is_skill(item: &Item) -> bool {
             match item {
@@ -25,21 +25,23 @@ impl Analyzer for EarlySkillsStats {
             }
         }
 
-        let first_reachables = seed
-            .groups
-            .iter()
-            .flat_map(|group| group.reachable.iter().flatten())
-            .take(self.reachable_limit)
-            .map(|node| &node.identifier)
-            .collect::<FxHashSet<_>>();
+        // Skills may be placed in other worlds, so the first reachables of every world are needed
+        let first_reachables = (0..seed.spawns.len())
+            .map(|world_index| {
+                seed.groups
+                    .iter()
+                    .flat_map(|group| group.reachable.get(world_index).into_iter().flatten())
+                    .take(self.reachable_limit)
+                    .map(|node| &node.identifier)
+                    .collect::<FxHashSet<_>>()
+            })
+            .collect::<Vec<_>>();
 
-        let early_skills = seed
-            .groups
-            .iter()
-            .flat_map(|group| group.placements.iter())
+        let early_skills = super::received_items(seed, world_index)
             .filter(|placement| {
                 is_skill(&placement.item)
-                    && first_reachables.contains(&&placement.location.identifier)
+                    && first_reachables[placement.origin_world_index]
+                        .contains(&&placement.location.identifier)
             })
             .count();
 
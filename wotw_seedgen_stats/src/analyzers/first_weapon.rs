@@ -9,30 +9,24 @@ impl Analyzer for FirstWeaponStats {
         "First Weapon".to_string()
     }
 
-    fn analyze(&self, seed: &SeedSpoiler) -> Vec<String> {
-        seed.groups
-            .iter()
-            .find_map(|group| {
-                group
-                    .placements
-                    .iter()
-                    .find(|placement| {
-                        matches!(
-                            placement.item,
-                            Item::Skill(
-                                Skill::Grenade
-                                    | Skill::Spear
-                                    | Skill::Bow
-                                    | Skill::Hammer
-                                    | Skill::Sword
-                                    | Skill::Shuriken
-                                    | Skill::Blaze
-                                    | Skill::Sentry
-                            )
-                        )
-                    })
-                    .map(|placement| placement.item_name.clone())
+    fn analyze(&self, seed: &SeedSpoiler, world_index: usize) -> Vec<String> {
+        super::received_items(seed, world_index)
+            .find(|placement| {
+                matches!(
+                    placement.item,
+                    Item::Skill(
+                        Skill::Grenade
+                            | Skill::Spear
+                            | Skill::Bow
+                            | Skill::Hammer
+                            | Skill::Sword
+                            | Skill::Shuriken
+                            | Skill::Blaze
+                            | Skill::Sentry
+                    )
+                )
             })
+            .map(|placement| placement.item_name.clone())
             .into_iter()
             .collect()
     }
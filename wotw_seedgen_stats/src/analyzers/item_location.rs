@@ -13,10 +13,8 @@ impl Analyzer for ItemLocationStats {
         format!("Location of {}", self.item)
     }
 
-    fn analyze(&self, seed: &SeedSpoiler) -> Vec<String> {
-        seed.groups
-            .iter()
-            .flat_map(|group| group.placements.iter())
+    fn analyze(&self, seed: &SeedSpoiler, world_index: usize) -> Vec<String> {
+        super::received_items(seed, world_index)
             .filter(|placement| placement.item_name == self.item)
             .map(|placement| placement.location.identifier.clone())
             .collect()
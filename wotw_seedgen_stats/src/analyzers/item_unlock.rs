@@ -15,17 +15,18 @@ impl Analyzer for ItemUnlockStats {
         format!("Reachables on {} unlock", self.item)
     }
 
-    fn analyze(&self, seed: &SeedSpoiler) -> Vec<String> {
+    fn analyze(&self, seed: &SeedSpoiler, world_index: usize) -> Vec<String> {
         let reachable_until_unlocked = seed
             .groups
             .iter()
             .take_while(|group| {
-                !group
-                    .placements
-                    .iter()
-                    .any(|placement| placement.item_name == self.item)
+                !group.placements.iter().any(|placement| {
+                    (placement.shared || placement.target_world_index == world_index)
+                        && placement.item_name == self.item
+                })
             })
-            .flat_map(|group| group.reachable.iter().map(|reachable| reachable.len()))
+            .filter_map(|group| group.reachable.get(world_index))
+            .map(|reachable| reachable.len())
             .sum::<usize>();
 
         vec![super::group_result(
@@ -11,10 +11,8 @@ impl Analyzer for LocationItemStats {
         format!("Item placed at {}", self.location)
     }
 
-    fn analyze(&self, seed: &SeedSpoiler) -> Vec<String> {
-        seed.groups
-            .iter()
-            .flat_map(|group| group.placements.iter())
+    fn analyze(&self, seed: &SeedSpoiler, world_index: usize) -> Vec<String> {
+        super::world_locations(seed, world_index)
             .find(|placement| placement.location.identifier == self.location)
             .map(|placement| placement.item_name.clone())
             .into_iter()
@@ -1,4 +1,5 @@
 mod compare;
+mod custom;
 mod early_skills;
 mod first_weapon;
 mod item_location;
@@ -6,6 +7,7 @@ mod item_unlock;
 mod item_zone;
 mod location_item;
 mod progression;
+mod seed_score;
 mod spawn_item_count;
 mod spawn_items;
 mod spawn_location;
@@ -15,6 +17,7 @@ mod zone_spirit_light;
 mod zone_unlock;
 
 use compare::*;
+pub use custom::{CustomResult, CustomStats, PlacementFilter, PlacementSelection};
 pub use early_skills::EarlySkillsStats;
 pub use first_weapon::FirstWeaponStats;
 pub use item_location::ItemLocationStats;
@@ -22,6 +25,7 @@ pub use item_unlock::ItemUnlockStats;
 pub use item_zone::ItemZoneStats;
 pub use location_item::LocationItemStats;
 pub use progression::ProgressionStats;
+pub use seed_score::SeedScoreStats;
 pub use spawn_item_count::SpawnItemCountStats;
 pub use spawn_items::SpawnItemStats;
 pub use spawn_location::SpawnLocationStats;
@@ -32,7 +36,7 @@ pub use zone_unlock::ZoneUnlockStats;
 
 use std::{cmp::Ordering, num::NonZeroUsize};
 
-use wotw_seedgen::generator::SeedSpoiler;
+use wotw_seedgen::generator::{SeedSpoiler, SpoilerPlacement};
 
 /// Trait for types that may analyze seeds and generate statistics
 ///
@@ -41,34 +45,43 @@ pub trait Analyzer: Sync {
     /// A brief title describing what kind of statistic is analyzed
     fn title(&self) -> String;
 
-    /// Analyze a given seed and return one or more keys that this seed should be categorized into
+    /// Analyze one world of a given seed and return one or more keys that this world should be categorized into
     ///
-    /// For instance, [`SpawnLocationStats`] will return the name of the spawn locations here
-    fn analyze(&self, seed: &SeedSpoiler) -> Vec<String>;
+    /// For instance, [`SpawnLocationStats`] will return the name of the world's spawn location here
+    ///
+    /// Which worlds get analyzed depends on the [`WorldScope`](crate::WorldScope), single world seeds only have the `world_index` 0
+    fn analyze(&self, seed: &SeedSpoiler, world_index: usize) -> Vec<String>;
 
     /// Compare two keys created by this analyzer
     ///
     /// You can manually implement this to customize how your keys will be ordered in the resulting csv
     fn compare_keys(&self) -> fn(&str, &str) -> Ordering {
         // We need the indirection of returning a function pointer so the trait can still be made into a trait object
-        fn compare(a: &str, b: &str) -> Ordering {
-            match a
-                .split('-')
-                .next()
-                .unwrap()
-                .parse::<u32>()
-                .into_iter()
-                .zip(b.split('-').next().unwrap().parse::<u32>())
-                .next()
-            {
-                None => a.cmp(b),
-                Some((a, b)) => a.cmp(&b),
-            }
-        }
-        compare
+        compare_numeric
     }
 }
 
+/// Placements of items the world receives, no matter which world they were placed in
+fn received_items(
+    seed: &SeedSpoiler,
+    world_index: usize,
+) -> impl Iterator<Item = &SpoilerPlacement> {
+    seed.groups
+        .iter()
+        .flat_map(|group| group.placements.iter())
+        .filter(move |placement| placement.shared || placement.target_world_index == world_index)
+}
+/// Placements on locations in the world, no matter which world receives them
+fn world_locations(
+    seed: &SeedSpoiler,
+    world_index: usize,
+) -> impl Iterator<Item = &SpoilerPlacement> {
+    seed.groups
+        .iter()
+        .flat_map(|group| group.placements.iter())
+        .filter(move |placement| placement.origin_world_index == world_index)
+}
+
 fn group_result(result: usize, bucket_size: NonZeroUsize) -> String {
     let bucket_size = bucket_size.get();
     if bucket_size == 1 {
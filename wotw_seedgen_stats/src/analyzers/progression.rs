@@ -11,11 +11,24 @@ impl Analyzer for ProgressionStats {
         "Progression items".to_string()
     }
 
-    fn analyze(&self, seed: &SeedSpoiler) -> Vec<String> {
+    fn analyze(&self, seed: &SeedSpoiler, world_index: usize) -> Vec<String> {
+        // Forced items aren't recorded per world, but they are always placed in the same group
         seed.groups
             .iter()
-            .flat_map(|group| group.forced_items.items.iter())
-            .flat_map(|(item, amount)| iter::repeat(item.to_string()).take(*amount as usize))
+            .flat_map(|group| {
+                group.forced_items.items.iter().map(move |(item, amount)| {
+                    let received = group
+                        .placements
+                        .iter()
+                        .filter(|placement| {
+                            (placement.shared || placement.target_world_index == world_index)
+                                && &placement.item == item
+                        })
+                        .count();
+                    (item, received.min(*amount as usize))
+                })
+            })
+            .flat_map(|(item, amount)| iter::repeat(item.to_string()).take(amount))
             .collect()
     }
 }
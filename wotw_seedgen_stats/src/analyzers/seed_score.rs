@@ -0,0 +1,21 @@
+use std::num::NonZeroUsize;
+
+use wotw_seedgen::generator::SeedSpoiler;
+
+use super::Analyzer;
+
+/// Analyzes the total [`SeedScore`](wotw_seedgen::generator::SeedScore), which estimates how long and tedious a seed is
+pub struct SeedScoreStats {
+    /// How many adjacent result to group together
+    pub result_bucket_size: NonZeroUsize,
+}
+impl Analyzer for SeedScoreStats {
+    fn title(&self) -> String {
+        "Seed score".to_string()
+    }
+
+    fn analyze(&self, seed: &SeedSpoiler, world_index: usize) -> Vec<String> {
+        let score = seed.score(world_index).total();
+        vec![super::group_result(score as usize, self.result_bucket_size)]
+    }
+}
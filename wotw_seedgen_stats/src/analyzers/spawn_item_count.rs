@@ -9,11 +9,8 @@ impl Analyzer for SpawnItemCountStats {
         "Spawn Item Count".to_string()
     }
 
-    fn analyze(&self, seed: &SeedSpoiler) -> Vec<String> {
-        let count = seed
-            .groups
-            .iter()
-            .flat_map(|group| group.placements.iter())
+    fn analyze(&self, seed: &SeedSpoiler, world_index: usize) -> Vec<String> {
+        let count = super::world_locations(seed, world_index)
             .filter(|placement| placement.location.identifier == "Spawn")
             .count();
 
@@ -9,10 +9,8 @@ impl Analyzer for SpawnItemStats {
         "Spawn Items".to_string()
     }
 
-    fn analyze(&self, seed: &SeedSpoiler) -> Vec<String> {
-        seed.groups
-            .iter()
-            .flat_map(|group| group.placements.iter())
+    fn analyze(&self, seed: &SeedSpoiler, world_index: usize) -> Vec<String> {
+        super::world_locations(seed, world_index)
             .filter(|placement| placement.location.identifier == "Spawn")
             .map(|placement| placement.item_name.clone())
             .collect()
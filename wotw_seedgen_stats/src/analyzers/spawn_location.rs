@@ -11,8 +11,8 @@ impl Analyzer for SpawnLocationStats {
         "Spawn Location".to_string()
     }
 
-    fn analyze(&self, seed: &SeedSpoiler) -> Vec<String> {
-        seed.spawns.clone()
+    fn analyze(&self, seed: &SeedSpoiler, world_index: usize) -> Vec<String> {
+        vec![seed.spawns[world_index].clone()]
     }
 
     fn compare_keys(&self) -> fn(&str, &str) -> Ordering {
@@ -11,11 +11,9 @@ impl Analyzer for SpawnRegionStats {
         "Spawn Region".to_string()
     }
 
-    fn analyze(&self, seed: &SeedSpoiler) -> Vec<String> {
-        seed.spawns
-            .iter()
-            .map(|spawn| spawn.split('.').next().unwrap().to_string())
-            .collect()
+    fn analyze(&self, seed: &SeedSpoiler, world_index: usize) -> Vec<String> {
+        let spawn = &seed.spawns[world_index];
+        vec![spawn.split('.').next().unwrap().to_string()]
     }
 
     fn compare_keys(&self) -> fn(&str, &str) -> Ordering {
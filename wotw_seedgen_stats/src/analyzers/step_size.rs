@@ -14,19 +14,11 @@ impl Analyzer for StepSizeStats {
         "Size of progression steps".to_string()
     }
 
-    fn analyze(&self, seed: &SeedSpoiler) -> Vec<String> {
+    fn analyze(&self, seed: &SeedSpoiler, world_index: usize) -> Vec<String> {
         seed.groups
             .iter()
-            .map(|group| {
-                super::group_result(
-                    group
-                        .reachable
-                        .iter()
-                        .map(|reachable| reachable.len())
-                        .sum(),
-                    self.result_bucket_size,
-                )
-            })
+            .filter_map(|group| group.reachable.get(world_index))
+            .map(|reachable| super::group_result(reachable.len(), self.result_bucket_size))
             .collect()
     }
 }
@@ -15,11 +15,8 @@ impl Analyzer for ZoneSpiritLightStats {
         format!("Spirit Light in {}", self.zone)
     }
 
-    fn analyze(&self, seed: &SeedSpoiler) -> Vec<String> {
-        let spirit_light = seed
-            .groups
-            .iter()
-            .flat_map(|group| group.placements.iter())
+    fn analyze(&self, seed: &SeedSpoiler, world_index: usize) -> Vec<String> {
+        let spirit_light = super::world_locations(seed, world_index)
             .filter(|placement| {
                 placement
                     .location
@@ -17,16 +17,18 @@ impl Analyzer for ZoneUnlockStats {
         format!("Reachables on {} unlock", self.zone)
     }
 
-    fn analyze(&self, seed: &SeedSpoiler) -> Vec<String> {
+    fn analyze(&self, seed: &SeedSpoiler, world_index: usize) -> Vec<String> {
         let groups_until_unlocked = seed.groups.iter().take_while(|group| {
             !group
                 .reachable
-                .iter()
+                .get(world_index)
+                .into_iter()
                 .flatten()
                 .any(|node| node.zone.map_or(false, |zone| zone == self.zone))
         });
         let reachable_count = groups_until_unlocked
-            .flat_map(|group| group.reachable.iter().map(|reachable| reachable.len()))
+            .filter_map(|group| group.reachable.get(world_index))
+            .map(|reachable| reachable.len())
             .sum::<usize>();
 
         vec![super::group_result(
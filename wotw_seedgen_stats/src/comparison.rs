@@ -0,0 +1,311 @@
+use std::{fmt::Write, sync::Arc};
+
+use itertools::Itertools;
+use rustc_hash::FxHashSet;
+use serde::Serialize;
+
+use crate::{summary::percentage, Stats};
+
+/// Differences with a p-value below this count as significant
+pub const SIGNIFICANCE_LEVEL: f64 = 0.05;
+
+/// The same stats generated for two [`Variant`](crate::Variant)s
+///
+/// See [`compare`](crate::compare)
+pub struct Comparison {
+    pub baseline: Stats,
+    pub changed: Stats,
+}
+impl Comparison {
+    pub fn title(&self) -> String {
+        format!("{} Comparison", self.baseline.title())
+    }
+    pub fn csv(&self) -> String {
+        let mut csv = self.baseline.title();
+        csv.push_str(
+            ", Baseline Count, Changed Count, Baseline %, Changed %, Difference, p-value\n",
+        );
+
+        csv.extend(Itertools::intersperse_with(
+            self.summary().rows.into_iter().map(|row| {
+                let mut data_line = row.keys.iter().join(", ");
+                write!(
+                    data_line,
+                    ", {}, {}, {:.2}, {:.2}, {:+.2}, {:.4}",
+                    row.baseline_count,
+                    row.changed_count,
+                    row.baseline_percentage,
+                    row.changed_percentage,
+                    row.difference,
+                    row.p_value,
+                )
+                .unwrap();
+                data_line
+            }),
+            || "\n".to_string(),
+        ));
+
+        csv
+    }
+    /// Compare every key and the distributions of numeric results with a significance test
+    ///
+    /// See [`ComparisonSummary`] for details
+    pub fn summary(&self) -> ComparisonSummary {
+        let baseline_samples = self.baseline.samples;
+        let changed_samples = self.changed.samples;
+
+        let mut keys = self
+            .baseline
+            .data
+            .keys()
+            .chain(self.changed.data.keys())
+            .collect::<FxHashSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        keys.sort_unstable_by(|a, b| self.baseline.compare_keys(a, b));
+
+        let rows = keys
+            .into_iter()
+            .map(|keys| {
+                let count = |stats: &Stats| stats.data.get(keys).copied().unwrap_or_default();
+                let baseline_count = count(&self.baseline);
+                let changed_count = count(&self.changed);
+                let baseline_percentage =
+                    percentage(baseline_count as f64, baseline_samples as f64);
+                let changed_percentage = percentage(changed_count as f64, changed_samples as f64);
+                let p_value = rate_p_value(
+                    baseline_count,
+                    baseline_samples,
+                    changed_count,
+                    changed_samples,
+                );
+
+                ComparisonRow {
+                    keys: keys.iter().map(Arc::to_string).collect(),
+                    baseline_count,
+                    changed_count,
+                    baseline_percentage,
+                    changed_percentage,
+                    difference: changed_percentage - baseline_percentage,
+                    p_value,
+                    significant: p_value < SIGNIFICANCE_LEVEL,
+                }
+            })
+            .collect();
+
+        let baseline_distributions = self.baseline.summary().distributions;
+        let distributions = self
+            .changed
+            .summary()
+            .distributions
+            .into_iter()
+            .filter_map(|changed| {
+                let baseline = baseline_distributions
+                    .iter()
+                    .find(|baseline| baseline.keys == changed.keys)?;
+                let p_value = mean_p_value(
+                    (baseline.mean, baseline.standard_deviation, baseline.count),
+                    (changed.mean, changed.standard_deviation, changed.count),
+                );
+
+                Some(DistributionComparison {
+                    keys: changed.keys,
+                    baseline_mean: baseline.mean,
+                    changed_mean: changed.mean,
+                    difference: changed.mean - baseline.mean,
+                    baseline_median: baseline.median,
+                    changed_median: changed.median,
+                    p_value,
+                    significant: p_value < SIGNIFICANCE_LEVEL,
+                })
+            })
+            .collect();
+
+        ComparisonSummary {
+            title: self.title(),
+            baseline_samples,
+            changed_samples,
+            rows,
+            distributions,
+        }
+    }
+}
+
+/// Differences between the two [`Variant`](crate::Variant)s of a [`Comparison`]
+///
+/// Keep in mind that when comparing many keys, some of them will likely show significant differences by chance
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComparisonSummary {
+    pub title: String,
+    pub baseline_samples: usize,
+    pub changed_samples: usize,
+    /// One row for every combination of keys that occured in either variant, in the same order as the csv
+    pub rows: Vec<ComparisonRow>,
+    /// Compares the [`Distribution`](crate::summary::Distribution)s that exist in both variants
+    pub distributions: Vec<DistributionComparison>,
+}
+impl ComparisonSummary {
+    /// Serialize the summary into json format
+    pub fn to_json(&self) -> String {
+        // This is safe because the summary is known to serialize successfully
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+/// How often one combination of keys occured in each variant
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComparisonRow {
+    pub keys: Vec<String>,
+    pub baseline_count: u32,
+    pub changed_count: u32,
+    /// `baseline_count` relative to the amount of baseline samples
+    pub baseline_percentage: f64,
+    /// `changed_count` relative to the amount of changed samples
+    pub changed_percentage: f64,
+    /// `changed_percentage - baseline_percentage`, in percentage points
+    pub difference: f64,
+    /// Two-sided p-value of a two-proportion z-test, or of a Poisson rate test if a key occured more often than there were samples
+    pub p_value: f64,
+    /// Whether the `p_value` is below [`SIGNIFICANCE_LEVEL`]
+    pub significant: bool,
+}
+
+/// Compares the means of a numeric result
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DistributionComparison {
+    pub keys: Vec<String>,
+    pub baseline_mean: f64,
+    pub changed_mean: f64,
+    /// `changed_mean - baseline_mean`
+    pub difference: f64,
+    pub baseline_median: f64,
+    pub changed_median: f64,
+    /// Two-sided p-value of Welch's test, using the normal approximation since samples are large
+    pub p_value: f64,
+    /// Whether the `p_value` is below [`SIGNIFICANCE_LEVEL`]
+    pub significant: bool,
+}
+
+fn rate_p_value(
+    baseline_count: u32,
+    baseline_samples: usize,
+    changed_count: u32,
+    changed_samples: usize,
+) -> f64 {
+    if baseline_samples == 0 || changed_samples == 0 {
+        return 1.;
+    }
+
+    let (baseline_count, changed_count) = (baseline_count as f64, changed_count as f64);
+    let (baseline_samples, changed_samples) = (baseline_samples as f64, changed_samples as f64);
+    let baseline_rate = baseline_count / baseline_samples;
+    let changed_rate = changed_count / changed_samples;
+
+    let variance = if baseline_rate <= 1. && changed_rate <= 1. {
+        let pooled = (baseline_count + changed_count) / (baseline_samples + changed_samples);
+        pooled * (1. - pooled) * (1. / baseline_samples + 1. / changed_samples)
+    } else {
+        baseline_count / baseline_samples.powi(2) + changed_count / changed_samples.powi(2)
+    };
+
+    z_test(changed_rate - baseline_rate, variance)
+}
+fn mean_p_value(baseline: (f64, f64, u32), changed: (f64, f64, u32)) -> f64 {
+    let (baseline_mean, baseline_deviation, baseline_count) = baseline;
+    let (changed_mean, changed_deviation, changed_count) = changed;
+
+    let variance = baseline_deviation.powi(2) / baseline_count as f64
+        + changed_deviation.powi(2) / changed_count as f64;
+
+    z_test(changed_mean - baseline_mean, variance)
+}
+/// Two-sided p-value of observing `difference` if there was no difference
+fn z_test(difference: f64, variance: f64) -> f64 {
+    if variance <= 0. || !variance.is_finite() {
+        // Without any variance, only the exact same result can happen by chance
+        return if difference == 0. { 1. } else { 0. };
+    }
+
+    let z = difference.abs() / variance.sqrt();
+    erfc(z / std::f64::consts::SQRT_2)
+}
+/// Complementary error function for non-negative `x`, using the approximation by Abramowitz and Stegun (7.1.26)
+fn erfc(x: f64) -> f64 {
+    const P: f64 = 0.327_591_1;
+    const A: [f64; 5] = [
+        0.254_829_592,
+        -0.284_496_736,
+        1.421_413_741,
+        -1.453_152_027,
+        1.061_405_429,
+    ];
+
+    let t = 1. / (1. + P * x);
+    let polynomial = A.iter().rev().fold(0., |sum, a| (sum + a) * t);
+    polynomial * (-x * x).exp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Reference p-values were computed with an exact complementary error function
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-6,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn complementary_error_function() {
+        assert_close(erfc(0.), 1.);
+        assert_close(erfc(0.5), 0.479_500_1);
+        assert_close(erfc(1.), 0.157_299_2);
+        assert_close(erfc(2.), 0.004_677_7);
+        assert_close(erfc(3.), 0.000_022_1);
+    }
+
+    #[test]
+    fn proportion_p_values() {
+        // Two-proportion z-test with pooled variance
+        assert_close(rate_p_value(50, 100, 60, 100), 0.155_218_5);
+        assert_close(rate_p_value(60, 100, 50, 100), 0.155_218_5);
+        assert_close(rate_p_value(100, 1000, 150, 1000), 0.000_723_2);
+        assert_close(rate_p_value(50, 100, 50, 100), 1.);
+
+        assert_eq!(rate_p_value(5, 0, 5, 100), 1.);
+        assert_eq!(rate_p_value(5, 100, 5, 0), 1.);
+    }
+
+    #[test]
+    fn poisson_p_values() {
+        // Keys occuring more often than there were samples are compared as rates
+        assert_close(rate_p_value(150, 100, 200, 100), 0.007_526_3);
+    }
+
+    #[test]
+    fn mean_p_values() {
+        // Welch's test with the normal approximation
+        assert_close(mean_p_value((10., 2., 50), (11., 2., 50)), 0.012_419_3);
+        assert_close(mean_p_value((11., 2., 50), (10., 2., 50)), 0.012_419_3);
+        assert_close(mean_p_value((10., 2., 50), (10., 3., 80)), 1.);
+    }
+
+    #[test]
+    fn zero_variance() {
+        assert_eq!(z_test(0., 0.), 1.);
+        assert_eq!(z_test(1., 0.), 0.);
+        assert_eq!(z_test(-1., 0.), 0.);
+        assert_eq!(z_test(1., f64::INFINITY), 0.);
+
+        // A key that never or always occurs in both variants has no variance
+        assert_eq!(rate_p_value(0, 100, 0, 100), 1.);
+        assert_eq!(rate_p_value(100, 100, 100, 100), 1.);
+        assert_eq!(mean_p_value((5., 0., 10), (5., 0., 10)), 1.);
+        assert_eq!(mean_p_value((5., 0., 10), (6., 0., 10)), 0.);
+    }
+}
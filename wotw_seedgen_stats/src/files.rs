@@ -12,14 +12,20 @@ use crate::Result;
 /// You can work without this behaviour by using [`NoFileAccess`], generating all seeds frmo scratch every time
 ///
 /// If the `fs` feature is enabled, you may use [`FileSystemAccess`] to store seeds in the local filesystem
+///
+/// Seeds are identified by the index of the sample they were generated for, which allows resuming interrupted stats runs
 pub trait FileAccess {
-    type Iter: Iterator<Item = Result<SeedSpoiler>>;
+    type Iter: Iterator<Item = Result<(usize, SeedSpoiler)>>;
 
-    /// fetch seeds that have been previously generated with these settings
-    fn read_seeds(settings: &UniverseSettings, limit: usize) -> Result<Self::Iter>;
+    /// fetch seeds that have been previously generated with these settings, along with the `key` they were written with
+    ///
+    /// Only seeds with a `key` below `sample_size` are needed
+    fn read_seeds(settings: &UniverseSettings, sample_size: usize) -> Result<Self::Iter>;
     /// write a seed generated from these settings for later use
     ///
-    /// `key` should be unique, although it is recommended you don't rely on this being true and take it as a hint for what key you could use
+    /// `key` is the index of the sample this seed was generated for. Samples are generated deterministically, so a seed previously written with the same `key` may be overwritten
+    ///
+    /// This may be called from multiple threads at once
     fn write_seed(seed: &SeedSpoiler, settings: &UniverseSettings, key: usize) -> Result<()>;
     /// clean all seeds that have previously been generated with these settings
     fn clean_seeds(settings: &UniverseSettings) -> Result<()>;
@@ -32,7 +38,7 @@ pub trait FileAccess {
 /// This prevents storing seeds for future stats, so all the seeds will have to be generated from scratch every time
 pub struct NoFileAccess;
 impl FileAccess for NoFileAccess {
-    type Iter = iter::Empty<Result<SeedSpoiler>>;
+    type Iter = iter::Empty<Result<(usize, SeedSpoiler)>>;
 
     fn read_seeds(_: &UniverseSettings, _: usize) -> Result<Self::Iter> {
         Ok(iter::empty())
@@ -48,68 +54,198 @@ impl FileAccess for NoFileAccess {
     }
 }
 
+#[cfg(feature = "fs")]
+mod stored_spoiler {
+    use serde::{Deserialize, Serialize};
+    use wotw_seedgen::{
+        generator::{NodeSummary, SeedSpoiler, SpoilerDoor, SpoilerGroup, SpoilerPlacement},
+        settings::MultiworldSharing,
+        Inventory, Item,
+    };
+
+    // The spoiler skips some fields when serializing to keep its json small, but bincode can't tell skipped fields apart
+    // So seeds are stored as these mirrored structs which never skip anything
+
+    #[derive(Serialize, Deserialize)]
+    struct StoredSpoiler {
+        spawns: Vec<String>,
+        groups: Vec<StoredGroup>,
+        doors: Vec<Vec<SpoilerDoor>>,
+        sharing: MultiworldSharing,
+    }
+    #[derive(Serialize, Deserialize)]
+    struct StoredGroup {
+        reachable: Vec<Vec<NodeSummary>>,
+        forced_items: Inventory,
+        placements: Vec<StoredPlacement>,
+    }
+    #[derive(Serialize, Deserialize)]
+    struct StoredPlacement {
+        origin_world_index: usize,
+        target_world_index: usize,
+        shared: bool,
+        location: NodeSummary,
+        item: Item,
+        item_name: String,
+    }
+
+    /// Serializes the seed into the format used for seed storage
+    pub(super) fn encode(seed: &SeedSpoiler) -> Vec<u8> {
+        let seed = seed.clone();
+        let stored = StoredSpoiler {
+            spawns: seed.spawns,
+            groups: seed
+                .groups
+                .into_iter()
+                .map(|group| StoredGroup {
+                    reachable: group.reachable,
+                    forced_items: group.forced_items,
+                    placements: group
+                        .placements
+                        .into_iter()
+                        .map(|placement| StoredPlacement {
+                            origin_world_index: placement.origin_world_index,
+                            target_world_index: placement.target_world_index,
+                            shared: placement.shared,
+                            location: placement.location,
+                            item: placement.item,
+                            item_name: placement.item_name,
+                        })
+                        .collect(),
+                })
+                .collect(),
+            doors: seed.doors,
+            sharing: seed.sharing,
+        };
+        bincode::serialize(&stored).expect("Failed to serialize spoiler")
+    }
+    /// Deserializes a seed from the format used for seed storage
+    pub(super) fn decode(bytes: &[u8]) -> bincode::Result<SeedSpoiler> {
+        let stored = bincode::deserialize::<StoredSpoiler>(bytes)?;
+        Ok(SeedSpoiler {
+            spawns: stored.spawns,
+            groups: stored
+                .groups
+                .into_iter()
+                .map(|group| SpoilerGroup {
+                    reachable: group.reachable,
+                    forced_items: group.forced_items,
+                    placements: group
+                        .placements
+                        .into_iter()
+                        .map(|placement| SpoilerPlacement {
+                            origin_world_index: placement.origin_world_index,
+                            target_world_index: placement.target_world_index,
+                            shared: placement.shared,
+                            location: placement.location,
+                            item: placement.item,
+                            item_name: placement.item_name,
+                        })
+                        .collect(),
+                })
+                .collect(),
+            doors: stored.doors,
+            sharing: stored.sharing,
+        })
+    }
+
+    // Before seeds had their own storage format, the spoiler was stored directly
+    // Back then it had no doors, sharing or shared placements
+
+    #[derive(Deserialize)]
+    struct LegacySpoiler {
+        spawns: Vec<String>,
+        groups: Vec<LegacyGroup>,
+    }
+    #[derive(Deserialize)]
+    struct LegacyGroup {
+        reachable: Vec<Vec<NodeSummary>>,
+        forced_items: Inventory,
+        placements: Vec<LegacyPlacement>,
+    }
+    #[derive(Deserialize)]
+    struct LegacyPlacement {
+        origin_world_index: usize,
+        target_world_index: usize,
+        location: NodeSummary,
+        item: Item,
+        item_name: String,
+    }
+
+    /// Deserializes a seed stored by an earlier version, before seed storage had its own format
+    pub(super) fn decode_legacy(bytes: &[u8]) -> bincode::Result<SeedSpoiler> {
+        let legacy = bincode::deserialize::<LegacySpoiler>(bytes)?;
+        Ok(SeedSpoiler {
+            spawns: legacy.spawns,
+            groups: legacy
+                .groups
+                .into_iter()
+                .map(|group| SpoilerGroup {
+                    reachable: group.reachable,
+                    forced_items: group.forced_items,
+                    placements: group
+                        .placements
+                        .into_iter()
+                        .map(|placement| SpoilerPlacement {
+                            origin_world_index: placement.origin_world_index,
+                            target_world_index: placement.target_world_index,
+                            shared: false,
+                            location: placement.location,
+                            item: placement.item,
+                            item_name: placement.item_name,
+                        })
+                        .collect(),
+                })
+                .collect(),
+            doors: vec![],
+            sharing: MultiworldSharing::default(),
+        })
+    }
+}
+
 #[cfg(feature = "fs")]
 pub use fs_access::*;
 #[cfg(feature = "fs")]
 mod fs_access {
     use crate::handle_errors::HandleErrors;
 
+    use super::stored_spoiler;
     use super::*;
 
     use std::{
-        fs::{self, DirEntry, ReadDir},
+        fs,
         hash::{Hash, Hasher},
-        io::{self, Write},
-        path::{Path, PathBuf},
+        io,
+        path::PathBuf,
+        vec,
     };
 
+    use itertools::Itertools;
     use rustc_hash::FxHasher;
+    use wotw_seedgen::settings::WorldSettings;
 
-    const SEED_STORAGE_FOLDER: &str = "seed_storage";
+    pub(super) const SEED_STORAGE_FOLDER: &str = "seed_storage";
 
     /// A [`FileAccess`] implementation storing and fetching seeds using the local filesystem
     pub struct FileSystemAccess;
     impl FileAccess for FileSystemAccess {
         type Iter = ReadSeeds;
 
-        fn read_seeds(settings: &UniverseSettings, limit: usize) -> Result<Self::Iter> {
+        fn read_seeds(settings: &UniverseSettings, sample_size: usize) -> Result<Self::Iter> {
             let path = path_from_settings(settings);
 
-            ReadSeeds::new(path, limit)
+            ReadSeeds::new(path, sample_size)
         }
 
-        fn write_seed(
-            seed: &SeedSpoiler,
-            settings: &UniverseSettings,
-            mut key: usize,
-        ) -> Result<()> {
-            let bytes = bincode::serialize(seed).expect("Failed to serialize spoiler");
-            let base_path = path_from_settings(settings);
-            fs::create_dir_all(&base_path)
+        fn write_seed(seed: &SeedSpoiler, settings: &UniverseSettings, key: usize) -> Result<()> {
+            let bytes = stored_spoiler::encode(seed);
+            let mut path = path_from_settings(settings);
+            fs::create_dir_all(&path)
                 .map_err(|err| format!("Failed to create folder for seed storage: {err}"))?;
-            loop {
-                let mut path = base_path.to_path_buf();
-                path.push(key.to_string());
-
-                match fs::OpenOptions::new()
-                    .write(true)
-                    .create_new(true)
-                    .open(&path)
-                {
-                    Ok(mut file) => {
-                        file.write_all(bytes.as_ref())
-                            .map_err(|err| format!("Failed to write seed to storage: {err}"))?;
-                        return Ok(());
-                    }
-                    Err(err) => {
-                        if err.kind() == io::ErrorKind::AlreadyExists {
-                            key += 1
-                        } else {
-                            return Err(format!("Failed to write seed to storage: {err}"));
-                        }
-                    }
-                }
-            }
+            path.push(key.to_string());
+
+            // If this gets interrupted, the partially written file will fail to deserialize and be replaced next time
+            fs::write(&path, bytes).map_err(|err| format!("Failed to write seed to storage: {err}"))
         }
         fn clean_seeds(settings: &UniverseSettings) -> Result<()> {
             let path = path_from_settings(settings);
@@ -129,98 +265,84 @@ mod fs_access {
     // An Iterator reading stored seeds from the filesystem
     pub struct ReadSeeds {
         #[allow(clippy::type_complexity)]
-        inner: Option<iter::Map<HandleErrorsReadDir, fn(DirEntry) -> Result<SeedSpoiler>>>,
+        inner: iter::Map<
+            vec::IntoIter<(usize, PathBuf)>,
+            fn((usize, PathBuf)) -> Result<(usize, SeedSpoiler)>,
+        >,
     }
     impl ReadSeeds {
-        fn new(path: PathBuf, limit: usize) -> Result<Self> {
-            fn format_read_dir_err(err: io::Error, path: &Path) -> String {
-                format!(
-                    "Failed to access seed storage at \"{}\": {}",
-                    path.display(),
-                    err
+        pub(super) fn new(path: PathBuf, sample_size: usize) -> Result<Self> {
+            let entries = match fs::read_dir(&path) {
+                Ok(read_dir) => HandleErrors::new(
+                    read_dir,
+                    (|err| eprintln!("Failed to read from seed storage: {err}")) as fn(io::Error),
                 )
-            }
-
-            match read_dir(&path, limit) {
-                Ok(dir) => print_feedback_for_existing_seeds(dir),
+                .filter_map(|entry| {
+                    // Files are named after the sample they were generated for
+                    let key = entry.file_name().to_str()?.parse::<usize>().ok()?;
+                    (key < sample_size).then(|| (key, entry.path()))
+                })
+                .collect::<Vec<_>>(),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => vec![],
                 Err(err) => {
-                    return if err.kind() == io::ErrorKind::NotFound {
-                        Ok(Self { inner: None })
-                    } else {
-                        Err(format_read_dir_err(err, &path))
-                    }
+                    return Err(format!(
+                        "Failed to access seed storage at \"{}\": {}",
+                        path.display(),
+                        err
+                    ))
                 }
-            }
+            };
 
-            let inner = read_dir(&path, limit)
-                .map_err(|err| format_read_dir_err(err, &path))?
-                .map(
-                    (|entry| {
-                        let path = entry.path();
-                        fs::read(&path)
-                            .map_err(|err| {
-                                (
-                                    format!(
-                                        "Failed to read seed from seed storage at \"{}\": {}",
-                                        path.display(),
-                                        err
-                                    ),
-                                    path.clone(),
-                                )
-                            })
-                            .map(|bytes| (bytes, path))
-                            .and_then(|(bytes, path)| {
-                                bincode::deserialize::<SeedSpoiler>(&bytes).map_err(|err| {
-                                    (
-                                        format!(
-                                            "Failed to deserialize seed from seed storage at \"{}\": {}",
-                                            path.display(),
-                                            err
-                                        ),
-                                        path,
-                                    )
-                                })
-                            })
-                            .map_err(|(err, path)| {
-                                match fs::remove_file(&path) {
-                                    Ok(()) => {
-                                        eprintln!(
-                                            "Removed \"{}\" from seed storage",
-                                            path.display()
-                                        )
-                                    }
-                                    Err(err) => {
-                                        eprintln!(
-                                            "Failed to remove \"{}\" from seed storage: {}",
-                                            path.display(),
-                                            err
-                                        )
-                                    }
-                                }
-                                err
-                            })
-                    }) as fn(DirEntry) -> Result<SeedSpoiler>,
-                );
+            print_feedback_for_existing_seeds(&entries);
+
+            let inner = entries
+                .into_iter()
+                .map(read_seed as fn((usize, PathBuf)) -> Result<(usize, SeedSpoiler)>);
 
-            Ok(Self { inner: Some(inner) })
+            Ok(Self { inner })
         }
     }
     impl Iterator for ReadSeeds {
-        type Item = Result<SeedSpoiler>;
+        type Item = Result<(usize, SeedSpoiler)>;
 
         fn next(&mut self) -> Option<Self::Item> {
-            self.inner.as_mut().and_then(Iterator::next)
+            self.inner.next()
         }
     }
-    type HandleErrorsReadDir =
-        HandleErrors<DirEntry, io::Error, iter::Take<ReadDir>, fn(io::Error)>;
-    fn read_dir<P: AsRef<Path>>(path: P, limit: usize) -> io::Result<HandleErrorsReadDir> {
-        fs::read_dir(&path).map(|read_dir| {
-            HandleErrors::new(
-                read_dir.take(limit),
-                (|err| eprintln!("Failed to read from seed storage: {err}")) as fn(io::Error),
-            )
-        })
+    fn read_seed((key, path): (usize, PathBuf)) -> Result<(usize, SeedSpoiler)> {
+        fs::read(&path)
+            .map_err(|err| {
+                format!(
+                    "Failed to read seed from seed storage at \"{}\": {}",
+                    path.display(),
+                    err
+                )
+            })
+            .and_then(|bytes| {
+                // Seeds stored by earlier versions use the legacy format
+                stored_spoiler::decode(&bytes)
+                    .or_else(|_| stored_spoiler::decode_legacy(&bytes))
+                    .map_err(|err| {
+                        format!(
+                            "Failed to deserialize seed from seed storage at \"{}\": {}",
+                            path.display(),
+                            err
+                        )
+                    })
+                    .inspect_err(|_| match fs::remove_file(&path) {
+                        Ok(()) => {
+                            eprintln!("Removed \"{}\" from seed storage", path.display())
+                        }
+                        Err(err) => {
+                            eprintln!(
+                                "Failed to remove \"{}\" from seed storage: {}",
+                                path.display(),
+                                err
+                            )
+                        }
+                    })
+            })
+            .map(|seed| (key, seed))
     }
 
     fn path_from_settings(settings: &UniverseSettings) -> PathBuf {
@@ -229,19 +351,89 @@ mod fs_access {
         path.push(folder);
         path
     }
-    fn hash_settings(settings: &UniverseSettings) -> u64 {
+    pub(super) fn hash_settings(settings: &UniverseSettings) -> u64 {
         let mut hasher = FxHasher::default();
-        let bytes =
-            bincode::serialize(&settings.world_settings).expect("Failed to serialize settings"); // We deliberately ignore the seed
+        // We deliberately ignore the seed
+        let mut bytes =
+            bincode::serialize(&settings.world_settings).expect("Failed to serialize settings");
+        // Item sharing affects how multiworld seeds are generated, the default is left out to keep the hashes of earlier versions
+        if !settings.multiworld_sharing.is_default() {
+            bytes.extend(
+                bincode::serialize(&settings.multiworld_sharing)
+                    .expect("Failed to serialize settings"),
+            );
+        }
+        // Rejecting seeds outside a score range changes which seeds are generated
+        if let Some(score_range) = &settings.score_range {
+            bytes.extend(bincode::serialize(score_range).expect("Failed to serialize settings"));
+        }
         bytes.hash(&mut hasher);
         hasher.finish()
     }
-    fn print_feedback_for_existing_seeds(seeds: HandleErrorsReadDir) {
+    /// The hash earlier versions used for these settings, before sets in the settings were serialized in sorted order
+    ///
+    /// Returns `None` if these settings use anything earlier versions didn't support
+    pub(super) fn legacy_hash_settings(settings: &UniverseSettings) -> Option<u64> {
+        if !settings.multiworld_sharing.is_default() || settings.score_range.is_some() {
+            return None;
+        }
+
+        let serialize = |sorted: bool| {
+            let world_settings = settings
+                .world_settings
+                .iter()
+                .map(|world_settings| {
+                    let WorldSettings {
+                        spawn,
+                        difficulty,
+                        tricks,
+                        hard,
+                        randomize_doors,
+                        goals,
+                        headers,
+                        header_config,
+                        inline_headers,
+                        ..
+                    } = world_settings;
+                    let (tricks, headers): (Vec<_>, Vec<_>) = if sorted {
+                        (
+                            tricks.iter().sorted().collect(),
+                            headers.iter().sorted().collect(),
+                        )
+                    } else {
+                        (tricks.iter().collect(), headers.iter().collect())
+                    };
+                    (
+                        spawn,
+                        difficulty,
+                        tricks,
+                        hard,
+                        randomize_doors,
+                        goals,
+                        headers,
+                        header_config,
+                        inline_headers,
+                    )
+                })
+                .collect::<Vec<_>>();
+            bincode::serialize(&world_settings).expect("Failed to serialize settings")
+        };
+
+        // Newer settings are only skipped while they have their default values, in which case the sorted fields serialize the same
+        let current =
+            bincode::serialize(&settings.world_settings).expect("Failed to serialize settings");
+        if serialize(true) != current {
+            return None;
+        }
+
+        let mut hasher = FxHasher::default();
+        serialize(false).hash(&mut hasher);
+        Some(hasher.finish())
+    }
+    fn print_feedback_for_existing_seeds(seeds: &[(usize, PathBuf)]) {
         let modify_timestamps = HandleErrors::new_print_errors(
-            HandleErrors::new_print_errors(seeds.map(|entry| {
-                let path = entry.path();
-                entry
-                    .metadata()
+            HandleErrors::new_print_errors(seeds.iter().map(|(_, path)| {
+                fs::metadata(path)
                     .map_err(|err| {
                         format!(
                             "Failed to read metadata for \"{}\": {}",
@@ -280,3 +472,823 @@ mod fs_access {
         }
     }
 }
+
+#[cfg(feature = "fs")]
+pub use archive_access::*;
+#[cfg(feature = "fs")]
+mod archive_access {
+    use crate::handle_errors::HandleErrors;
+
+    use super::fs_access::{hash_settings, legacy_hash_settings, SEED_STORAGE_FOLDER};
+    use super::stored_spoiler;
+    use super::*;
+
+    use std::{
+        ffi::OsString,
+        fs::{self, File},
+        io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+        path::Path,
+        sync::Mutex,
+        vec,
+    };
+
+    use rustc_hash::{FxHashMap, FxHashSet};
+
+    const SEED_ARCHIVE_FILE: &str = "seed_storage.pack";
+    const MAGIC: &[u8; 8] = b"WOTWSEED";
+    /// settings hash, key and payload length
+    const RECORD_HEADER_LEN: u64 = 24;
+
+    // Appends from multiple threads have to happen one after another
+    // The flag tracks whether records cut off by an interrupted run have already been removed
+    static WRITE_LOCK: Mutex<bool> = Mutex::new(false);
+
+    /// A [`FileAccess`] implementation storing all seeds in a single archive file in the local filesystem
+    ///
+    /// Seeds are appended to the archive, a seed written with the same settings and key as an earlier one replaces it
+    ///
+    /// Seeds stored by [`FileSystemAccess`] can be moved into the archive with [`ArchiveAccess::migrate_folder_storage`]
+    pub struct ArchiveAccess;
+    impl FileAccess for ArchiveAccess {
+        type Iter = ReadArchive;
+
+        fn read_seeds(settings: &UniverseSettings, sample_size: usize) -> Result<Self::Iter> {
+            let (settings_hash, legacy_hash) = settings_hashes(settings);
+            read_archive(
+                Path::new(SEED_ARCHIVE_FILE),
+                settings_hash,
+                legacy_hash,
+                sample_size,
+            )
+        }
+
+        fn write_seed(seed: &SeedSpoiler, settings: &UniverseSettings, key: usize) -> Result<()> {
+            let bytes = stored_spoiler::encode(seed);
+            let mut repaired = WRITE_LOCK.lock().expect("Another thread panicked");
+            let archive = Path::new(SEED_ARCHIVE_FILE);
+            let mut file = open_for_append(archive, &mut repaired)
+                .map_err(|err| format_archive_err(archive, err))?;
+
+            append_record(&mut file, hash_settings(settings), key as u64, &bytes)
+                .map_err(|err| format!("Failed to write seed to archive: {err}"))
+        }
+        fn clean_seeds(settings: &UniverseSettings) -> Result<()> {
+            let (settings_hash, legacy_hash) = settings_hashes(settings);
+            let mut repaired = WRITE_LOCK.lock().expect("Another thread panicked");
+
+            clean_archive(Path::new(SEED_ARCHIVE_FILE), &mut repaired, |hash| {
+                hash == settings_hash || Some(hash) == legacy_hash
+            })
+        }
+        fn clean_all_seeds() -> Result<()> {
+            let mut repaired = WRITE_LOCK.lock().expect("Another thread panicked");
+
+            fs::remove_file(SEED_ARCHIVE_FILE).or_else(|err| match err.kind() {
+                io::ErrorKind::NotFound => Ok(()),
+                _ => Err(format!("Failed to clean seed archive: {err}")),
+            })?;
+            *repaired = true;
+
+            Ok(())
+        }
+    }
+    impl ArchiveAccess {
+        /// Moves all seeds stored by [`FileSystemAccess`] or earlier versions into the archive and removes them from the folder they were stored in
+        ///
+        /// Returns how many seeds were moved. Files that can't be migrated are reported and left in the folder
+        pub fn migrate_folder_storage() -> Result<usize> {
+            let mut repaired = WRITE_LOCK.lock().expect("Another thread panicked");
+
+            migrate(
+                Path::new(SEED_STORAGE_FOLDER),
+                Path::new(SEED_ARCHIVE_FILE),
+                &mut repaired,
+            )
+        }
+    }
+
+    /// The hash to store seeds for these settings under, and the one earlier versions may have stored them under if it's different
+    fn settings_hashes(settings: &UniverseSettings) -> (u64, Option<u64>) {
+        let settings_hash = hash_settings(settings);
+        let legacy_hash =
+            legacy_hash_settings(settings).filter(|legacy_hash| *legacy_hash != settings_hash);
+        (settings_hash, legacy_hash)
+    }
+
+    fn read_archive(
+        archive: &Path,
+        settings_hash: u64,
+        legacy_hash: Option<u64>,
+        sample_size: usize,
+    ) -> Result<ReadArchive> {
+        let mut file = match File::open(archive) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(ReadArchive::default()),
+            Err(err) => return Err(format_archive_err(archive, err)),
+        };
+
+        let (records, _) = scan(&mut file).map_err(|err| format_archive_err(archive, err))?;
+        let mut index = FxHashMap::default();
+        let mut legacy_index = FxHashMap::default();
+        for record in records {
+            if record.settings_hash == settings_hash {
+                if record.key < sample_size as u64 {
+                    index.insert(record.key, record);
+                }
+            } else if Some(record.settings_hash) == legacy_hash {
+                legacy_index.insert(record.key, record);
+            }
+        }
+
+        // Seeds stored by earlier versions weren't generated for a specific sample, so they take the place of samples that are still missing
+        let mut legacy_records = legacy_index.into_values().collect::<Vec<_>>();
+        legacy_records.sort_unstable_by_key(|record| record.key);
+        let mut legacy_records = legacy_records.into_iter();
+        for key in 0..sample_size as u64 {
+            if index.contains_key(&key) {
+                continue;
+            }
+            let Some(mut record) = legacy_records.next() else {
+                break;
+            };
+            record.key = key;
+            index.insert(key, record);
+        }
+
+        let mut records = index.into_values().collect::<Vec<_>>();
+        records.sort_unstable_by_key(|record| record.offset);
+
+        let amount = records.len();
+        if amount > 0 {
+            eprintln!(
+                "Reusing {} seed{} with these settings from the seed archive",
+                amount,
+                if amount == 1 { "" } else { "s" },
+            );
+        }
+
+        Ok(ReadArchive {
+            file: Some(BufReader::new(file)),
+            records: records.into_iter(),
+        })
+    }
+    /// Rewrites the archive without the seeds whose settings hash matches `remove`
+    fn clean_archive<F: Fn(u64) -> bool>(
+        archive: &Path,
+        repaired: &mut bool,
+        remove: F,
+    ) -> Result<()> {
+        let mut file = match File::open(archive) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(format_archive_err(archive, err)),
+        };
+        let (records, _) = scan(&mut file).map_err(|err| format_archive_err(archive, err))?;
+        if !records.iter().any(|record| remove(record.settings_hash)) {
+            return Ok(());
+        }
+
+        let mut temp_path = OsString::from(archive);
+        temp_path.push(".tmp");
+        let rewrite = || -> io::Result<()> {
+            let mut reader = BufReader::new(file);
+            let mut writer = BufWriter::new(File::create(&temp_path)?);
+            writer.write_all(MAGIC)?;
+
+            for record in records
+                .iter()
+                .filter(|record| !remove(record.settings_hash))
+            {
+                let bytes = record.read(&mut reader)?;
+                append_record(&mut writer, record.settings_hash, record.key, &bytes)?;
+            }
+
+            writer.flush()?;
+            fs::rename(&temp_path, archive)
+        };
+        rewrite().map_err(|err| format!("Failed to clean seed archive: {err}"))?;
+        *repaired = true;
+
+        Ok(())
+    }
+    fn migrate(folder: &Path, archive: &Path, repaired: &mut bool) -> Result<usize> {
+        let folders = match fs::read_dir(folder) {
+            Ok(folders) => folders,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(0),
+            Err(err) => return Err(format!("Failed to access seed storage: {err}")),
+        };
+
+        let mut file =
+            open_for_append(archive, repaired).map_err(|err| format_archive_err(archive, err))?;
+        let (records, _) = scan(&mut file).map_err(|err| format_archive_err(archive, err))?;
+        file.seek(SeekFrom::End(0))
+            .map_err(|err| format_archive_err(archive, err))?;
+        let mut used_keys = FxHashMap::<u64, FxHashSet<u64>>::default();
+        for record in records {
+            used_keys
+                .entry(record.settings_hash)
+                .or_default()
+                .insert(record.key);
+        }
+
+        let mut archive = BufWriter::new(file);
+        let mut migrated = 0;
+        let mut skipped = 0;
+        let mut skip = |path: &Path, reason: String| {
+            eprintln!(
+                "Skipped \"{}\" while migrating seed storage: {}",
+                path.display(),
+                reason
+            );
+            skipped += 1;
+        };
+
+        for folder in HandleErrors::new_print_errors(folders) {
+            let path = folder.path();
+            // Folders are named after the settings hash and files after the key, both of which the archive uses as well
+            let Some(settings_hash) = folder
+                .file_name()
+                .to_str()
+                .and_then(|name| u64::from_str_radix(name, 16).ok())
+            else {
+                skip(&path, "Not named after a settings hash".to_string());
+                continue;
+            };
+            let entries = match fs::read_dir(&path) {
+                Ok(entries) => entries,
+                Err(err) => {
+                    skip(&path, err.to_string());
+                    continue;
+                }
+            };
+
+            let used_keys = used_keys.entry(settings_hash).or_default();
+            let mut legacy_seeds = vec![];
+            let mut moved = vec![];
+
+            for entry in HandleErrors::new_print_errors(entries) {
+                let path = entry.path();
+                let Some(key) = entry
+                    .file_name()
+                    .to_str()
+                    .and_then(|name| name.parse::<u64>().ok())
+                else {
+                    skip(&path, "Not named after a key".to_string());
+                    continue;
+                };
+                let bytes = match fs::read(&path) {
+                    Ok(bytes) => bytes,
+                    Err(err) => {
+                        skip(&path, err.to_string());
+                        continue;
+                    }
+                };
+
+                match stored_spoiler::decode(&bytes) {
+                    Ok(_) => {
+                        append_record(&mut archive, settings_hash, key, &bytes)
+                            .map_err(|err| format!("Failed to write seed to archive: {err}"))?;
+                        used_keys.insert(key);
+                        moved.push(path);
+                    }
+                    Err(err) => match stored_spoiler::decode_legacy(&bytes) {
+                        Ok(seed) => legacy_seeds.push((key, stored_spoiler::encode(&seed), path)),
+                        Err(_) => skip(&path, err.to_string()),
+                    },
+                }
+            }
+
+            // Earlier versions counted keys from 1 and skipped taken ones, so the seeds are given the keys that are still free
+            legacy_seeds.sort_unstable_by_key(|(key, _, _)| *key);
+            let mut key = 0;
+            for (_, bytes, path) in legacy_seeds {
+                while used_keys.contains(&key) {
+                    key += 1;
+                }
+                append_record(&mut archive, settings_hash, key, &bytes)
+                    .map_err(|err| format!("Failed to write seed to archive: {err}"))?;
+                used_keys.insert(key);
+                moved.push(path);
+            }
+
+            archive
+                .flush()
+                .map_err(|err| format!("Failed to write seed to archive: {err}"))?;
+            migrated += moved.len();
+            for path in moved {
+                if let Err(err) = fs::remove_file(&path) {
+                    eprintln!(
+                        "Failed to remove \"{}\" from seed storage: {}",
+                        path.display(),
+                        err
+                    );
+                }
+            }
+            // Folders with files that were kept stay in place
+            let _ = fs::remove_dir(&path);
+        }
+
+        if skipped > 0 {
+            eprintln!(
+                "Kept {} file{} in \"{}\" that couldn't be migrated",
+                skipped,
+                if skipped == 1 { "" } else { "s" },
+                folder.display()
+            );
+        } else {
+            let _ = fs::remove_dir(folder);
+        }
+
+        Ok(migrated)
+    }
+
+    /// An Iterator reading stored seeds from the archive
+    #[derive(Default)]
+    pub struct ReadArchive {
+        file: Option<BufReader<File>>,
+        records: vec::IntoIter<Record>,
+    }
+    impl Iterator for ReadArchive {
+        type Item = Result<(usize, SeedSpoiler)>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            let record = self.records.next()?;
+            let file = self.file.as_mut()?;
+
+            let seed = record
+                .read(file)
+                .map_err(|err| format!("Failed to read seed from seed archive: {err}"))
+                .and_then(|bytes| {
+                    stored_spoiler::decode(&bytes).map_err(|err| {
+                        format!("Failed to deserialize seed from seed archive: {err}")
+                    })
+                })
+                .map(|seed| (record.key as usize, seed));
+
+            Some(seed)
+        }
+    }
+
+    /// The position of one seed in the archive
+    struct Record {
+        settings_hash: u64,
+        key: u64,
+        offset: u64,
+        len: u64,
+    }
+    impl Record {
+        fn read<R: Read + Seek>(&self, reader: &mut R) -> io::Result<Vec<u8>> {
+            reader.seek(SeekFrom::Start(self.offset))?;
+            let mut bytes = vec![0; self.len as usize];
+            reader.read_exact(&mut bytes)?;
+            Ok(bytes)
+        }
+    }
+
+    /// Reads the record headers of the archive
+    ///
+    /// Also returns the length of the archive up to the last complete record, anything after that was cut off while writing
+    fn scan(file: &mut File) -> io::Result<(Vec<Record>, u64)> {
+        let file_len = file.metadata()?.len();
+        if file_len < MAGIC.len() as u64 {
+            return Ok((vec![], 0));
+        }
+
+        let mut reader = BufReader::new(file);
+        reader.seek(SeekFrom::Start(0))?;
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "This is not a seed archive",
+            ));
+        }
+
+        let mut records = vec![];
+        let mut position = MAGIC.len() as u64;
+        while position + RECORD_HEADER_LEN <= file_len {
+            let mut header = [0; RECORD_HEADER_LEN as usize];
+            reader.read_exact(&mut header)?;
+            let [settings_hash, key, len] = [0, 8, 16]
+                .map(|start| u64::from_le_bytes(header[start..start + 8].try_into().unwrap()));
+
+            let offset = position + RECORD_HEADER_LEN;
+            if offset + len > file_len {
+                break;
+            }
+            reader.seek_relative(len as i64)?;
+
+            records.push(Record {
+                settings_hash,
+                key,
+                offset,
+                len,
+            });
+            position = offset + len;
+        }
+
+        Ok((records, position))
+    }
+    /// Opens the archive to append records, creating it if it doesn't exist yet
+    fn open_for_append(archive: &Path, repaired: &mut bool) -> io::Result<File> {
+        let mut file = fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(archive)?;
+
+        if !*repaired {
+            let (_, valid_len) = scan(&mut file)?;
+            file.set_len(valid_len)?;
+            *repaired = true;
+        }
+        if file.seek(SeekFrom::End(0))? == 0 {
+            file.write_all(MAGIC)?;
+        }
+
+        Ok(file)
+    }
+    fn append_record<W: Write>(
+        writer: &mut W,
+        settings_hash: u64,
+        key: u64,
+        bytes: &[u8],
+    ) -> io::Result<()> {
+        // Written in one go so an interruption can only cut off the end of the archive
+        let mut record = Vec::with_capacity(RECORD_HEADER_LEN as usize + bytes.len());
+        record.extend_from_slice(&settings_hash.to_le_bytes());
+        record.extend_from_slice(&key.to_le_bytes());
+        record.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+        record.extend_from_slice(bytes);
+        writer.write_all(&record)
+    }
+    fn format_archive_err(archive: &Path, err: io::Error) -> String {
+        format!(
+            "Failed to access seed archive at \"{}\": {}",
+            archive.display(),
+            err
+        )
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        use std::path::PathBuf;
+
+        use wotw_seedgen::{
+            generator::{NodeSummary, SpoilerGroup, SpoilerPlacement},
+            settings::{MultiworldSharing, ScoreRange, Trick},
+            util::Zone,
+            Inventory, Item,
+        };
+
+        fn temp_dir(name: &str) -> PathBuf {
+            let path = std::env::temp_dir().join(format!(
+                "wotw_seedgen_stats_{}_{}",
+                name,
+                std::process::id()
+            ));
+            let _ = fs::remove_dir_all(&path);
+            fs::create_dir_all(&path).unwrap();
+            path
+        }
+        fn seed(item_name: &str) -> SeedSpoiler {
+            SeedSpoiler {
+                spawns: vec!["MarshSpawn.Main".to_string()],
+                groups: vec![SpoilerGroup {
+                    reachable: vec![],
+                    forced_items: Inventory::default(),
+                    placements: vec![SpoilerPlacement {
+                        origin_world_index: 0,
+                        target_world_index: 0,
+                        shared: false,
+                        location: NodeSummary {
+                            identifier: "MarshSpawn.RockHC".to_string(),
+                            position: None,
+                            zone: Some(Zone::Marsh),
+                        },
+                        item: Item::SpiritLight(100),
+                        item_name: item_name.to_string(),
+                    }],
+                }],
+                doors: vec![],
+                sharing: MultiworldSharing::default(),
+            }
+        }
+        // The spoiler as it was serialized before seed storage had its own format
+        fn legacy_bytes(seed: &SeedSpoiler) -> Vec<u8> {
+            let groups = seed
+                .groups
+                .iter()
+                .map(|group| {
+                    let placements = group
+                        .placements
+                        .iter()
+                        .map(|placement| {
+                            (
+                                placement.origin_world_index,
+                                placement.target_world_index,
+                                &placement.location,
+                                &placement.item,
+                                &placement.item_name,
+                            )
+                        })
+                        .collect::<Vec<_>>();
+                    (&group.reachable, &group.forced_items, placements)
+                })
+                .collect::<Vec<_>>();
+            bincode::serialize(&(&seed.spawns, groups)).unwrap()
+        }
+        fn write(
+            archive: &Path,
+            repaired: &mut bool,
+            settings_hash: u64,
+            key: u64,
+            item_name: &str,
+        ) {
+            let mut file = open_for_append(archive, repaired).unwrap();
+            append_record(
+                &mut file,
+                settings_hash,
+                key,
+                &stored_spoiler::encode(&seed(item_name)),
+            )
+            .unwrap();
+        }
+        fn read(
+            archive: &Path,
+            settings_hash: u64,
+            legacy_hash: Option<u64>,
+            sample_size: usize,
+        ) -> Vec<(usize, String)> {
+            let mut seeds = read_archive(archive, settings_hash, legacy_hash, sample_size)
+                .unwrap()
+                .map(|seed| {
+                    let (key, seed) = seed.unwrap();
+                    (key, seed.groups[0].placements[0].item_name.clone())
+                })
+                .collect::<Vec<_>>();
+            seeds.sort();
+            seeds
+        }
+        fn keys(archive: &Path) -> Vec<(u64, u64)> {
+            let (records, _) = scan(&mut File::open(archive).unwrap()).unwrap();
+            records
+                .iter()
+                .map(|record| (record.settings_hash, record.key))
+                .collect()
+        }
+
+        #[test]
+        fn scan_records() {
+            let dir = temp_dir("scan");
+            let archive = dir.join(SEED_ARCHIVE_FILE);
+
+            let mut repaired = false;
+            write(&archive, &mut repaired, 1, 0, "a");
+            write(&archive, &mut repaired, 2, 5, "b");
+
+            let mut file = File::open(&archive).unwrap();
+            let (records, valid_len) = scan(&mut file).unwrap();
+            assert_eq!(valid_len, file.metadata().unwrap().len());
+            assert_eq!(records.len(), 2);
+            assert_eq!((records[1].settings_hash, records[1].key), (2, 5));
+            let bytes = records[1].read(&mut file).unwrap();
+            assert_eq!(bytes, stored_spoiler::encode(&seed("b")));
+
+            fs::write(&archive, b"NOTSEEDS").unwrap();
+            assert!(scan(&mut File::open(&archive).unwrap()).is_err());
+
+            fs::remove_dir_all(dir).unwrap();
+        }
+
+        #[test]
+        fn repair_truncated_records() {
+            let dir = temp_dir("repair");
+            let archive = dir.join(SEED_ARCHIVE_FILE);
+
+            let mut repaired = false;
+            write(&archive, &mut repaired, 1, 0, "a");
+            write(&archive, &mut repaired, 1, 1, "b");
+            let valid_len = fs::metadata(&archive).unwrap().len();
+
+            // Simulate a run that was interrupted while writing a record
+            let mut file = fs::OpenOptions::new().append(true).open(&archive).unwrap();
+            let mut record = vec![];
+            append_record(&mut record, 1, 2, &stored_spoiler::encode(&seed("c"))).unwrap();
+            file.write_all(&record[..record.len() / 2]).unwrap();
+            drop(file);
+
+            let (records, len) = scan(&mut File::open(&archive).unwrap()).unwrap();
+            assert_eq!(records.len(), 2);
+            assert_eq!(len, valid_len);
+
+            let mut repaired = false;
+            write(&archive, &mut repaired, 1, 3, "d");
+            assert_eq!(keys(&archive), [(1, 0), (1, 1), (1, 3)]);
+            assert_eq!(
+                read(&archive, 1, None, 10),
+                [
+                    (0, "a".to_string()),
+                    (1, "b".to_string()),
+                    (3, "d".to_string())
+                ]
+            );
+
+            fs::remove_dir_all(dir).unwrap();
+        }
+
+        #[test]
+        fn replace_by_key() {
+            let dir = temp_dir("replace");
+            let archive = dir.join(SEED_ARCHIVE_FILE);
+
+            let mut repaired = false;
+            write(&archive, &mut repaired, 1, 0, "a");
+            write(&archive, &mut repaired, 1, 1, "b");
+            write(&archive, &mut repaired, 2, 0, "other settings");
+            write(&archive, &mut repaired, 1, 0, "c");
+            write(&archive, &mut repaired, 1, 7, "outside sample");
+
+            assert_eq!(
+                read(&archive, 1, None, 5),
+                [(0, "c".to_string()), (1, "b".to_string())]
+            );
+            assert_eq!(read(&archive, 3, None, 5), []);
+            assert_eq!(read(&dir.join("missing.pack"), 1, None, 5), []);
+
+            fs::remove_dir_all(dir).unwrap();
+        }
+
+        #[test]
+        fn legacy_hash() {
+            let dir = temp_dir("legacy_hash");
+            let archive = dir.join(SEED_ARCHIVE_FILE);
+
+            let mut repaired = false;
+            write(&archive, &mut repaired, 1, 1, "a");
+            write(&archive, &mut repaired, 2, 4, "legacy b");
+            write(&archive, &mut repaired, 2, 2, "legacy a");
+
+            // Seeds stored under the legacy hash fill the keys that are still missing
+            assert_eq!(
+                read(&archive, 1, Some(2), 5),
+                [
+                    (0, "legacy a".to_string()),
+                    (1, "a".to_string()),
+                    (2, "legacy b".to_string())
+                ]
+            );
+            assert_eq!(
+                read(&archive, 1, Some(2), 2),
+                [(0, "legacy a".to_string()), (1, "a".to_string())]
+            );
+
+            let mut settings = UniverseSettings::default();
+            assert_eq!(
+                legacy_hash_settings(&settings),
+                Some(hash_settings(&settings))
+            );
+            settings.world_settings[0].tricks.extend([
+                Trick::SwordSentryJump,
+                Trick::HammerSentryJump,
+                Trick::ShurikenBreak,
+            ]);
+            assert!(legacy_hash_settings(&settings).is_some());
+            settings.multiworld_sharing = MultiworldSharing::LocalSkills;
+            assert_eq!(legacy_hash_settings(&settings), None);
+            settings.multiworld_sharing = MultiworldSharing::default();
+            settings.world_settings[0].placement_weights.skill_depth += 1.;
+            assert_eq!(legacy_hash_settings(&settings), None);
+
+            let mut settings = UniverseSettings::default();
+            let default_hash = hash_settings(&settings);
+            settings.score_range = Some(ScoreRange {
+                min: Some(10),
+                max: None,
+            });
+            assert_ne!(hash_settings(&settings), default_hash);
+            assert_eq!(legacy_hash_settings(&settings), None);
+
+            fs::remove_dir_all(dir).unwrap();
+        }
+
+        #[test]
+        fn clean_rewrite() {
+            let dir = temp_dir("clean");
+            let archive = dir.join(SEED_ARCHIVE_FILE);
+
+            let mut repaired = false;
+            write(&archive, &mut repaired, 1, 0, "a");
+            write(&archive, &mut repaired, 2, 0, "b");
+            write(&archive, &mut repaired, 3, 0, "c");
+            write(&archive, &mut repaired, 2, 1, "d");
+
+            clean_archive(&archive, &mut repaired, |hash| hash == 2).unwrap();
+            assert_eq!(keys(&archive), [(1, 0), (3, 0)]);
+            assert_eq!(read(&archive, 1, None, 5), [(0, "a".to_string())]);
+            assert_eq!(read(&archive, 3, None, 5), [(0, "c".to_string())]);
+            assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+            // Nothing to remove leaves the archive untouched
+            let len = fs::metadata(&archive).unwrap().len();
+            clean_archive(&archive, &mut repaired, |hash| hash == 2).unwrap();
+            assert_eq!(fs::metadata(&archive).unwrap().len(), len);
+
+            fs::remove_dir_all(dir).unwrap();
+        }
+
+        #[test]
+        fn legacy_folder_seeds() {
+            let dir = temp_dir("legacy_folder");
+            fs::write(dir.join("0"), legacy_bytes(&seed("legacy 0"))).unwrap();
+            fs::write(dir.join("1"), stored_spoiler::encode(&seed("current 1"))).unwrap();
+            fs::write(dir.join("2"), b"garbage").unwrap();
+
+            let mut seeds = ReadSeeds::new(dir.clone(), 5)
+                .unwrap()
+                .filter_map(Result::ok)
+                .map(|(key, seed)| (key, seed.groups[0].placements[0].item_name.clone()))
+                .collect::<Vec<_>>();
+            seeds.sort();
+            assert_eq!(
+                seeds,
+                [(0, "legacy 0".to_string()), (1, "current 1".to_string())]
+            );
+
+            // Only the file neither format can read is removed
+            assert!(dir.join("0").exists());
+            assert!(dir.join("1").exists());
+            assert!(!dir.join("2").exists());
+
+            fs::remove_dir_all(dir).unwrap();
+        }
+
+        #[test]
+        fn migration() {
+            let dir = temp_dir("migration");
+            let archive = dir.join(SEED_ARCHIVE_FILE);
+            let folder = dir.join(SEED_STORAGE_FOLDER);
+
+            let mut repaired = false;
+            write(&archive, &mut repaired, 0xa, 0, "archived");
+
+            let legacy_folder = folder.join("a");
+            fs::create_dir_all(&legacy_folder).unwrap();
+            // Earlier versions counted keys from 1
+            fs::write(legacy_folder.join("1"), legacy_bytes(&seed("legacy 1"))).unwrap();
+            fs::write(legacy_folder.join("2"), legacy_bytes(&seed("legacy 2"))).unwrap();
+            fs::write(
+                legacy_folder.join("5"),
+                stored_spoiler::encode(&seed("current 5")),
+            )
+            .unwrap();
+            fs::write(legacy_folder.join("3"), b"garbage").unwrap();
+            fs::write(legacy_folder.join("notes.txt"), b"").unwrap();
+
+            let current_folder = folder.join("b");
+            fs::create_dir_all(&current_folder).unwrap();
+            fs::write(
+                current_folder.join("0"),
+                stored_spoiler::encode(&seed("current 0")),
+            )
+            .unwrap();
+            fs::create_dir_all(folder.join("not a hash")).unwrap();
+
+            let migrated = migrate(&folder, &archive, &mut repaired).unwrap();
+            assert_eq!(migrated, 4);
+            assert_eq!(
+                read(&archive, 0xa, None, 10),
+                [
+                    (0, "archived".to_string()),
+                    (1, "legacy 1".to_string()),
+                    (2, "legacy 2".to_string()),
+                    (5, "current 5".to_string())
+                ]
+            );
+            assert_eq!(
+                read(&archive, 0xb, None, 10),
+                [(0, "current 0".to_string())]
+            );
+
+            // Files that couldn't be migrated are kept
+            assert!(legacy_folder.join("3").exists());
+            assert!(legacy_folder.join("notes.txt").exists());
+            assert!(!legacy_folder.join("1").exists());
+            assert!(!current_folder.exists());
+            assert!(folder.join("not a hash").exists());
+
+            fs::remove_file(legacy_folder.join("3")).unwrap();
+            fs::remove_file(legacy_folder.join("notes.txt")).unwrap();
+            fs::remove_dir(folder.join("not a hash")).unwrap();
+            assert_eq!(migrate(&folder, &archive, &mut repaired).unwrap(), 0);
+            assert!(!folder.exists());
+            assert_eq!(migrate(&folder, &archive, &mut repaired).unwrap(), 0);
+
+            fs::remove_dir_all(dir).unwrap();
+        }
+    }
+}
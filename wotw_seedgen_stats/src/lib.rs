@@ -1,14 +1,19 @@
 pub mod analyzers;
+pub mod comparison;
 pub mod files;
 mod handle_errors;
 mod seed_storage;
+pub mod summary;
 
-use std::{cmp::Ordering, fmt::Write, sync::Arc, time::Instant};
+use std::{cmp::Ordering, fmt::Write, rc::Rc, str::FromStr, sync::Arc, time::Instant};
 
 use analyzers::Analyzer;
-use files::FileAccess;
+use comparison::Comparison;
+use files::{FileAccess, NoFileAccess};
 use itertools::Itertools;
 use rustc_hash::FxHashMap;
+use seed_storage::SeedData;
+use summary::StatsSummary;
 use wotw_seedgen::{settings::UniverseSettings, world::Graph};
 
 type Result<T> = std::result::Result<T, String>;
@@ -42,39 +47,73 @@ pub struct StatsArgs<'graph> {
     pub error_message_limit: Option<usize>,
     /// If `true`, cleans the seed storage for the provided `settings` and generates new seeds from scratch
     pub overwrite_seed_storage: bool,
+    /// Which worlds of multiworld seeds should be analyzed and whether to tell them apart
+    ///
+    /// For single world seeds, all variants except [`WorldScope::PerWorld`] behave the same
+    pub world_scope: WorldScope,
 }
 /// Multiple [`Analyzer`]s chained together
 pub type ChainedAnalyzers = Vec<Box<dyn Analyzer>>;
 
+/// Which worlds of a seed get passed to the [`Analyzer`]s
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum WorldScope {
+    /// Analyze every world and count the results of all worlds together
+    #[default]
+    Aggregated,
+    /// Analyze every world and keep the results separate by adding the world index as the first key
+    PerWorld,
+    /// Only analyze the world with this index
+    World(usize),
+}
+impl WorldScope {
+    fn world_indices(self, world_count: usize) -> std::ops::Range<usize> {
+        match self {
+            WorldScope::Aggregated | WorldScope::PerWorld => 0..world_count,
+            WorldScope::World(index) => index..index + 1,
+        }
+    }
+}
+impl FromStr for WorldScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "aggregated" => Ok(WorldScope::Aggregated),
+            "per-world" => Ok(WorldScope::PerWorld),
+            _ => s.parse().map(WorldScope::World).map_err(|_| {
+                format!("Invalid world scope \"{s}\", expected \"aggregated\", \"per-world\" or a world index")
+            }),
+        }
+    }
+}
+
 pub struct Stats {
-    analyzers: ChainedAnalyzers,
+    analyzers: Rc<ChainedAnalyzers>,
+    world_scope: WorldScope,
+    samples: usize,
     pub data: FxHashMap<Vec<Arc<String>>, u32>,
 }
 impl Stats {
     pub fn title(&self) -> String {
-        self.analyzers
+        let title = self
+            .analyzers
             .iter()
             .map(|analyzer| analyzer.title())
-            .join(" and ")
+            .join(" and ");
+
+        match self.world_scope {
+            WorldScope::Aggregated => title,
+            WorldScope::PerWorld => format!("{title} per World"),
+            WorldScope::World(index) => format!("{title} in World {index}"),
+        }
     }
     pub fn csv(&self) -> String {
         let mut csv = self.title();
         csv.push_str(", Count\n");
 
-        let mut data = self.data.iter().collect::<Vec<_>>();
-        data.sort_unstable_by(|(a, _), (b, _)| {
-            for ((x, y), analyzer) in a.iter().zip(b.iter()).zip(self.analyzers.iter()) {
-                match analyzer.compare_keys()(x, y) {
-                    Ordering::Equal => (),
-                    non_eq => return non_eq,
-                }
-            }
-
-            Ordering::Equal
-        });
-
         csv.extend(Itertools::intersperse_with(
-            data.into_iter().map(|(keys, value)| {
+            self.sorted_data().into_iter().map(|(keys, value)| {
                 let mut data_line = keys.iter().join(", ");
                 write!(data_line, ", {value}").unwrap();
                 data_line
@@ -84,6 +123,40 @@ impl Stats {
 
         csv
     }
+    /// Summarize the data with percentages, confidence intervals and distributions of numeric results
+    ///
+    /// See [`StatsSummary`] for details
+    pub fn summary(&self) -> StatsSummary {
+        summary::summarize(self.title(), self.samples, &self.sorted_data())
+    }
+
+    fn sorted_data(&self) -> Vec<(&Vec<Arc<String>>, &u32)> {
+        let mut data = self.data.iter().collect::<Vec<_>>();
+        data.sort_unstable_by(|(a, _), (b, _)| self.compare_keys(a, b));
+        data
+    }
+    fn compare_keys(&self, mut a: &[Arc<String>], mut b: &[Arc<String>]) -> Ordering {
+        if self.world_scope == WorldScope::PerWorld {
+            // The world index is always a number we put in ourselves
+            let world_a = a[0].parse::<usize>().unwrap();
+            let world_b = b[0].parse::<usize>().unwrap();
+            match world_a.cmp(&world_b) {
+                Ordering::Equal => (),
+                non_eq => return non_eq,
+            }
+            a = &a[1..];
+            b = &b[1..];
+        }
+
+        for ((x, y), analyzer) in a.iter().zip(b.iter()).zip(self.analyzers.iter()) {
+            match analyzer.compare_keys()(x, y) {
+                Ordering::Equal => (),
+                non_eq => return non_eq,
+            }
+        }
+
+        Ordering::Equal
+    }
 }
 
 /// Generates a set of stats
@@ -100,34 +173,175 @@ pub fn stats<F: FileAccess>(args: StatsArgs) -> Result<Vec<Stats>> {
         tolerated_errors,
         error_message_limit,
         overwrite_seed_storage,
+        world_scope,
     } = args;
+    let options = SampleOptions {
+        sample_size,
+        tolerated_errors,
+        error_message_limit,
+        overwrite_seed_storage,
+        world_scope,
+    };
+
+    let (data, samples) = analyze_variant::<F>(&settings, graph, &analyzers, &options)?;
+
+    let stats = data
+        .into_iter()
+        .zip(analyzers)
+        .map(|(data, analyzers)| Stats {
+            analyzers: Rc::new(analyzers),
+            world_scope,
+            samples,
+            data,
+        })
+        .collect();
+
+    let elapsed = now.elapsed();
+    eprintln!("Generated stats in {:.1}s", elapsed.as_secs_f32());
+
+    Ok(stats)
+}
+
+/// One side of a [`compare`] call
+pub struct Variant<'graph> {
+    /// The [`UniverseSettings`] to generate seeds with
+    pub settings: UniverseSettings,
+    /// The logical [`Graph`], which has to be parsed with these `settings`
+    pub graph: &'graph Graph,
+}
+/// Arguments passed to [`compare`]
+///
+/// The remaining arguments behave the same as in [`StatsArgs`] and apply to both [`Variant`]s
+pub struct CompareArgs<'graph> {
+    /// The [`Variant`] to compare against
+    pub baseline: Variant<'graph>,
+    /// The [`Variant`] whose differences to the `baseline` should be reported, e.g. using a modified logic or additional headers
+    ///
+    /// Unless it shares the `baseline`'s `graph`, seeds for this variant are never read from or written to the seed storage
+    pub changed: Variant<'graph>,
+    pub sample_size: usize,
+    pub analyzers: Vec<ChainedAnalyzers>,
+    pub tolerated_errors: Option<usize>,
+    pub error_message_limit: Option<usize>,
+    pub overwrite_seed_storage: bool,
+    pub world_scope: WorldScope,
+}
+
+/// Generates the same set of stats for two [`Variant`]s and compares them
+///
+/// See [`CompareArgs`] for more details on the passed arguments and [`Comparison`] for the results
+pub fn compare<F: FileAccess>(args: CompareArgs) -> Result<Vec<Comparison>> {
+    let now = Instant::now();
+
+    let CompareArgs {
+        baseline,
+        changed,
+        sample_size,
+        analyzers,
+        tolerated_errors,
+        error_message_limit,
+        overwrite_seed_storage,
+        world_scope,
+    } = args;
+    let options = SampleOptions {
+        sample_size,
+        tolerated_errors,
+        error_message_limit,
+        overwrite_seed_storage,
+        world_scope,
+    };
+
+    eprintln!("Generating stats for the baseline");
+    let (baseline_data, baseline_samples) =
+        analyze_variant::<F>(&baseline.settings, baseline.graph, &analyzers, &options)?;
+    eprintln!("Generating stats for the changes");
+    let (changed_data, changed_samples) =
+        if baseline.settings == changed.settings || !std::ptr::eq(baseline.graph, changed.graph) {
+            // The seed storage only tells settings apart, so it would mix in seeds generated with a different logic
+            analyze_variant::<NoFileAccess>(&changed.settings, changed.graph, &analyzers, &options)?
+        } else {
+            analyze_variant::<F>(&changed.settings, changed.graph, &analyzers, &options)?
+        };
+
+    let comparisons = baseline_data
+        .into_iter()
+        .zip(changed_data)
+        .zip(analyzers)
+        .map(|((baseline_data, changed_data), analyzers)| {
+            let analyzers = Rc::new(analyzers);
+            Comparison {
+                baseline: Stats {
+                    analyzers: Rc::clone(&analyzers),
+                    world_scope,
+                    samples: baseline_samples,
+                    data: baseline_data,
+                },
+                changed: Stats {
+                    analyzers,
+                    world_scope,
+                    samples: changed_samples,
+                    data: changed_data,
+                },
+            }
+        })
+        .collect();
+
+    let elapsed = now.elapsed();
+    eprintln!("Generated comparison in {:.1}s", elapsed.as_secs_f32());
+
+    Ok(comparisons)
+}
+
+struct SampleOptions {
+    sample_size: usize,
+    tolerated_errors: Option<usize>,
+    error_message_limit: Option<usize>,
+    overwrite_seed_storage: bool,
+    world_scope: WorldScope,
+}
+/// Returns the data for each [`ChainedAnalyzers`] and how many samples the data is relative to
+fn analyze_variant<F: FileAccess>(
+    settings: &UniverseSettings,
+    graph: &Graph,
+    analyzers: &[ChainedAnalyzers],
+    options: &SampleOptions,
+) -> Result<(Vec<SeedData>, usize)> {
+    let SampleOptions {
+        sample_size,
+        tolerated_errors,
+        error_message_limit,
+        overwrite_seed_storage,
+        world_scope,
+    } = *options;
 
     if overwrite_seed_storage {
-        F::clean_seeds(&settings)?;
+        F::clean_seeds(settings)?;
         eprintln!("Cleaned seed storage for these settings");
     }
 
-    if settings.world_count() > 1 {
-        return Err("Multiworld seeds aren't well supported yet".to_string());
+    let world_count = settings.world_count();
+    if let WorldScope::World(index) = world_scope {
+        if index >= world_count {
+            return Err(format!(
+                "Cannot analyze world {index} since there are only {world_count} worlds"
+            ));
+        }
     }
 
     let data = seed_storage::analyze::<F>(
-        &analyzers,
-        &settings,
+        analyzers,
+        settings,
         sample_size,
+        world_scope,
         tolerated_errors,
         error_message_limit,
         graph,
     )?;
 
-    let stats = data
-        .into_iter()
-        .zip(analyzers)
-        .map(|(data, analyzers)| Stats { analyzers, data })
-        .collect();
+    let samples = match world_scope {
+        WorldScope::Aggregated => sample_size * world_count,
+        WorldScope::PerWorld | WorldScope::World(_) => sample_size,
+    };
 
-    let elapsed = now.elapsed();
-    eprintln!("Generated stats in {:.1}s", elapsed.as_secs_f32());
-
-    Ok(stats)
+    Ok((data, samples))
 }
@@ -14,9 +14,9 @@ use wotw_seedgen::{
 };
 
 use itertools::Itertools;
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 
-use crate::{files::FileAccess, handle_errors::HandleErrors, ChainedAnalyzers, Result};
+use crate::{files::FileAccess, handle_errors::HandleErrors, ChainedAnalyzers, Result, WorldScope};
 
 const DEFAULT_ERROR_MESSAGE_LIMIT: usize = 10;
 const ANOTHER_THREAD_PANICKED: &str = "Another thread panicked";
@@ -26,136 +26,188 @@ pub(crate) fn analyze<F: FileAccess>(
     analyzers: &[ChainedAnalyzers],
     settings: &UniverseSettings,
     sample_size: usize,
+    world_scope: WorldScope,
     tolerated_errors: Option<usize>,
     error_message_limit: Option<usize>,
     graph: &Graph,
 ) -> Result<Vec<SeedData>> {
-    let mut data = iter::repeat(FxHashMap::<_, u32>::default())
-        .take(analyzers.len())
+    let mut data = empty_data(analyzers);
+
+    let existing =
+        analyze_existing_seeds::<F>(analyzers, settings, sample_size, world_scope, &mut data)?;
+
+    // Previous runs may have been interrupted at any point, so we fill in whichever samples are missing
+    let missing = (0..sample_size)
+        .filter(|key| !existing.contains(key))
         .collect::<Vec<_>>();
+    if missing.is_empty() {
+        return Ok(data);
+    }
 
-    let existing_amount = analyze_existing_seeds::<F>(analyzers, settings, sample_size, &mut data)?;
-
-    let missing = sample_size.saturating_sub(existing_amount);
-    if missing > 0 {
-        let available = thread::available_parallelism().map_or(4, NonZeroUsize::get);
-        let mut remainder = iter::repeat(1).take(missing % available);
-        let sample_size_per_thread = iter::repeat(missing / available)
-            .map(|sample_size| sample_size + remainder.next().unwrap_or(0))
-            .take(available);
-
-        let data = Mutex::new(data);
-        let count = AtomicUsize::new(0);
-        let tolerated_errors =
-            tolerated_errors.unwrap_or_else(|| usize::max(missing.saturating_mul(4), 100));
-        let errors = AtomicUsize::new(0);
-        let error_message_limit = error_message_limit.unwrap_or(DEFAULT_ERROR_MESSAGE_LIMIT);
-        let error_messages = Mutex::new(vec![]);
-        let write_errors = AtomicUsize::new(0);
-
-        std::thread::scope(|scope| {
-            let data = &data;
-            let count = &count;
-            let errors = &errors;
-            let error_messages = &error_messages;
-            let write_errors = &write_errors;
-
-            // we collect to spawn all the threads
-            #[allow(clippy::needless_collect)]
-            let handles = sample_size_per_thread.map(|thread_sample_size| {
-                thread::Builder::new().name("seedgen".to_string()).spawn_scoped(scope, move || {
-                    let mut settings = settings.clone();
-
-                    for _ in 0..thread_sample_size {
-                        let count = count.fetch_add(1, Ordering::Relaxed) + 1;
-                        eprint!("Generating seed {}/{}\r", count, missing);
-
-                        settings.seed = rand::random::<u64>().to_string();
-
-                        let seed = loop {
-                            match wotw_seedgen::generate_seed(
-                                graph,
-                                &FILE_SYSTEM_ACCESS,
-                                &settings,
-                            ) {
-                                Ok(seed) => break seed.spoiler,
-                                Err(err) => {
-                                    let mut error_messages_lock = error_messages.lock().expect(ANOTHER_THREAD_PANICKED);
-
-                                    if error_messages_lock.len() < error_message_limit {
-                                        error_messages_lock.push(err);
-                                    }
-                                    let errors = errors.fetch_add(1, Ordering::Relaxed) + 1;
-                                    if errors > tolerated_errors {
-                                        let more = errors - error_messages_lock.len();
-                                        let mut error_message = format!(
-                                            "Too many errors while generating seeds\nSample of some errors:\n{}",
-                                            error_messages_lock.join("\n")
-                                        );
-                                        if more > 0 {
-                                            write!(error_message, "\n...{more} more").unwrap();
-                                        }
-                                        return Err(error_message);
+    let available = thread::available_parallelism().map_or(4, NonZeroUsize::get);
+    let thread_count = available.min(missing.len());
+
+    let next = AtomicUsize::new(0);
+    let tolerated_errors =
+        tolerated_errors.unwrap_or_else(|| usize::max(missing.len().saturating_mul(4), 100));
+    let errors = AtomicUsize::new(0);
+    let error_message_limit = error_message_limit.unwrap_or(DEFAULT_ERROR_MESSAGE_LIMIT);
+    let error_messages = Mutex::new(vec![]);
+    let write_errors = AtomicUsize::new(0);
+
+    let thread_data = thread::scope(|scope| {
+        let missing = &missing;
+        let next = &next;
+        let errors = &errors;
+        let error_messages = &error_messages;
+        let write_errors = &write_errors;
+
+        // we collect to spawn all the threads
+        #[allow(clippy::needless_collect)]
+        let handles = (0..thread_count).map(|_| {
+            thread::Builder::new().name("seedgen".to_string()).spawn_scoped(scope, move || {
+                let mut settings = settings.clone();
+                // Each thread analyzes its seeds as soon as they are generated and the results get merged at the end
+                let mut data = empty_data(analyzers);
+
+                loop {
+                    let position = next.fetch_add(1, Ordering::Relaxed);
+                    let key = match missing.get(position) {
+                        Some(key) => *key,
+                        None => break,
+                    };
+                    eprint!("Generating seed {}/{}\r", position + 1, missing.len());
+
+                    let mut attempt = 0;
+                    let seed = loop {
+                        settings.seed = sample_seed(key, attempt);
+
+                        match wotw_seedgen::generate_seed(
+                            graph,
+                            &FILE_SYSTEM_ACCESS,
+                            &settings,
+                        ) {
+                            Ok(seed) => break seed.spoiler,
+                            Err(err) => {
+                                let mut error_messages_lock = error_messages.lock().expect(ANOTHER_THREAD_PANICKED);
+
+                                if error_messages_lock.len() < error_message_limit {
+                                    error_messages_lock.push(err.to_string());
+                                }
+                                let errors = errors.fetch_add(1, Ordering::Relaxed) + 1;
+                                if errors > tolerated_errors {
+                                    let more = errors - error_messages_lock.len();
+                                    let mut error_message = format!(
+                                        "Too many errors while generating seeds\nSample of some errors:\n{}",
+                                        error_messages_lock.join("\n")
+                                    );
+                                    if more > 0 {
+                                        write!(error_message, "\n...{more} more").unwrap();
                                     }
+                                    return Err(error_message);
                                 }
                             }
-                        };
-
-                        if let Err(err) =
-                            F::write_seed(&seed, &settings, existing_amount + count)
-                        {
-                            let write_errors = write_errors.fetch_add(1, Ordering::Relaxed);
-                            if write_errors < 10 {
-                                eprintln!("{err}");
-                            }
-                        };
-
-                        analyze_seed(&seed, analyzers, &mut data.lock().expect(ANOTHER_THREAD_PANICKED));
-                    }
-
-                    Ok(())
-                }).expect("failed to create thread")
-            }).collect::<Vec<_>>();
+                        }
+                        attempt += 1;
+                    };
+
+                    if let Err(err) = F::write_seed(&seed, &settings, key) {
+                        let write_errors = write_errors.fetch_add(1, Ordering::Relaxed);
+                        if write_errors < 10 {
+                            eprintln!("{err}");
+                        }
+                    };
+
+                    analyze_seed(&seed, analyzers, world_scope, &mut data);
+                }
+
+                Ok(data)
+            }).expect("failed to create thread")
+        }).collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("A seedgen thread panicked"))
+            .collect::<Result<Vec<_>>>()
+    })?;
+
+    for thread_data in thread_data {
+        for (data, thread_data) in data.iter_mut().zip(thread_data) {
+            for (key, count) in thread_data {
+                *data.entry(key).or_default() += count;
+            }
+        }
+    }
 
-            handles
-                .into_iter()
-                .try_for_each(|handle| handle.join().expect("A seedgen thread panicked"))
-        })?;
+    print_feedback_for_generated_seeds(missing.len(), sample_size);
 
-        print_feedback_for_generated_seeds(missing, sample_size);
+    Ok(data)
+}
 
-        Ok(data.into_inner().expect("data was poisoned"))
+/// The seed used to generate the sample with this `key`
+///
+/// Since the seed only depends on the sample, the same samples get generated no matter how many threads or runs it takes
+fn sample_seed(key: usize, attempt: usize) -> String {
+    if attempt == 0 {
+        key.to_string()
     } else {
-        Ok(data)
+        format!("{key}.{attempt}")
     }
 }
 
+fn empty_data(analyzers: &[ChainedAnalyzers]) -> Vec<SeedData> {
+    iter::repeat(SeedData::default())
+        .take(analyzers.len())
+        .collect()
+}
+
 fn analyze_existing_seeds<F: FileAccess>(
     analyzers: &[ChainedAnalyzers],
     settings: &UniverseSettings,
     sample_size: usize,
+    world_scope: WorldScope,
     data: &mut [SeedData],
-) -> Result<usize> {
+) -> Result<FxHashSet<usize>> {
     let mut existing = HandleErrors::new(F::read_seeds(settings, sample_size)?, |err| {
         eprintln!("{err}");
     });
-    let mut existing_amount = 0;
+    let mut existing_keys = FxHashSet::default();
 
-    for seed in existing.by_ref() {
-        existing_amount += 1;
-        analyze_seed(&seed, analyzers, data);
+    for (key, seed) in existing.by_ref() {
+        // Every sample should only be counted once, even if a FileAccess implementation returns more than asked for
+        if key < sample_size && existing_keys.insert(key) {
+            analyze_seed(&seed, analyzers, world_scope, data);
+        }
     }
     print_feedback_for_unusable_seeds(existing.errors);
 
-    Ok(existing_amount)
+    Ok(existing_keys)
 }
-fn analyze_seed(seed: &SeedSpoiler, analyzers: &[ChainedAnalyzers], data: &mut [SeedData]) {
-    for (data, chained_analyzers) in data.iter_mut().zip(analyzers.iter()) {
-        chained_analyzers
-            .iter()
-            .map(|analyzer| analyzer.analyze(seed).into_iter().map(Arc::new))
-            .multi_cartesian_product()
-            .for_each(|key| *data.entry(key).or_default() += 1);
+fn analyze_seed(
+    seed: &SeedSpoiler,
+    analyzers: &[ChainedAnalyzers],
+    world_scope: WorldScope,
+    data: &mut [SeedData],
+) {
+    for world_index in world_scope.world_indices(seed.spawns.len()) {
+        let world_key =
+            (world_scope == WorldScope::PerWorld).then(|| Arc::new(world_index.to_string()));
+
+        for (data, chained_analyzers) in data.iter_mut().zip(analyzers.iter()) {
+            chained_analyzers
+                .iter()
+                .map(|analyzer| {
+                    analyzer
+                        .analyze(seed, world_index)
+                        .into_iter()
+                        .map(Arc::new)
+                })
+                .multi_cartesian_product()
+                .for_each(|key| {
+                    let key = world_key.iter().cloned().chain(key).collect();
+                    *data.entry(key).or_default() += 1;
+                });
+        }
     }
 }
 
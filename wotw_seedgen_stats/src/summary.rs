@@ -0,0 +1,339 @@
+use std::sync::Arc;
+
+use itertools::Itertools;
+use serde::Serialize;
+
+/// z-score for 95% confidence
+const Z: f64 = 1.96;
+
+/// A summary of [`Stats`](crate::Stats) with percentages, confidence intervals and distributions of numeric results
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsSummary {
+    pub title: String,
+    /// How many worlds were analyzed, or how many were analyzed for each world index if the results were kept apart per world
+    pub samples: usize,
+    /// One row for every combination of keys, in the same order as the csv
+    pub rows: Vec<SummaryRow>,
+    /// If the last [`Analyzer`](crate::analyzers::Analyzer) in the chain produces numeric keys, their distribution for each combination of the remaining keys
+    ///
+    /// Bucketed keys like "10-19" count as their midpoint
+    pub distributions: Vec<Distribution>,
+}
+impl StatsSummary {
+    /// Serialize the summary into json format
+    pub fn to_json(&self) -> String {
+        // This is safe because the summary is known to serialize successfully
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+/// How often one combination of keys occured
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SummaryRow {
+    pub keys: Vec<String>,
+    pub count: u32,
+    /// `count` relative to the amount of `samples`
+    ///
+    /// This exceeds 100% for keys that may occur multiple times in one world
+    pub percentage: f64,
+    /// `count` relative to the counts of all rows, in percent
+    pub share: f64,
+    /// 95% confidence interval of the `percentage` as Wilson score interval
+    ///
+    /// `None` if a key occured more often than there were samples, since it isn't a proportion then
+    pub confidence_interval: Option<ConfidenceInterval>,
+}
+
+/// The range a value lies in with 95% confidence
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ConfidenceInterval {
+    pub lower: f64,
+    pub upper: f64,
+}
+
+/// Distribution of the numeric keys that occured along with the same preceding keys
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Distribution {
+    /// The preceding keys, empty if the numeric [`Analyzer`](crate::analyzers::Analyzer) isn't chained to others
+    pub keys: Vec<String>,
+    pub count: u32,
+    pub mean: f64,
+    pub standard_deviation: f64,
+    /// 95% confidence interval of the `mean`
+    pub mean_confidence_interval: ConfidenceInterval,
+    pub min: f64,
+    pub p10: f64,
+    pub p25: f64,
+    pub median: f64,
+    pub p75: f64,
+    pub p90: f64,
+    pub max: f64,
+}
+
+pub(crate) fn summarize(
+    title: String,
+    samples: usize,
+    data: &[(&Vec<Arc<String>>, &u32)],
+) -> StatsSummary {
+    let total = data.iter().map(|(_, count)| **count as f64).sum::<f64>();
+    let rows = data
+        .iter()
+        .map(|(keys, count)| SummaryRow {
+            keys: keys.iter().map(|key| key.to_string()).collect(),
+            count: **count,
+            percentage: percentage(**count as f64, samples as f64),
+            share: percentage(**count as f64, total),
+            confidence_interval: wilson_interval(**count as usize, samples),
+        })
+        .collect();
+
+    StatsSummary {
+        title,
+        samples,
+        rows,
+        distributions: distributions(data).unwrap_or_default(),
+    }
+}
+
+/// Returns `None` if the last keys aren't all numeric
+fn distributions(data: &[(&Vec<Arc<String>>, &u32)]) -> Option<Vec<Distribution>> {
+    let values = data
+        .iter()
+        .map(|(keys, count)| {
+            let (last, preceding) = keys.split_last()?;
+            Some((preceding, parse_numeric(last)?, **count))
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    // The data is sorted by keys, so equal preceding keys are next to each other
+    let distributions = values
+        .into_iter()
+        .group_by(|(preceding, _, _)| *preceding)
+        .into_iter()
+        .map(|(preceding, values)| {
+            let values = values
+                .map(|(_, value, count)| (value, count))
+                .collect::<Vec<_>>();
+            distribution(preceding, values)
+        })
+        .collect::<Vec<_>>();
+
+    Some(distributions)
+}
+fn parse_numeric(key: &str) -> Option<f64> {
+    match key.split_once('-') {
+        Some((floor, ceiling)) if !floor.is_empty() => {
+            Some((floor.parse::<f64>().ok()? + ceiling.parse::<f64>().ok()?) / 2.)
+        }
+        _ => key.parse().ok(),
+    }
+}
+fn distribution(preceding: &[Arc<String>], mut values: Vec<(f64, u32)>) -> Distribution {
+    values.sort_unstable_by(|(a, _), (b, _)| a.total_cmp(b));
+
+    let count = values.iter().map(|(_, count)| *count).sum::<u32>();
+    let n = count as f64;
+    let mean = values
+        .iter()
+        .map(|(value, count)| value * *count as f64)
+        .sum::<f64>()
+        / n;
+    let variance = if count > 1 {
+        values
+            .iter()
+            .map(|(value, count)| (value - mean).powi(2) * *count as f64)
+            .sum::<f64>()
+            / (n - 1.)
+    } else {
+        0.
+    };
+    let standard_deviation = variance.sqrt();
+    let margin = Z * standard_deviation / n.sqrt();
+
+    // Nearest-rank percentiles
+    let percentile = |percent: f64| {
+        let rank = ((percent / 100. * n).ceil() as u32).max(1);
+        let mut cumulative = 0;
+        values
+            .iter()
+            .find(|(_, count)| {
+                cumulative += count;
+                cumulative >= rank
+            })
+            .map_or(f64::NAN, |(value, _)| *value)
+    };
+
+    Distribution {
+        keys: preceding.iter().map(|key| key.to_string()).collect(),
+        count,
+        mean,
+        standard_deviation,
+        mean_confidence_interval: ConfidenceInterval {
+            lower: mean - margin,
+            upper: mean + margin,
+        },
+        min: values.first().map_or(f64::NAN, |(value, _)| *value),
+        p10: percentile(10.),
+        p25: percentile(25.),
+        median: percentile(50.),
+        p75: percentile(75.),
+        p90: percentile(90.),
+        max: values.last().map_or(f64::NAN, |(value, _)| *value),
+    }
+}
+
+pub(crate) fn percentage(count: f64, samples: f64) -> f64 {
+    if samples == 0. {
+        0.
+    } else {
+        count / samples * 100.
+    }
+}
+fn wilson_interval(count: usize, samples: usize) -> Option<ConfidenceInterval> {
+    if samples == 0 || count > samples {
+        return None;
+    }
+
+    let n = samples as f64;
+    let p = count as f64 / n;
+    let z2 = Z * Z;
+    let denominator = 1. + z2 / n;
+    let center = (p + z2 / (2. * n)) / denominator;
+    let margin = Z * (p * (1. - p) / n + z2 / (4. * n * n)).sqrt() / denominator;
+
+    Some(ConfidenceInterval {
+        lower: percentage(center - margin, 1.).max(0.),
+        upper: percentage(center + margin, 1.).min(100.),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-3,
+            "expected {expected}, got {actual}"
+        );
+    }
+    fn keys(keys: &[&str]) -> Vec<Arc<String>> {
+        keys.iter().map(|key| Arc::new(key.to_string())).collect()
+    }
+
+    #[test]
+    fn wilson_bounds() {
+        // Reference values from the Wilson score interval with z = 1.96
+        let interval = wilson_interval(8, 10).unwrap();
+        assert_close(interval.lower, 49.016);
+        assert_close(interval.upper, 94.332);
+        let interval = wilson_interval(50, 100).unwrap();
+        assert_close(interval.lower, 40.383);
+        assert_close(interval.upper, 59.617);
+
+        let interval = wilson_interval(0, 10).unwrap();
+        assert_eq!(interval.lower, 0.);
+        assert_close(interval.upper, 27.754);
+        let interval = wilson_interval(10, 10).unwrap();
+        assert_close(interval.lower, 72.246);
+        assert_eq!(interval.upper, 100.);
+
+        assert!(wilson_interval(11, 10).is_none());
+        assert!(wilson_interval(0, 0).is_none());
+    }
+
+    #[test]
+    fn count_exceeding_samples() {
+        let [a, b] = [keys(&["a"]), keys(&["b"])];
+        let summary = summarize("test".to_string(), 10, &[(&a, &15), (&b, &5)]);
+
+        assert_eq!(summary.rows[0].percentage, 150.);
+        assert_eq!(summary.rows[0].share, 75.);
+        assert!(summary.rows[0].confidence_interval.is_none());
+        assert_eq!(summary.rows[1].percentage, 50.);
+        assert_eq!(summary.rows[1].share, 25.);
+        assert!(summary.rows[1].confidence_interval.is_some());
+        assert!(summary.distributions.is_empty());
+    }
+
+    #[test]
+    fn numeric_keys() {
+        assert_eq!(parse_numeric("7"), Some(7.));
+        assert_eq!(parse_numeric("2.5"), Some(2.5));
+        assert_eq!(parse_numeric("-3"), Some(-3.));
+        assert_eq!(parse_numeric("10-19"), Some(14.5));
+        assert_eq!(parse_numeric("0-0"), Some(0.));
+        assert_eq!(parse_numeric("Marsh"), None);
+        assert_eq!(parse_numeric("10-"), None);
+        assert_eq!(parse_numeric("a-b"), None);
+    }
+
+    #[test]
+    fn nearest_rank_percentiles() {
+        let values = (1..=10).map(|value| (value as f64, 1)).collect();
+        let result = distribution(&[], values);
+
+        assert_eq!(result.count, 10);
+        assert_close(result.mean, 5.5);
+        assert_close(result.standard_deviation, 3.02765);
+        assert_close(
+            result.mean_confidence_interval.lower,
+            5.5 - 1.96 * 3.02765 / 10f64.sqrt(),
+        );
+        assert_close(
+            result.mean_confidence_interval.upper,
+            5.5 + 1.96 * 3.02765 / 10f64.sqrt(),
+        );
+        assert_eq!(result.min, 1.);
+        assert_eq!(result.p10, 1.);
+        assert_eq!(result.p25, 3.);
+        assert_eq!(result.median, 5.);
+        assert_eq!(result.p75, 8.);
+        assert_eq!(result.p90, 9.);
+        assert_eq!(result.max, 10.);
+
+        // Counts weigh the values
+        let result = distribution(&[], vec![(4., 1), (1., 3)]);
+        assert_eq!(result.count, 4);
+        assert_close(result.mean, 1.75);
+        assert_eq!(result.p25, 1.);
+        assert_eq!(result.median, 1.);
+        assert_eq!(result.p75, 1.);
+        assert_eq!(result.p90, 4.);
+
+        let result = distribution(&[], vec![(3., 1)]);
+        assert_eq!(result.standard_deviation, 0.);
+        assert_eq!(result.median, 3.);
+    }
+
+    #[test]
+    fn bucket_distributions() {
+        let rows = [
+            keys(&["Marsh", "0-9"]),
+            keys(&["Marsh", "10-19"]),
+            keys(&["Wellspring", "20-29"]),
+        ];
+        let summary = summarize(
+            "test".to_string(),
+            4,
+            &[(&rows[0], &1), (&rows[1], &3), (&rows[2], &2)],
+        );
+
+        assert_eq!(summary.distributions.len(), 2);
+        let marsh = &summary.distributions[0];
+        assert_eq!(marsh.keys, ["Marsh"]);
+        assert_eq!(marsh.count, 4);
+        assert_close(marsh.mean, (4.5 + 14.5 * 3.) / 4.);
+        assert_eq!(marsh.min, 4.5);
+        assert_eq!(marsh.max, 14.5);
+        let wellspring = &summary.distributions[1];
+        assert_eq!(wellspring.keys, ["Wellspring"]);
+        assert_eq!(wellspring.median, 24.5);
+
+        let mixed = [keys(&["1"]), keys(&["Marsh"])];
+        assert!(distributions(&[(&mixed[0], &1), (&mixed[1], &1)]).is_none());
+    }
+}